@@ -16,7 +16,9 @@ use crypto::ed25519::*;
 use network::validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender};
 
 use crate::chained_bft::{
-    chained_bft_smr::ChainedBftSMRConfig, common::Author, persistent_storage::StorageWriteProxy,
+    chained_bft_smr::ChainedBftSMRConfig,
+    common::Author,
+    persistent_storage::{DefaultLivenessCodec, StorageWriteProxy},
 };
 use config::config::{ConsensusProposerType::FixedProposer, NodeConfig};
 use execution_proto::proto::execution_grpc::ExecutionClient;
@@ -79,7 +81,7 @@ impl ChainedBftProvider {
         debug!("[Consensus] My peer: {:?}", initial_setup.author);
         debug!("[Consensus] Chosen proposer: {:?}", proposer);
         let config = ChainedBftSMRConfig::from_node_config(&node_config.consensus);
-        let (storage, initial_data) = StorageWriteProxy::start(node_config);
+        let (storage, initial_data) = StorageWriteProxy::<DefaultLivenessCodec>::start(node_config);
         info!(
             "Starting up the consensus state machine with recovery data - {:?}, {:?}",
             initial_data.state(),
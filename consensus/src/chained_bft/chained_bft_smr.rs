@@ -7,7 +7,7 @@ use crate::{
         common::{Payload, Round},
         event_processor::EventProcessor,
         liveness::{
-            pacemaker::{ExponentialTimeInterval, NewRoundEvent, Pacemaker},
+            pacemaker::{ExponentialTimeInterval, NewRoundEvent, Pacemaker, PacemakerBuilder},
             pacemaker_timeout_manager::HighestTimeoutCertificates,
             proposal_generator::ProposalGenerator,
             proposer_election::ProposerElection,
@@ -128,17 +128,26 @@ impl<T: Payload> ChainedBftSMR<T> {
             1.5,
             6,
         ));
-        Pacemaker::new(
+        let mut pacemaker = PacemakerBuilder::new(
             persistent_liveness_storage,
             time_interval,
-            highest_committed_round,
-            highest_certified_round,
             time_service,
             new_round_events_sender,
             timeout_sender,
             self.quorum_size,
-            highest_timeout_certificates,
         )
+        .highest_committed_round(highest_committed_round)
+        .highest_qc_round(highest_certified_round)
+        .highest_timeout_certificates(highest_timeout_certificates)
+        .try_build()
+        .expect("Invalid pacemaker time interval configuration");
+        pacemaker.set_local_author(self.author);
+        let proposer_election: Box<dyn ProposerElection<T> + Send + Sync> = Box::new(
+            RotatingProposer::new(self.proposers.clone(), self.config.contiguous_rounds),
+        );
+        pacemaker.set_proposer_election(proposer_election);
+        block_on(pacemaker.start());
+        pacemaker
     }
 
     /// Create a proposer election handler based on proposers
@@ -29,12 +29,14 @@ use types::{
 // Internal use only. Contains all the fields in PaceMakerTimeout that contributes to the
 // computation of its hash.
 struct PacemakerTimeoutSerializer {
+    epoch: u64,
     round: Round,
     author: Author,
 }
 
 impl CanonicalSerialize for PacemakerTimeoutSerializer {
     fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> failure::Result<()> {
+        serializer.encode_u64(self.epoch)?;
         serializer.encode_u64(self.round)?;
         serializer.encode_struct(&self.author)?;
         Ok(())
@@ -60,47 +62,83 @@ pub enum TimeoutVerificationError {
     /// The signature doesn't pass verification
     #[fail(display = "SigVerifyError: {}", _0)]
     SigVerifyError(VerifyError),
+    /// The timeout certificate carried by the timeout doesn't pass verification.
+    #[fail(display = "TimeoutCertificateError: {}", _0)]
+    TimeoutCertificateError(PacemakerTimeoutCertificateVerificationError),
 }
 
 /// This message will be broadcast by a pacemaker as part of TimeoutMsg when its local
 /// timeout for a round is reached.  Once f+1 PacemakerTimeout structs
 /// from unique authors is gathered it forms a TimeoutCertificate.  A TimeoutCertificate is
 /// a proof that will cause a replica to advance to the minimum round in the TimeoutCertificate.
+///
+/// The signature covers the epoch of the timeout along with its round, such that a timeout of a
+/// previous epoch cannot be replayed into the current one.
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct PacemakerTimeout {
+    epoch: u64,
     round: Round,
     author: Author,
     signature: Ed25519Signature,
     vote: Option<VoteMsg>,
+    // The timeout certificate is self-authenticating, hence not covered by the signature
+    highest_timeout_certificate: Option<PacemakerTimeoutCertificate>,
 }
 
 impl PacemakerTimeout {
     /// Creates new PacemakerTimeout
     pub fn new(
+        epoch: u64,
         round: Round,
         validator_signer: &ValidatorSigner<Ed25519PrivateKey>,
         vote: Option<VoteMsg>,
+    ) -> Self {
+        Self::new_with_highest_timeout_certificate(epoch, round, validator_signer, vote, None)
+    }
+
+    /// Creates new PacemakerTimeout attaching the highest timeout certificate known to the
+    /// author, which lets the receivers advance on the timeout evidence alone.  The timeouts of
+    /// the attached certificate don't carry certificates themselves to avoid nesting them.
+    pub fn new_with_highest_timeout_certificate(
+        epoch: u64,
+        round: Round,
+        validator_signer: &ValidatorSigner<Ed25519PrivateKey>,
+        vote: Option<VoteMsg>,
+        highest_timeout_certificate: Option<PacemakerTimeoutCertificate>,
     ) -> Self {
         let author = validator_signer.author();
-        let digest = PacemakerTimeoutSerializer { round, author }.hash();
+        let digest = Self::pacemaker_timeout_digest(author, epoch, round);
         let signature = validator_signer
             .sign_message(digest)
             .expect("Failed to sign PacemakerTimeout");
         PacemakerTimeout {
+            epoch,
             round,
             author,
             signature,
             vote,
+            highest_timeout_certificate: highest_timeout_certificate
+                .map(PacemakerTimeoutCertificate::without_attached_certificates),
         }
     }
 
-    fn pacemaker_timeout_digest(author: AccountAddress, round: Round) -> HashValue {
-        PacemakerTimeoutSerializer { round, author }.hash()
+    fn pacemaker_timeout_digest(author: AccountAddress, epoch: u64, round: Round) -> HashValue {
+        PacemakerTimeoutSerializer {
+            epoch,
+            round,
+            author,
+        }
+        .hash()
     }
 
     /// Calculates digest for this struct
     pub fn digest(&self) -> HashValue {
-        Self::pacemaker_timeout_digest(self.author, self.round)
+        Self::pacemaker_timeout_digest(self.author, self.epoch, self.round)
+    }
+
+    /// Returns the epoch the timeout was signed for
+    pub fn epoch(&self) -> u64 {
+        self.epoch
     }
 
     pub fn round(&self) -> Round {
@@ -111,6 +149,11 @@ impl PacemakerTimeout {
         self.vote.as_ref()
     }
 
+    /// Returns the highest timeout certificate attached by the author
+    pub fn highest_timeout_certificate(&self) -> Option<&PacemakerTimeoutCertificate> {
+        self.highest_timeout_certificate.as_ref()
+    }
+
     /// Verifies that this message has valid signature
     pub fn verify(
         &self,
@@ -123,6 +166,10 @@ impl PacemakerTimeout {
             vote.verify(validator)
                 .map_err(TimeoutVerificationError::VoteMsgError)?;
         }
+        if let Some(tc) = self.highest_timeout_certificate.as_ref() {
+            tc.verify(validator)
+                .map_err(TimeoutVerificationError::TimeoutCertificateError)?;
+        }
         Ok(())
     }
 
@@ -142,12 +189,16 @@ impl IntoProto for PacemakerTimeout {
 
     fn into_proto(self) -> Self::ProtoType {
         let mut proto = Self::ProtoType::new();
+        proto.set_epoch(self.epoch);
         proto.set_round(self.round);
         proto.set_author(self.author.into());
         proto.set_signature(self.signature.to_bytes().as_ref().into());
         if let Some(vote) = self.vote {
             proto.set_vote(vote.into_proto());
         }
+        if let Some(tc) = self.highest_timeout_certificate {
+            proto.set_highest_timeout_certificate(tc.into_proto());
+        }
         proto
     }
 }
@@ -156,6 +207,7 @@ impl FromProto for PacemakerTimeout {
     type ProtoType = network::proto::PacemakerTimeout;
 
     fn from_proto(mut object: Self::ProtoType) -> failure::Result<Self> {
+        let epoch = object.get_epoch();
         let round = object.get_round();
         let author = Author::try_from(object.take_author())?;
         let signature = Ed25519Signature::try_from(object.get_signature())?;
@@ -164,11 +216,19 @@ impl FromProto for PacemakerTimeout {
         } else {
             None
         };
+        let highest_timeout_certificate =
+            if let Some(tc) = object.highest_timeout_certificate.into_option() {
+                Some(PacemakerTimeoutCertificate::from_proto(tc)?)
+            } else {
+                None
+            };
         Ok(PacemakerTimeout {
+            epoch,
             round,
             author,
             signature,
             vote,
+            highest_timeout_certificate,
         })
     }
 }
@@ -314,6 +374,9 @@ pub enum PacemakerTimeoutCertificateVerificationError {
     /// The signature on one of timeouts doesn't pass verification
     #[fail(display = "SigVerifyError for {}: {}", _0, _1)]
     SigVerifyError(Author, VerifyError),
+    /// The timeouts are not all of the same epoch
+    #[fail(display = "EpochMismatch {} {}", _0, _1)]
+    EpochMismatch(u64, u64),
 }
 
 impl fmt::Display for PacemakerTimeoutCertificate {
@@ -343,6 +406,11 @@ impl PacemakerTimeoutCertificate {
         let mut min_round: Option<Round> = None;
         let mut unique_authors = HashSet::new();
         for timeout in &self.timeouts {
+            // The timeouts of a certificate all belong to the epoch of the first one
+            let epoch = self.timeouts[0].epoch();
+            if timeout.epoch() != epoch {
+                return Err(EpochMismatch(epoch, timeout.epoch()));
+            }
             if let Err(e) =
                 validator.verify_signature(timeout.author(), timeout.digest(), timeout.signature())
             {
@@ -378,6 +446,14 @@ impl PacemakerTimeoutCertificate {
     pub fn timeouts(&self) -> &Vec<PacemakerTimeout> {
         &self.timeouts
     }
+
+    /// Drops the timeout certificates attached to the timeouts of this certificate
+    fn without_attached_certificates(mut self) -> Self {
+        for timeout in &mut self.timeouts {
+            timeout.highest_timeout_certificate = None;
+        }
+        self
+    }
 }
 
 impl IntoProto for PacemakerTimeoutCertificate {
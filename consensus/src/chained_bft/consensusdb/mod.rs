@@ -25,6 +25,8 @@ use std::{collections::HashMap, iter::Iterator, path::Path, time::Instant};
 
 type HighestTimeoutCertificates = Vec<u8>;
 type ConsensusStateData = Vec<u8>;
+type RoundDetailsData = Vec<u8>;
+type ElectionStateData = Vec<u8>;
 
 pub struct ConsensusDB {
     db: DB,
@@ -102,6 +104,29 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    pub fn save_round_details(&self, round_details: RoundDetailsData) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        batch.put::<SingleEntrySchema>(&SingleEntryKey::RoundDetails, &round_details)?;
+        self.commit(batch)
+    }
+
+    /// Get the details of the last sampled round (we only store the latest sampled round).
+    pub fn get_round_details(&self) -> Result<Option<RoundDetailsData>> {
+        self.db
+            .get::<SingleEntrySchema>(&SingleEntryKey::RoundDetails)
+    }
+
+    pub fn save_election_state(&self, election_state: ElectionStateData) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        batch.put::<SingleEntrySchema>(&SingleEntryKey::ElectionState, &election_state)?;
+        self.commit(batch)
+    }
+
+    pub fn get_election_state(&self) -> Result<Option<ElectionStateData>> {
+        self.db
+            .get::<SingleEntrySchema>(&SingleEntryKey::ElectionState)
+    }
+
     pub fn save_state(&self, state: ConsensusStateData) -> Result<()> {
         let mut batch = SchemaBatch::new();
         batch.put::<SingleEntrySchema>(&SingleEntryKey::ConsensusState, &state)?;
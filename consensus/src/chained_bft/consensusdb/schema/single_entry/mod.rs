@@ -35,6 +35,10 @@ pub enum SingleEntryKey {
     ConsensusState = 0,
     // Used to store the highest timeout certificates
     HighestTimeoutCertificates = 1,
+    // Used to store the details of the last sampled round of the pacemaker
+    RoundDetails = 3,
+    // Used to store the state of the proposer election
+    ElectionState = 4,
 }
 
 impl KeyCodec<SingleEntrySchema> for SingleEntryKey {
@@ -155,7 +155,7 @@ impl<T: Payload> EventProcessor<T> {
         let mut network = self.network.clone();
         debug!("Propose {}", proposal);
         let timeout_certificate = match &new_round_event.reason {
-            NewRoundReason::Timeout { cert }
+            NewRoundReason::Timeout { cert, .. }
                 if cert.round() > proposal.quorum_cert().certified_block_round() =>
             {
                 Some(cert.clone())
@@ -425,7 +425,13 @@ impl<T: Payload> EventProcessor<T> {
                     self.block_store.highest_ledger_info().as_ref().clone(),
                     self.pacemaker.highest_timeout_certificate(),
                 ),
-                PacemakerTimeout::new(round, self.block_store.signer(), vote_msg_to_attach),
+                PacemakerTimeout::new_with_highest_timeout_certificate(
+                    self.pacemaker.epoch(),
+                    round,
+                    self.block_store.signer(),
+                    vote_msg_to_attach,
+                    self.pacemaker.timeout_certificate_to_attach(),
+                ),
                 self.block_store.signer(),
             ))
             .await;
@@ -455,11 +461,7 @@ impl<T: Payload> EventProcessor<T> {
             }
         }
         self.pacemaker
-            .process_certificates(
-                qc.certified_block_round(),
-                highest_committed_proposal_round,
-                tc,
-            )
+            .process_quorum_cert(qc, highest_committed_proposal_round, tc)
             .await;
     }
 
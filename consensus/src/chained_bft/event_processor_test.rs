@@ -14,8 +14,9 @@ use crate::{
         },
         event_processor::EventProcessor,
         liveness::{
-            pacemaker::{ExponentialTimeInterval, NewRoundEvent, NewRoundReason, Pacemaker},
-            pacemaker_timeout_manager::HighestTimeoutCertificates,
+            pacemaker::{
+                ExponentialTimeInterval, NewRoundEvent, NewRoundReason, Pacemaker, PacemakerBuilder,
+            },
             proposal_generator::ProposalGenerator,
             proposer_election::ProposerElection,
             rotating_proposer_election::RotatingProposer,
@@ -95,22 +96,20 @@ impl NodeSetup {
         let highest_certified_round = 0;
         let (new_round_events_sender, new_round_events_receiver) = channel::new_test(1_024);
         let (pacemaker_timeout_sender, _) = channel::new_test(1_024);
-        (
-            Pacemaker::new(
-                MockStorage::<TestPayload>::start_for_testing()
-                    .0
-                    .persistent_liveness_storage(),
-                time_interval,
-                0,
-                highest_certified_round,
-                time_service,
-                new_round_events_sender,
-                pacemaker_timeout_sender,
-                1,
-                HighestTimeoutCertificates::new(None, None),
-            ),
-            new_round_events_receiver,
+        let mut pacemaker = PacemakerBuilder::new(
+            MockStorage::<TestPayload>::start_for_testing()
+                .0
+                .persistent_liveness_storage(),
+            time_interval,
+            time_service,
+            new_round_events_sender,
+            pacemaker_timeout_sender,
+            1,
         )
+        .highest_qc_round(highest_certified_round)
+        .build();
+        block_on(pacemaker.start());
+        (pacemaker, new_round_events_receiver)
     }
 
     fn create_proposer_election(
@@ -253,6 +252,9 @@ fn basic_new_rank_event_test() {
                 round: new_round,
                 reason: NewRoundReason::QCReady,
                 timeout: Duration::new(5, 0),
+                sequence: 0,
+                proposal_deadline: Duration::new(5, 0),
+                skipped: 0,
             })
             .await;
         let pending_messages = playground
@@ -294,6 +296,9 @@ fn basic_new_rank_event_test() {
                 round: 2,
                 reason: NewRoundReason::QCReady,
                 timeout: Duration::new(5, 0),
+                sequence: 1,
+                proposal_deadline: Duration::new(5, 0),
+                skipped: 0,
             })
             .await;
         let pending_messages = playground
@@ -528,7 +533,7 @@ fn process_new_round_msg_test() {
                 QuorumCert::certificate_for_genesis(),
                 None,
             ),
-            PacemakerTimeout::new(2, &non_proposer.signer, None),
+            PacemakerTimeout::new(0, 2, &non_proposer.signer, None),
             &non_proposer.signer,
         ),
         2,
@@ -624,7 +629,7 @@ fn process_timeout_certificate_test() {
         node.block_store.signer(),
     );
     let tc =
-        PacemakerTimeoutCertificate::new(1, vec![PacemakerTimeout::new(1, &node.signer, None)]);
+        PacemakerTimeoutCertificate::new(1, vec![PacemakerTimeout::new(0, 1, &node.signer, None)]);
     block_on(async move {
         let skip_round_proposal = ProposalMsg::<TestPayload> {
             proposal: block_skip_round,
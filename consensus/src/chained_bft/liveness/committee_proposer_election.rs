@@ -0,0 +1,87 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::{Author, Payload, Round},
+    consensus_types::block::Block,
+    liveness::{
+        proposer_election::{compute_validator_set_root, ProposerElection},
+        rotating_proposer_election::RotatingProposer,
+    },
+};
+use crypto::HashValue;
+
+/// The committee proposer only elects the proposers from a proposing committee, a subset of the
+/// validators, which it rotates through like the rotating proposer.  All the validators still
+/// vote: the committee does not affect the quorums.
+///
+/// An empty proposing committee halts proposing: no proposer is elected for any round.
+pub struct CommitteeProposerElection {
+    // Rotation through the proposing committee, None if the committee is empty
+    committee_proposer: Option<RotatingProposer>,
+    // Root hash of the validators, with a weight of 1 for the members of the proposing committee
+    // and 0 for the other validators
+    validator_set_root: HashValue,
+}
+
+impl CommitteeProposerElection {
+    /// The proposing committee rotates in the order of the validators, its members that are not
+    /// validators are ignored.
+    pub fn new(
+        validators: Vec<Author>,
+        proposer_committee: Vec<Author>,
+        contiguous_rounds: u32,
+    ) -> Self {
+        let weighted_validators: Vec<(Author, u64)> = validators
+            .iter()
+            .map(|author| (*author, proposer_committee.contains(author) as u64))
+            .collect();
+        let proposers: Vec<Author> = validators
+            .into_iter()
+            .filter(|author| proposer_committee.contains(author))
+            .collect();
+        let committee_proposer = if proposers.is_empty() {
+            None
+        } else {
+            Some(RotatingProposer::new(proposers, contiguous_rounds))
+        };
+        Self {
+            committee_proposer,
+            validator_set_root: compute_validator_set_root(&weighted_validators),
+        }
+    }
+}
+
+impl<T: Payload> ProposerElection<T> for CommitteeProposerElection {
+    fn is_valid_proposer(&self, author: Author, round: Round) -> Option<Author> {
+        let committee_proposer = self.committee_proposer.as_ref()?;
+        ProposerElection::<T>::is_valid_proposer(committee_proposer, author, round)
+    }
+
+    fn get_valid_proposers(&self, round: Round) -> Vec<Author> {
+        match self.committee_proposer.as_ref() {
+            Some(committee_proposer) => {
+                ProposerElection::<T>::get_valid_proposers(committee_proposer, round)
+            }
+            None => vec![],
+        }
+    }
+
+    fn process_proposal(&self, proposal: Block<T>) -> Option<Block<T>> {
+        self.committee_proposer.as_ref()?.process_proposal(proposal)
+    }
+
+    fn validator_set_root(&self) -> HashValue {
+        self.validator_set_root
+    }
+
+    fn next_change_round(&self, from_round: Round, author: Author) -> Option<Round> {
+        match self.committee_proposer.as_ref() {
+            Some(committee_proposer) => {
+                ProposerElection::<T>::next_change_round(committee_proposer, from_round, author)
+            }
+            // The author is not the proposer of any round
+            None => Some(from_round),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::Author,
+    liveness::{
+        committee_proposer_election::CommitteeProposerElection,
+        proposer_election::{ElectionError, ProposerElection},
+    },
+};
+use crypto::ed25519::*;
+use std::collections::HashSet;
+use types::validator_signer::ValidatorSigner;
+
+fn make_authors(num_authors: u8) -> Vec<Author> {
+    (0..num_authors)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]).author())
+        .collect()
+}
+
+#[test]
+fn test_committee_proposers() {
+    let authors = make_authors(7);
+    let committee = vec![authors[5], authors[1], authors[3]];
+    let pe: &dyn ProposerElection<u32> =
+        &CommitteeProposerElection::new(authors.clone(), committee.clone(), 2);
+
+    let mut elected = HashSet::new();
+    for round in 0..1_000 {
+        let proposer = pe.get_valid_proposer_checked(round).unwrap();
+        assert!(
+            committee.contains(&proposer),
+            "Round {} elects {}",
+            round,
+            proposer
+        );
+        elected.insert(proposer);
+        for author in &authors {
+            assert_eq!(
+                pe.is_valid_proposer(*author, round).is_some(),
+                *author == proposer
+            );
+        }
+    }
+    // The whole committee proposes, in the order of the validators
+    assert_eq!(elected.len(), committee.len());
+    assert_eq!(pe.proposer_history(0, 6).len(), 6);
+    assert_eq!(pe.get_valid_proposers(0), vec![authors[1]]);
+    assert_eq!(pe.get_valid_proposers(2), vec![authors[3]]);
+    assert_eq!(pe.get_valid_proposers(4), vec![authors[5]]);
+
+    // The validators that only vote are part of the validator set root
+    let other_pe: &dyn ProposerElection<u32> =
+        &CommitteeProposerElection::new(authors[..6].to_vec(), committee, 2);
+    assert_ne!(pe.validator_set_root(), other_pe.validator_set_root());
+}
+
+#[test]
+fn test_empty_committee() {
+    let authors = make_authors(4);
+    let pe: &dyn ProposerElection<u32> =
+        &CommitteeProposerElection::new(authors.clone(), vec![], 1);
+    for round in 0..10 {
+        assert!(pe.get_valid_proposers(round).is_empty());
+        assert_eq!(
+            pe.get_valid_proposer_checked(round),
+            Err(ElectionError::NoProposer { round })
+        );
+        assert_eq!(pe.is_valid_proposer(authors[0], round), None);
+        assert_eq!(pe.next_change_round(round, authors[0]), Some(round));
+    }
+}
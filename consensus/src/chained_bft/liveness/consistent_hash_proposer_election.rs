@@ -0,0 +1,96 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::{Author, Payload, Round},
+    consensus_types::block::Block,
+    liveness::proposer_election::{compute_validator_set_root, ProposerElection},
+};
+use crypto::{
+    hash::{CryptoHasher, ProposerElectionHasher},
+    HashValue,
+};
+
+/// The consistent hash proposer maps a round to the author with the highest hash of the round and
+/// of the author (rendezvous hashing).  Unlike the rotating proposer, the draw of an author does
+/// not depend on the other authors: when a validator joins, only the rounds it wins change their
+/// proposer (about 1/n of them), and when a validator leaves, only the rounds it won do.
+///
+/// The ties between the hashes are broken by the address of the authors.
+pub struct ConsistentHashProposerElection {
+    // Proposers, ordered by address
+    proposers: Vec<Author>,
+    // Root hash of the proposers (all with the same weight)
+    validator_set_root: HashValue,
+}
+
+impl ConsistentHashProposerElection {
+    pub fn new(proposers: Vec<Author>) -> Self {
+        assert!(
+            !proposers.is_empty(),
+            "ConsistentHashProposerElection requires at least one proposer"
+        );
+        let mut proposers = proposers;
+        proposers.sort();
+        proposers.dedup();
+        let validators: Vec<(Author, u64)> = proposers.iter().map(|author| (*author, 1)).collect();
+        Self {
+            validator_set_root: compute_validator_set_root(&validators),
+            proposers,
+        }
+    }
+
+    /// Returns the hash of the author for the round, which only depends on the two of them.
+    fn score(round: Round, author: Author) -> HashValue {
+        let mut hasher = ProposerElectionHasher::default();
+        hasher.write(&round.to_le_bytes());
+        hasher.write(author.as_ref());
+        hasher.finish()
+    }
+
+    fn get_proposer(&self, round: Round) -> Author {
+        *self
+            .proposers
+            .iter()
+            .max_by_key(|author| (Self::score(round, **author), **author))
+            .expect("ConsistentHashProposerElection has at least one proposer")
+    }
+}
+
+impl<T: Payload> ProposerElection<T> for ConsistentHashProposerElection {
+    fn is_valid_proposer(&self, author: Author, round: Round) -> Option<Author> {
+        if self.get_proposer(round) == author {
+            Some(author)
+        } else {
+            None
+        }
+    }
+
+    fn get_valid_proposers(&self, round: Round) -> Vec<Author> {
+        vec![self.get_proposer(round)]
+    }
+
+    fn process_proposal(&self, proposal: Block<T>) -> Option<Block<T>> {
+        let round_author = self.get_proposer(proposal.round());
+        if Some(round_author) != proposal.author() {
+            None
+        } else {
+            Some(proposal)
+        }
+    }
+
+    fn validator_set_root(&self) -> HashValue {
+        self.validator_set_root
+    }
+
+    fn next_change_round(&self, from_round: Round, author: Author) -> Option<Round> {
+        if self.proposers.iter().all(|proposer| *proposer == author) {
+            return None;
+        }
+        let mut round = from_round;
+        while self.get_proposer(round) == author {
+            round = round.checked_add(1)?;
+        }
+        Some(round)
+    }
+}
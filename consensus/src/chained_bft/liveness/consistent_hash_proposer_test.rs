@@ -0,0 +1,70 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::Author,
+    liveness::{
+        consistent_hash_proposer_election::ConsistentHashProposerElection,
+        proposer_election::ProposerElection, rotating_proposer_election::RotatingProposer,
+    },
+    test_utils::TestPayload,
+};
+use crypto::ed25519::*;
+use types::validator_signer::ValidatorSigner;
+
+const ROUNDS: u64 = 100;
+
+fn make_authors(num_authors: u8) -> Vec<Author> {
+    (0..num_authors)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]).author())
+        .collect()
+}
+
+/// Returns the number of the rounds whose proposer differs between the two elections
+fn changed_rounds(
+    pe: &dyn ProposerElection<TestPayload>,
+    other_pe: &dyn ProposerElection<TestPayload>,
+) -> usize {
+    (0..ROUNDS)
+        .filter(|round| pe.get_valid_proposers(*round) != other_pe.get_valid_proposers(*round))
+        .count()
+}
+
+#[test]
+fn test_consistent_hash_proposer_churn() {
+    let authors = make_authors(10);
+    let pe: &dyn ProposerElection<TestPayload> =
+        &ConsistentHashProposerElection::new(authors[..9].to_vec());
+    let joined_pe: &dyn ProposerElection<TestPayload> =
+        &ConsistentHashProposerElection::new(authors.clone());
+
+    // Only the rounds won by the joining validator change their proposer, about 1/10 of them
+    let changed = changed_rounds(pe, joined_pe);
+    assert!(changed <= 25, "{} rounds changed their proposer", changed);
+    for round in 0..ROUNDS {
+        let proposers = joined_pe.get_valid_proposers(round);
+        if proposers != pe.get_valid_proposers(round) {
+            assert_eq!(proposers, vec![authors[9]]);
+        }
+    }
+    assert_ne!(pe.validator_set_root(), joined_pe.validator_set_root());
+
+    // The round-robin rotation reshuffles most of the rounds instead
+    let rotating_pe: &dyn ProposerElection<TestPayload> =
+        &RotatingProposer::new(authors[..9].to_vec(), 1);
+    let joined_rotating_pe: &dyn ProposerElection<TestPayload> =
+        &RotatingProposer::new(authors.clone(), 1);
+    assert!(changed_rounds(rotating_pe, joined_rotating_pe) > 50);
+}
+
+#[test]
+fn test_consistent_hash_proposer_order_independent() {
+    let authors = make_authors(5);
+    let mut reversed_authors = authors.clone();
+    reversed_authors.reverse();
+    let pe: &dyn ProposerElection<TestPayload> = &ConsistentHashProposerElection::new(authors);
+    let other_pe: &dyn ProposerElection<TestPayload> =
+        &ConsistentHashProposerElection::new(reversed_authors);
+    assert_eq!(pe.validator_set_root(), other_pe.validator_set_root());
+    assert_eq!(changed_rounds(pe, other_pe), 0);
+}
@@ -1,13 +1,51 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+// Alternative proposer election for a proposing committee among the validators.
+#[allow(dead_code)]
+pub(crate) mod committee_proposer_election;
+// Alternative proposer election with a minimal churn on validator set changes.
+#[allow(dead_code)]
+pub(crate) mod consistent_hash_proposer_election;
 pub(crate) mod pacemaker;
+pub(crate) mod pacemaker_config;
+pub(crate) mod pacemaker_decision_log;
+pub(crate) mod pacemaker_epoch;
+pub(crate) mod pacemaker_history;
+pub(crate) mod pacemaker_observers;
 pub(crate) mod pacemaker_timeout_manager;
+// Alternative proposer election excluding the validators below a liveness score.
+#[allow(dead_code)]
+pub(crate) mod performance_proposer_election;
 pub(crate) mod proposal_generator;
 pub(crate) mod proposer_election;
 pub(crate) mod rotating_proposer_election;
+// Alternative proposer election for fixed leader schedules (e.g., reproducible testnets).
+#[allow(dead_code)]
+pub(crate) mod scheduled_proposer_election;
+// Alternative proposer election skipping the failing proposers.
+#[allow(dead_code)]
+pub(crate) mod skipping_proposer_election;
+pub(crate) mod timeout_broadcaster;
+// Alternative proposer election, not used by the default node configuration.
+#[allow(dead_code)]
+pub(crate) mod weighted_proposer_election;
 
+#[cfg(test)]
+mod committee_proposer_test;
+#[cfg(test)]
+mod consistent_hash_proposer_test;
+#[cfg(test)]
+mod multi_pacemaker_simulator;
 #[cfg(test)]
 mod pacemaker_test;
 #[cfg(test)]
+mod performance_proposer_test;
+#[cfg(test)]
 mod rotating_proposer_test;
+#[cfg(test)]
+mod scheduled_proposer_test;
+#[cfg(test)]
+mod skipping_proposer_test;
+#[cfg(test)]
+mod weighted_proposer_test;
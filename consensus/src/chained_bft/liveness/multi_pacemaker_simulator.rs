@@ -0,0 +1,366 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        common::{Author, Round},
+        consensus_types::timeout_msg::PacemakerTimeout,
+        liveness::{
+            pacemaker::{ExponentialTimeInterval, NewRoundEvent, Pacemaker, PacemakerBuilder},
+            proposer_election::ProposerElection,
+        },
+        persistent_storage::PersistentStorage,
+        test_utils::{MockStorage, TestPayload},
+    },
+    util::time_service::{ScheduledTask, TimeService},
+};
+use channel;
+use crypto::ed25519::*;
+use futures::{
+    executor::block_on,
+    future::{self, Either},
+    Future, FutureExt, StreamExt,
+};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use types::validator_signer::ValidatorSigner;
+
+/// A clock shared by the simulated nodes: the scheduled tasks only run when the simulator
+/// advances the clock past their deadline, in the order of their deadlines (then of their
+/// scheduling).
+#[derive(Default)]
+struct SimulatorTimeService {
+    inner: Mutex<SimulatorTimeServiceInner>,
+}
+
+#[derive(Default)]
+struct SimulatorTimeServiceInner {
+    now: Duration,
+    // Scheduled tasks with their deadline and their scheduling sequence number
+    pending: Vec<(Duration, u64, Box<dyn ScheduledTask>)>,
+    next_sequence: u64,
+}
+
+impl SimulatorTimeService {
+    fn next_deadline(&self) -> Option<Duration> {
+        let inner = self.inner.lock().unwrap();
+        inner.pending.iter().map(|(deadline, _, _)| *deadline).min()
+    }
+
+    /// Moves the clock to the given time, running the tasks due until then.
+    fn advance_to(&self, time: Duration) {
+        loop {
+            let mut task = {
+                let mut inner = self.inner.lock().unwrap();
+                let next = inner
+                    .pending
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (deadline, _, _))| *deadline <= time)
+                    .min_by_key(|(_, (deadline, sequence, _))| (*deadline, *sequence))
+                    .map(|(index, _)| index);
+                match next {
+                    Some(index) => {
+                        let (deadline, _, task) = inner.pending.remove(index);
+                        inner.now = deadline;
+                        task
+                    }
+                    None => {
+                        inner.now = time;
+                        return;
+                    }
+                }
+            };
+            block_on(task.run());
+        }
+    }
+}
+
+impl TimeService for SimulatorTimeService {
+    fn run_after(&self, timeout: Duration, task: Box<dyn ScheduledTask>) {
+        let mut inner = self.inner.lock().unwrap();
+        let deadline = inner.now + timeout;
+        let sequence = inner.next_sequence;
+        inner.next_sequence += 1;
+        inner.pending.push((deadline, sequence, task));
+    }
+
+    fn get_current_timestamp(&self) -> Duration {
+        self.inner.lock().unwrap().now
+    }
+
+    fn sleep(&self, _t: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        future::ready(()).boxed()
+    }
+}
+
+struct SimulatedNode {
+    pacemaker: Pacemaker,
+    signer: ValidatorSigner<Ed25519PrivateKey>,
+    new_round_events_receiver: channel::Receiver<NewRoundEvent>,
+    timeout_receiver: channel::Receiver<Round>,
+    // Rounds of the timeout certificates formed by the node, with the time they formed at
+    formed_timeout_certificates: Vec<(Round, Duration)>,
+    // Rounds proposed by the node, with the time they were proposed at
+    proposed_rounds: Vec<(Round, Duration)>,
+    // A crashed node does not propose, send or receive anything
+    crashed: bool,
+}
+
+/// A message sent by a node on the way to another one.
+enum SimulatedMessage {
+    Timeout(PacemakerTimeout),
+    // The quorum certificate of the round, standing for the proposal and its votes
+    QuorumCert(Round),
+}
+
+struct InFlightMessage {
+    delivery_time: Duration,
+    to: usize,
+    message: SimulatedMessage,
+}
+
+/// Runs several pacemakers against a shared simulated clock, delivering the local timeouts each
+/// of them broadcasts to all the nodes (itself included) over links with configurable delays, and
+/// the quorum certificates of their proposals (see `enable_proposals`).  The simulation is
+/// deterministic: the events due at the same time are processed in the order they were scheduled.
+pub struct MultiPacemakerSimulator {
+    time_service: Arc<SimulatorTimeService>,
+    nodes: Vec<SimulatedNode>,
+    // Delays of the links from a node to another, 0 if not set
+    link_delays: HashMap<(usize, usize), Duration>,
+    in_flight: Vec<InFlightMessage>,
+    // Election of the proposers and delay for the proposals to be certified, see enable_proposals
+    proposals: Option<(Arc<dyn ProposerElection<TestPayload>>, Duration)>,
+}
+
+impl MultiPacemakerSimulator {
+    /// Creates and starts the given number of pacemakers with a fixed round duration.
+    pub fn new(num_nodes: usize, round_duration: Duration, timeout_quorum_size: usize) -> Self {
+        let time_service = Arc::new(SimulatorTimeService::default());
+        let nodes = (0..num_nodes)
+            .map(|index| {
+                let (new_round_events_sender, new_round_events_receiver) = channel::new_test(1_024);
+                let (timeout_sender, timeout_receiver) = channel::new_test(1_024);
+                let mut pacemaker = PacemakerBuilder::new(
+                    MockStorage::<TestPayload>::start_for_testing()
+                        .0
+                        .persistent_liveness_storage(),
+                    Box::new(ExponentialTimeInterval::fixed(round_duration)),
+                    time_service.clone(),
+                    new_round_events_sender,
+                    timeout_sender,
+                    timeout_quorum_size,
+                )
+                .build();
+                block_on(pacemaker.start());
+                SimulatedNode {
+                    pacemaker,
+                    signer: ValidatorSigner::random([index as u8; 32]),
+                    new_round_events_receiver,
+                    timeout_receiver,
+                    formed_timeout_certificates: vec![],
+                    proposed_rounds: vec![],
+                    crashed: false,
+                }
+            })
+            .collect();
+        Self {
+            time_service,
+            nodes,
+            link_delays: HashMap::new(),
+            in_flight: vec![],
+            proposals: None,
+        }
+    }
+
+    /// Makes the nodes propose: on entering a round it is the proposer of, a live node proposes
+    /// it, and all the nodes receive the quorum certificate of the round after the proposal delay.
+    /// The votes are not simulated.  Without proposals, the rounds only advance on timeouts.
+    pub fn enable_proposals(
+        &mut self,
+        proposer_election: Arc<dyn ProposerElection<TestPayload>>,
+        proposal_delay: Duration,
+    ) {
+        self.proposals = Some((proposer_election, proposal_delay));
+    }
+
+    /// Returns the author of the node.
+    pub fn author(&self, node: usize) -> Author {
+        self.nodes[node].signer.author()
+    }
+
+    /// Crashes the node of the author: until it recovers, it does not propose nor broadcast its
+    /// local timeouts, and the messages sent to it are lost.
+    pub fn crash_node(&mut self, author: Author) {
+        let node = self.node_of(author);
+        self.nodes[node].crashed = true;
+    }
+
+    /// Recovers the crashed node of the author, which resumes from the state it crashed in.
+    pub fn recover_node(&mut self, author: Author) {
+        let node = self.node_of(author);
+        self.nodes[node].crashed = false;
+    }
+
+    fn node_of(&self, author: Author) -> usize {
+        self.nodes
+            .iter()
+            .position(|node| node.signer.author() == author)
+            .expect("Unknown author")
+    }
+
+    /// Sets the delay of the timeouts broadcast by node `from` to reach node `to`.
+    pub fn set_link_delay(&mut self, from: usize, to: usize, delay: Duration) {
+        self.link_delays.insert((from, to), delay);
+    }
+
+    /// Returns the pacemaker of the node.
+    pub fn pacemaker(&self, node: usize) -> &Pacemaker {
+        &self.nodes[node].pacemaker
+    }
+
+    /// Returns the rounds of the timeout certificates formed by the node, with the simulated time
+    /// they formed at.
+    pub fn formed_timeout_certificates(&self, node: usize) -> &[(Round, Duration)] {
+        &self.nodes[node].formed_timeout_certificates
+    }
+
+    /// Returns the rounds proposed by the node, with the simulated time they were proposed at.
+    pub fn proposed_rounds(&self, node: usize) -> &[(Round, Duration)] {
+        &self.nodes[node].proposed_rounds
+    }
+
+    /// Runs the simulation until the given simulated time.
+    pub fn run_until(&mut self, end: Duration) {
+        loop {
+            // The rounds entered by then are proposed
+            self.process_new_round_events();
+            let next_delivery = self
+                .in_flight
+                .iter()
+                .map(|in_flight| in_flight.delivery_time)
+                .min();
+            let next_time = match (self.time_service.next_deadline(), next_delivery) {
+                (Some(deadline), Some(delivery)) => deadline.min(delivery),
+                (Some(time), None) | (None, Some(time)) => time,
+                (None, None) => break,
+            };
+            if next_time > end {
+                break;
+            }
+            // The local timeouts due by then are sent on the timeout channels of the nodes
+            self.time_service.advance_to(next_time);
+            self.process_local_timeouts();
+            self.deliver_messages(next_time);
+        }
+        self.time_service.advance_to(end);
+        self.process_local_timeouts();
+        self.process_new_round_events();
+    }
+
+    fn process_new_round_events(&mut self) {
+        let now = self.time_service.get_current_timestamp();
+        for from in 0..self.nodes.len() {
+            while let Some(event) = try_next_event(&mut self.nodes[from].new_round_events_receiver)
+            {
+                let node = &self.nodes[from];
+                let proposal_delay = match &self.proposals {
+                    Some((proposer_election, proposal_delay))
+                        if !node.crashed
+                            && proposer_election
+                                .is_valid_proposer(node.signer.author(), event.round)
+                                .is_some() =>
+                    {
+                        *proposal_delay
+                    }
+                    _ => continue,
+                };
+                self.nodes[from].proposed_rounds.push((event.round, now));
+                for to in 0..self.nodes.len() {
+                    self.in_flight.push(InFlightMessage {
+                        delivery_time: now + proposal_delay,
+                        to,
+                        message: SimulatedMessage::QuorumCert(event.round),
+                    });
+                }
+            }
+        }
+    }
+
+    fn process_local_timeouts(&mut self) {
+        let now = self.time_service.get_current_timestamp();
+        for from in 0..self.nodes.len() {
+            while let Some(round) = try_next_event(&mut self.nodes[from].timeout_receiver) {
+                if self.nodes[from].crashed
+                    || !self.nodes[from].pacemaker.process_local_timeout(round)
+                {
+                    continue;
+                }
+                let node = &self.nodes[from];
+                let timeout =
+                    PacemakerTimeout::new(node.pacemaker.epoch(), round, &node.signer, None);
+                for to in 0..self.nodes.len() {
+                    let delay = self
+                        .link_delays
+                        .get(&(from, to))
+                        .cloned()
+                        .unwrap_or_else(|| Duration::from_millis(0));
+                    self.in_flight.push(InFlightMessage {
+                        delivery_time: now + delay,
+                        to,
+                        message: SimulatedMessage::Timeout(timeout.clone()),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Delivers the messages due at the given time, in the order they were sent.  The messages to
+    /// the crashed nodes are lost.
+    fn deliver_messages(&mut self, time: Duration) {
+        let (due, in_flight): (Vec<_>, Vec<_>) = self
+            .in_flight
+            .drain(..)
+            .partition(|in_flight| in_flight.delivery_time <= time);
+        self.in_flight = in_flight;
+        for in_flight in due {
+            let node = &mut self.nodes[in_flight.to];
+            if node.crashed {
+                continue;
+            }
+            let timeout = match in_flight.message {
+                SimulatedMessage::Timeout(timeout) => timeout,
+                SimulatedMessage::QuorumCert(round) => {
+                    block_on(node.pacemaker.process_certificates(round, None, None));
+                    continue;
+                }
+            };
+            block_on(node.pacemaker.process_remote_timeout(timeout));
+            let tc_round = node
+                .pacemaker
+                .highest_timeout_certificate()
+                .map(|tc| tc.round());
+            if let Some(tc_round) = tc_round {
+                if node
+                    .formed_timeout_certificates
+                    .last()
+                    .map_or(true, |(round, _)| *round < tc_round)
+                {
+                    node.formed_timeout_certificates.push((tc_round, time));
+                }
+            }
+        }
+    }
+}
+
+fn try_next_event<T>(rx: &mut channel::Receiver<T>) -> Option<T> {
+    match block_on(future::select(rx.next(), future::ready(()))) {
+        Either::Left((event, _)) => event,
+        Either::Right(_) => None,
+    }
+}
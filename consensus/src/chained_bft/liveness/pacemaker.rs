@@ -1,42 +1,105 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(test)]
+use crate::{
+    chained_bft::liveness::{
+        pacemaker_config::{BatchOrdering, ProposerMismatchPolicy, QuorumSpec},
+        pacemaker_epoch::ReconfigParams,
+        pacemaker_history::{PacemakerSnapshot, PendingTimeoutsSnapshot},
+        pacemaker_observers::ProposerMismatch,
+        timeout_broadcaster::TimeoutBroadcaster,
+    },
+    util::time_service::FallbackTimeService,
+};
 use crate::{
     chained_bft::{
-        common::Round,
-        consensus_types::timeout_msg::{PacemakerTimeout, PacemakerTimeoutCertificate},
-        liveness::pacemaker_timeout_manager::{
-            HighestTimeoutCertificates, PacemakerTimeoutManager,
+        common::{Author, Round},
+        consensus_types::{
+            quorum_cert::QuorumCert,
+            timeout_msg::{
+                PacemakerTimeout, PacemakerTimeoutCertificate,
+                PacemakerTimeoutCertificateVerificationError, TimeoutVerificationError,
+            },
+        },
+        liveness::{
+            pacemaker_config::{
+                AcceptAnyQcValidator, AttachedQcExtractor, BadQcPolicy, CertificateTieBreak,
+                ConflictingTcPolicy, JitterSeedPolicy, PacemakerConfig, ProposerTimeoutPolicy,
+                QcValidator, StorageFailurePolicy, TimeoutQcExtractor,
+            },
+            pacemaker_decision_log::{PacemakerDecisionLog, PacemakerInput, PacemakerOutput},
+            pacemaker_epoch::{EpochChange, EpochProposers, EPOCH_START_ROUND},
+            pacemaker_history::{CompletedRound, PacemakerMetrics, RoundHistory},
+            pacemaker_observers::{
+                HaltReason, PacemakerHeartbeat, PacemakerObservers, PacemakerSpan,
+                RoundDurationChange, RoundSummary, TriggerStateSync,
+            },
+            pacemaker_timeout_manager::{HighestTimeoutCertificates, PacemakerTimeoutManager},
+            proposer_election::ProposerElection,
         },
-        persistent_storage::PersistentLivenessStorage,
+        persistent_storage::{BatchingLivenessStorage, PersistentLivenessStorage},
     },
     counters,
-    util::time_service::{SendTask, TimeService},
+    util::time_service::{CancellableTask, CancellationHandle, SendTask, TimeService},
 };
 use channel;
-use futures::{executor::block_on, future, Future, FutureExt, SinkExt};
+use crypto::{
+    ed25519::*,
+    hash::{CryptoHasher, PacemakerJitterHasher},
+};
+use futures::{channel::oneshot, future, Future, FutureExt, SinkExt};
 use logger::prelude::*;
 use mirai_annotations::assume;
+use prometheus::IntCounter;
+#[cfg(test)]
+use proto_conv::IntoProtoBytes;
+#[cfg(test)]
+use rmp_serde::{from_slice, to_vec_named};
+use serde::{Deserialize, Serialize};
+#[cfg(test)]
+use std::collections::HashMap;
 use std::{
-    cmp, fmt,
+    cmp,
+    collections::VecDeque,
+    fmt,
     pin::Pin,
     sync::Arc,
     time::{Duration, Instant},
 };
 use termion::color::*;
+use types::validator_verifier::{ValidatorVerifier, VerifyError};
 
 /// A reason for starting a new round: introduced for monitoring / debug purposes.
-#[derive(Eq, Debug, PartialEq)]
+/// The variants are serialized with explicit tags, such that external tools (e.g., monitoring
+/// over RPC) keep decoding the known variants when new ones are added.
+#[derive(Deserialize, Serialize, Clone, Eq, Debug, PartialEq)]
+#[serde(tag = "type", content = "details")]
 pub enum NewRoundReason {
+    #[serde(rename = "qc_ready")]
     QCReady,
-    Timeout { cert: PacemakerTimeoutCertificate },
+    #[serde(rename = "timeout")]
+    Timeout {
+        cert: PacemakerTimeoutCertificate,
+        /// The certificate is received from another replica (e.g., in a sync info or through
+        /// `Pacemaker::install_highest_timeout_certificate`) rather than gathered locally from
+        /// the timeouts: the network timed out, not necessarily the local node.
+        #[serde(default)]
+        external: bool,
+    },
 }
 
 impl fmt::Display for NewRoundReason {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             NewRoundReason::QCReady => write!(f, "QCReady"),
-            NewRoundReason::Timeout { cert } => write!(f, "{}", cert),
+            NewRoundReason::Timeout { cert, external } => {
+                write!(f, "{}", cert)?;
+                if *external {
+                    write!(f, " (external)")?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -45,23 +108,137 @@ impl fmt::Display for NewRoundReason {
 /// NewRoundEvents are consumed by the rest of the system: they can cause sending new proposals
 /// or voting for some proposals that wouldn't have been voted otherwise.
 /// The duration is populated for debugging and testing
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct NewRoundEvent {
     pub round: Round,
     pub reason: NewRoundReason,
     pub timeout: Duration,
+    /// Number of the events emitted before by the pacemaker: unlike the rounds, which can jump,
+    /// the sequence increases by exactly 1 per event, which lets the consumers detect the events
+    /// they missed.
+    #[serde(default)]
+    pub sequence: u64,
+    /// Time after the start of the round by which the proposer is expected to produce its block,
+    /// which lets the network fail fast on a dead proposer.  It is at most `timeout`, after which
+    /// the round is given up.
+    #[serde(default)]
+    pub proposal_deadline: Duration,
+    /// Number of the rounds entered right before this one whose events were not emitted, because
+    /// they were entered during the same catch-up burst (see `Pacemaker::begin_catch_up`).
+    #[serde(default)]
+    pub skipped: u64,
+}
+
+impl NewRoundEvent {
+    #[cfg(test)]
+    pub fn to_bytes(&self) -> failure::Result<Vec<u8>> {
+        Ok(to_vec_named(self)?)
+    }
+
+    #[cfg(test)]
+    pub fn from_bytes(bytes: &[u8]) -> failure::Result<Self> {
+        Ok(from_slice(bytes)?)
+    }
 }
 
 impl fmt::Display for NewRoundEvent {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "NewRoundEvent: [round: {}, reason: {}, timeout: {:?}]",
-            self.round, self.reason, self.timeout
+            "NewRoundEvent: [round: {}, reason: {}, timeout: {:?}, proposal deadline: {:?}, \
+             sequence: {}, skipped: {}]",
+            self.round,
+            self.reason,
+            self.timeout,
+            self.proposal_deadline,
+            self.sequence,
+            self.skipped
         )
     }
 }
 
+/// Progress of a round as observed by the local pacemaker, introduced for diagnostics.
+#[cfg(test)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RoundStatus {
+    /// The round is neither timed out nor certified
+    Active,
+    /// The local timeout of the round fired, but no timeout certificate is formed yet
+    LocalTimeout,
+    /// A timeout certificate is formed (locally or received) for the round
+    TimeoutCertificateFormed,
+    /// A quorum certificate is known for the round (rounds below the highest certified round are
+    /// considered certified as well)
+    Certified,
+}
+
+/// Outcome of the ingestion of a remote timeout by the pacemaker: either the timeout is accepted,
+/// or the reason why it is dropped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TimeoutIngestResult {
+    /// The timeout is recorded (it may or may not form a timeout certificate)
+    Accepted,
+    /// The signature of the timeout (or of its attached vote or certificate) is invalid
+    BadSignature,
+    /// The author of the timeout is not a known validator
+    UnknownAuthor,
+    /// The timeout is signed for another epoch than the current one (e.g., it is replayed from a
+    /// previous epoch)
+    WrongEpoch,
+    /// The round of the timeout is already committed, such timeouts are useless and repeated ones
+    /// may indicate a misbehaving peer
+    CommittedRound,
+    /// The round of the timeout is lower than the current round (beyond
+    /// `PacemakerConfig::past_timeout_rounds`)
+    StaleRound,
+    /// The timeout is the own timeout of the local node (see `Pacemaker::set_local_author`)
+    /// echoed back (e.g., by gossip) for a round the node already left.  It is dropped silently.
+    SelfEcho,
+    /// The round of the timeout is beyond `PacemakerConfig::max_future_timeout_rounds`
+    FutureRound,
+    /// A timeout of the same author is already recorded for the same or a higher round
+    Duplicate,
+    /// The quorum certificate that comes with the timeout is rejected, and so is the timeout
+    /// (see `BadQcPolicy::DropTimeout`)
+    BadQuorumCert,
+    /// The quorum certificate that comes with the timeout is timestamped beyond
+    /// `PacemakerConfig::max_qc_timestamp_skew` ahead of the local clock, and so the timeout is
+    /// dropped
+    ImplausibleQcTimestamp,
+    /// The timeouts of `PacemakerConfig::max_timeout_authors_per_round` distinct authors are
+    /// already buffered for the round of the timeout
+    TooManyAuthors,
+    /// The pacemaker already processed the inputs of `PacemakerConfig::input_budget`
+    BudgetExhausted,
+}
+
+impl TimeoutIngestResult {
+    /// Returns the counter of the remote timeouts with this outcome.
+    pub fn counter(self) -> &'static IntCounter {
+        match self {
+            TimeoutIngestResult::Accepted => &*counters::TIMEOUT_INGEST_ACCEPTED_COUNT,
+            TimeoutIngestResult::BadSignature => &*counters::TIMEOUT_INGEST_BAD_SIGNATURE_COUNT,
+            TimeoutIngestResult::UnknownAuthor => &*counters::TIMEOUT_INGEST_UNKNOWN_AUTHOR_COUNT,
+            TimeoutIngestResult::WrongEpoch => &*counters::TIMEOUT_INGEST_WRONG_EPOCH_COUNT,
+            TimeoutIngestResult::CommittedRound => &*counters::TIMEOUT_INGEST_COMMITTED_ROUND_COUNT,
+            TimeoutIngestResult::StaleRound => &*counters::TIMEOUT_INGEST_STALE_ROUND_COUNT,
+            TimeoutIngestResult::SelfEcho => &*counters::TIMEOUT_INGEST_SELF_ECHO_COUNT,
+            TimeoutIngestResult::FutureRound => &*counters::TIMEOUT_INGEST_FUTURE_ROUND_COUNT,
+            TimeoutIngestResult::Duplicate => &*counters::TIMEOUT_INGEST_DUPLICATE_COUNT,
+            TimeoutIngestResult::BadQuorumCert => &*counters::TIMEOUT_INGEST_BAD_QUORUM_CERT_COUNT,
+            TimeoutIngestResult::ImplausibleQcTimestamp => {
+                &*counters::TIMEOUT_INGEST_IMPLAUSIBLE_QC_TIMESTAMP_COUNT
+            }
+            TimeoutIngestResult::TooManyAuthors => {
+                &*counters::TIMEOUT_INGEST_TOO_MANY_AUTHORS_COUNT
+            }
+            TimeoutIngestResult::BudgetExhausted => {
+                &*counters::TIMEOUT_INGEST_BUDGET_EXHAUSTED_COUNT
+            }
+        }
+    }
+}
+
 /// Determines the maximum round duration based on the round difference between the current
 /// round and the committed round
 pub trait PacemakerTimeInterval: Send + Sync + 'static {
@@ -75,23 +252,74 @@ pub trait PacemakerTimeInterval: Send + Sync + 'static {
     /// to calculate the round duration of round 6 and the highest committed round is 3 (meaning
     /// the highest round to commit a block is round 5, then the round index is 0.
     fn get_round_duration(&self, round_index_after_committed_qc: usize) -> Duration;
+
+    /// Describe the strategy and its parameters, e.g., for the admin tooling.  The default
+    /// description only names a custom strategy.
+    fn describe(&self) -> IntervalDescription {
+        IntervalDescription {
+            type_name: "custom".to_string(),
+            ..IntervalDescription::default()
+        }
+    }
+
+    /// Returns the first round index whose duration meets or exceeds the target (e.g., how many
+    /// consecutive timeouts until the rounds last 60s), or None if the durations are capped below
+    /// the target.  The default scans the round indices checked by `validate_time_interval`.
+    fn rounds_until_duration(&self, target: Duration) -> Option<u64> {
+        first_round_index_reaching(self, target, TIME_INTERVAL_SELF_TEST_ROUNDS)
+    }
+}
+
+/// Returns the first round index up to `last_round_index` (included) whose duration meets or
+/// exceeds the target.
+fn first_round_index_reaching<I: PacemakerTimeInterval + ?Sized>(
+    time_interval: &I,
+    target: Duration,
+    last_round_index: usize,
+) -> Option<u64> {
+    (0..=last_round_index)
+        .find(|round_index| time_interval.get_round_duration(*round_index) >= target)
+        .map(|round_index| round_index as u64)
+}
+
+/// Serializable description of a PacemakerTimeInterval, the parameters that do not apply to the
+/// strategy are not set.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct IntervalDescription {
+    /// Name of the strategy
+    pub type_name: String,
+    /// Duration of the first round index, in milliseconds
+    pub base_ms: Option<u64>,
+    /// Multiplier of the duration between consecutive round indices
+    pub multiplier: Option<f64>,
+    /// Multiplier as the integer ratio (numerator, denominator), if given so
+    pub multiplier_ratio: Option<(u64, u64)>,
+    /// Maximum exponent of the multiplier, which caps the durations
+    pub max_exponent: Option<usize>,
+    /// Number of round indices that keep the base duration before the backoff begins
+    pub backoff_start_round: Option<usize>,
 }
 
 /// Round durations increase exponentially
 /// Basically time interval is base * mul^power
-/// Where power=max(rounds_since_qc, max_exponent)
+/// Where power=min(max(rounds_since_qc - backoff_start_round, 0), max_exponent)
 #[derive(Clone)]
 pub struct ExponentialTimeInterval {
     // Initial time interval duration after a successful quorum commit.
     base_ms: u64,
     // By how much we increase interval every time
     exponent_base: f64,
+    // Integer ratio (numerator, denominator) used instead of exponent_base when set, such that
+    // the durations are computed with integer math only
+    exponent_ratio: Option<(u64, u64)>,
     // Maximum time interval won't exceed base * mul^max_pow.
     // Theoretically, setting it means
     // that we rely on synchrony assumptions when the known max messaging delay is
     // max_interval.  Alternatively, we can consider using max_interval to meet partial synchrony
     // assumptions where while delta is unknown, it is <= max_interval.
     max_exponent: usize,
+    // Number of round indices that keep the base duration before the backoff begins.
+    backoff_start_round: usize,
 }
 
 impl ExponentialTimeInterval {
@@ -101,6 +329,17 @@ impl ExponentialTimeInterval {
     }
 
     pub fn new(base: Duration, exponent_base: f64, max_exponent: usize) -> Self {
+        Self::new_with_backoff_start_round(base, exponent_base, max_exponent, 0)
+    }
+
+    /// Same as `new`, but the durations of the first `backoff_start_round` round indices stay at
+    /// the base, which tolerates a few timeouts (e.g., at startup) before backing off.
+    pub fn new_with_backoff_start_round(
+        base: Duration,
+        exponent_base: f64,
+        max_exponent: usize,
+        backoff_start_round: usize,
+    ) -> Self {
         assert!(
             max_exponent < 32,
             "max_exponent for PacemakerTimeInterval should be <32"
@@ -112,18 +351,180 @@ impl ExponentialTimeInterval {
         ExponentialTimeInterval {
             base_ms: base.as_millis() as u64, // any reasonable ms timeout fits u64 perfectly
             exponent_base,
+            exponent_ratio: None,
+            max_exponent,
+            backoff_start_round,
+        }
+    }
+
+    /// Same as `new`, but the multiplier is the ratio `num / den` and the durations are computed
+    /// with integer math, which guarantees identical durations across platforms.  The durations
+    /// whose integer computation would overflow are computed as with `new` instead.
+    #[cfg(test)]
+    pub fn new_ratio(base: Duration, num: u64, den: u64, max_exponent: usize) -> Self {
+        assert!(den > 0, "Denominator of the multiplier should be positive");
+        assert!(
+            max_exponent < 32,
+            "max_exponent for PacemakerTimeInterval should be <32"
+        );
+        // Reduced, such that the powers overflow as late as possible
+        let gcd = gcd(num, den);
+        let (num, den) = (num / gcd, den / gcd);
+        let below_max_multiplier = match checked_ratio_pow(num, den, max_exponent as u32) {
+            Some((num_pow, den_pow)) => num_pow / den_pow < u128::from(std::u32::MAX),
+            None => (num as f64 / den as f64).powi(max_exponent as i32) < f64::from(std::u32::MAX),
+        };
+        assert!(
+            below_max_multiplier,
+            "Maximum interval multiplier should be less then u32::Max"
+        );
+        ExponentialTimeInterval {
+            base_ms: base.as_millis() as u64,
+            exponent_base: num as f64 / den as f64,
+            exponent_ratio: Some((num, den)),
             max_exponent,
+            backoff_start_round: 0,
+        }
+    }
+}
+
+/// Returns the greatest common divisor of the two numbers.
+#[cfg(test)]
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// Returns the powers of the numerator and of the denominator of a ratio, or None if either
+/// overflows.
+fn checked_ratio_pow(num: u64, den: u64, exponent: u32) -> Option<(u128, u128)> {
+    Some((
+        u128::from(num).checked_pow(exponent)?,
+        u128::from(den).checked_pow(exponent)?,
+    ))
+}
+
+/// Number of round indices that are checked by `validate_time_interval`.  It covers the maximum
+/// exponent supported by `ExponentialTimeInterval`, after which the durations are capped.
+const TIME_INTERVAL_SELF_TEST_ROUNDS: usize = 32;
+
+/// Pacemaker construction and configuration errors.
+#[derive(Debug, Fail, PartialEq)]
+pub enum PacemakerError {
+    /// The time interval returned a zero duration, which would cause a busy loop of timeouts
+    #[fail(display = "ZeroRoundDuration at round index {}", round_index)]
+    ZeroRoundDuration { round_index: usize },
+    /// The time interval returned a shorter duration than for the previous round index
+    #[fail(display = "DecreasingRoundDuration at round index {}", round_index)]
+    DecreasingRoundDuration { round_index: usize },
+    /// The installed timeout certificate doesn't pass verification
+    #[fail(display = "InvalidTimeoutCertificate: {}", _0)]
+    InvalidTimeoutCertificate(PacemakerTimeoutCertificateVerificationError),
+    /// The installed timeout certificate contains timeouts of another epoch
+    #[fail(display = "TimeoutCertificateOfOtherEpoch {}", epoch)]
+    TimeoutCertificateOfOtherEpoch { epoch: u64 },
+    /// The installed timeout certificate is not higher than the highest known one
+    #[fail(
+        display = "StaleTimeoutCertificate at round {} (highest round {})",
+        round, highest_round
+    )]
+    StaleTimeoutCertificate { round: Round, highest_round: Round },
+    /// The reconfiguration is not for an epoch higher than the current one (e.g., a duplicate)
+    #[fail(display = "StaleEpoch {} (current epoch {})", epoch, current_epoch)]
+    StaleEpoch { epoch: u64, current_epoch: u64 },
+}
+
+/// Verifies that the round durations of the given time interval are never zero and never
+/// decrease with the round index (up to the cap of the interval).
+pub fn validate_time_interval(
+    time_interval: &dyn PacemakerTimeInterval,
+) -> Result<(), PacemakerError> {
+    let mut prev_duration = Duration::from_millis(0);
+    for round_index in 0..=TIME_INTERVAL_SELF_TEST_ROUNDS {
+        let duration = time_interval.get_round_duration(round_index);
+        if duration == Duration::from_millis(0) {
+            return Err(PacemakerError::ZeroRoundDuration { round_index });
+        }
+        if duration < prev_duration {
+            return Err(PacemakerError::DecreasingRoundDuration { round_index });
         }
+        prev_duration = duration;
     }
+    Ok(())
 }
 
 impl PacemakerTimeInterval for ExponentialTimeInterval {
     fn get_round_duration(&self, round_index_after_committed_qc: usize) -> Duration {
-        let pow = round_index_after_committed_qc.min(self.max_exponent) as u32;
+        let pow = round_index_after_committed_qc
+            .saturating_sub(self.backoff_start_round)
+            .min(self.max_exponent) as u32;
+        if let Some((num, den)) = self.exponent_ratio {
+            // Rounded up like the float computation, which is the fallback on overflow
+            let duration_ms = checked_ratio_pow(num, den, pow).and_then(|(num_pow, den_pow)| {
+                u128::from(self.base_ms)
+                    .checked_mul(num_pow)?
+                    .checked_add(den_pow - 1)
+                    .map(|scaled_ms| scaled_ms / den_pow)
+            });
+            if let Some(duration_ms) = duration_ms {
+                return Duration::from_millis(
+                    cmp::min(duration_ms, u128::from(std::u64::MAX)) as u64
+                );
+            }
+        }
         let base_multiplier = self.exponent_base.powf(f64::from(pow));
-        let duration_ms = ((self.base_ms as f64) * base_multiplier).ceil() as u64;
-        Duration::from_millis(duration_ms)
+        let duration_ms = ((self.base_ms as f64) * base_multiplier).ceil();
+        // Clamped, as casting an out of range float is undefined
+        if duration_ms >= std::u64::MAX as f64 {
+            return Duration::from_millis(std::u64::MAX);
+        }
+        Duration::from_millis(duration_ms as u64)
+    }
+
+    fn describe(&self) -> IntervalDescription {
+        IntervalDescription {
+            type_name: "exponential".to_string(),
+            base_ms: Some(self.base_ms),
+            multiplier: Some(self.exponent_base),
+            multiplier_ratio: self.exponent_ratio,
+            max_exponent: Some(self.max_exponent),
+            backoff_start_round: Some(self.backoff_start_round),
+        }
     }
+
+    fn rounds_until_duration(&self, target: Duration) -> Option<u64> {
+        // The durations are capped from the round index of the maximum exponent on
+        first_round_index_reaching(self, target, self.backoff_start_round + self.max_exponent)
+    }
+}
+
+/// The configured and the effective quorum of timeouts, see `Pacemaker::quorum_info`.
+#[cfg(test)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct QuorumInfo {
+    /// The configured `PacemakerConfig::quorum_spec`, if any
+    pub spec: Option<QuorumSpec>,
+    /// Size of the current validator set, if known (see `Pacemaker::update_quorum_size`)
+    pub validator_set_size: Option<usize>,
+    /// Quorum of timeouts computed from the spec for the current validator set
+    pub quorum_size: usize,
+    /// The configured `PacemakerConfig::timeout_certificate_min_signers`
+    pub min_signers: usize,
+    /// Number of timeouts actually required to form a timeout certificate: the quorum, raised
+    /// to the minimum number of signers
+    pub effective_threshold: usize,
+}
+
+/// The rounds entered during a catch-up burst, see `Pacemaker::begin_catch_up`.
+#[derive(Default)]
+struct CatchUp {
+    entered_rounds: u64,
+    // Reason for entering the last of the rounds
+    last_reason: Option<NewRoundReason>,
 }
 
 /// `Pacemaker` is a Pacemaker implementation that relies on increasing local timeouts
@@ -144,9 +545,15 @@ impl PacemakerTimeInterval for ExponentialTimeInterval {
 /// indicating to move to the next round, all other honest replicas will move to the next round
 /// within a bounded time. This can be guaranteed via all honest replicas gossiping their highest
 /// QC to f+1 other replicas for instance.
+///
+/// A single validator network (quorum size of 1) is a degenerate but well-defined case: the
+/// validator certifies every round by itself, so the rounds advance on its QCs without waiting
+/// for any timeout, and a round only times out if the validator fails to certify it.
 pub struct Pacemaker {
     // Determines the time interval for a round interval
     time_interval: Box<dyn PacemakerTimeInterval>,
+    // Replaces time_interval from the next round on
+    pending_time_interval: Option<Box<dyn PacemakerTimeInterval>>,
     // Highest round that a block was committed
     highest_committed_round: Round,
     // Highest round known certified by QC.
@@ -158,6 +565,13 @@ pub struct Pacemaker {
     current_round: Round,
     // Approximate deadline when current round ends
     current_round_deadline: Instant,
+    // Timestamp (as reported by the time service) when the current round timeout was set up
+    current_round_start: Duration,
+    // Duration of the current round timeout
+    current_round_timeout: Duration,
+    // Timestamp of the quorum certificate being processed, the start of the round it triggers
+    // (see PacemakerConfig::max_round_start_skew)
+    round_start_anchor: Option<Duration>,
     // Service for timer
     time_service: Arc<dyn TimeService>,
     // To send new round events.
@@ -166,20 +580,213 @@ pub struct Pacemaker {
     timeout_sender: channel::Sender<Round>,
     // Manages the PacemakerTimeout and PacemakerTimeoutCertificate structs
     pacemaker_timeout_manager: PacemakerTimeoutManager,
+    // Optional behaviors
+    config: PacemakerConfig,
+    // Current epoch
+    epoch: u64,
+    // Number of local timeouts broadcast in the current epoch
+    timeouts_sent_in_epoch: u64,
+    // Last round for which the local timeout fired
+    last_local_timeout_round: Option<Round>,
+    // First round of this pacemaker
+    bootstrap_round: Round,
+    // Whether the pacemaker is constructed from a recovered state, see
+    // PacemakerConfig::post_recovery_grace
+    recovered: bool,
+    // Whether the first new round event was emitted
+    started: bool,
+    // Time at which the pacemaker started, see PacemakerConfig::startup_grace_period
+    start_time: Option<Duration>,
+    // Time of the last new round event, see PacemakerConfig::max_stall_interval
+    last_advance_time: Option<Duration>,
+    // Number of local timeouts broadcast for the current round, see retransmit_count
+    timeout_broadcasts_in_round: u32,
+    // Rounds awaited by await_timeout_certificate, with the senders of their certificates
+    timeout_certificate_waiters: Vec<(Round, oneshot::Sender<PacemakerTimeoutCertificate>)>,
+    // Round of the local timeout waiting for the end of the coalescing window
+    pending_coalesced_timeout: Option<Round>,
+    // Decides whether a quorum certificate advances the round
+    qc_validator: Arc<dyn QcValidator>,
+    // Extracts the quorum certificates of the remote timeouts
+    timeout_qc_extractor: Arc<dyn TimeoutQcExtractor>,
+    // Inputs and outputs of the pacemaker, if recorded
+    decision_log: Option<PacemakerDecisionLog>,
+    // Verifier of the remote timeouts, the callers are trusted to verify them if not set
+    validator_verifier: Option<Arc<ValidatorVerifier<Ed25519PublicKey>>>,
+    // Size of the validator set the quorum of timeouts was last computed for
+    validator_set_size: Option<usize>,
+    // The most recent completed rounds
+    round_history: RoundHistory,
+    // Cancels the scheduled timeout of the current round
+    round_timeout_handle: Option<CancellationHandle>,
+    // Cancel the scheduled heartbeats of the current round
+    heartbeat_handles: Vec<CancellationHandle>,
+    // Cancels the scheduled proposer turn notification of the next round
+    proposer_turn_handle: Option<CancellationHandle>,
+    // Round of the last early local timeout, which is triggered at most once per round
+    early_timeout_round: Option<Round>,
+    // Transport of the local timeouts, see broadcast_local_timeout
+    #[cfg(test)]
+    timeout_broadcaster: Option<Arc<dyn TimeoutBroadcaster>>,
+    // Time since which the certification gap is above the alert threshold, if it is
+    certification_gap_exceeded_since: Option<Duration>,
+    // Whether the alert is already sent for the current excess of the certification gap
+    certification_gap_alerted: bool,
+    // Whether the alert is already sent for the current excess of the oscillation
+    oscillation_alerted: bool,
+    // Highest round of the accepted remote timeouts of the other authors
+    highest_remote_timeout_round: Round,
+    // Whether the most recent local timeouts fired before any remote timeout of their round
+    local_timeouts_first: VecDeque<bool>,
+    // Whether the alert is already sent for the current excess of the local timeouts first
+    fast_clock_alerted: bool,
+    // Number of rounds timed out since the last advance on a quorum certificate
+    timed_out_rounds_since_qc: u64,
+    // Whether the state sync is already triggered for the current rounds timed out
+    state_sync_triggered: bool,
+    // Number of inputs processed, see PacemakerConfig::input_budget
+    processed_inputs: u64,
+    // Duration of the last round entered, see PacemakerConfig::round_duration_change_threshold
+    last_round_duration: Option<Duration>,
+    // Counts of the events since the last reset, see metrics_snapshot
+    metrics: PacemakerMetrics,
+    // Proposers of the rounds, see set_proposer_election and set_proposer_election_factory
+    epoch_proposers: EpochProposers,
+    // Sequence number of the next new round event
+    next_event_sequence: u64,
+    // Time of the last round advance driven by a quorum certificate
+    last_qc_advance_time: Option<Instant>,
+    // Rounds entered during the current catch-up burst, see begin_catch_up
+    catch_up: Option<CatchUp>,
+    // Reason of the halt of the pacemaker if it halted, see HaltReason
+    halt_reason: Option<HaltReason>,
+    // Channels and observers of the events of the pacemaker
+    observers: PacemakerObservers,
+    // Author of the local node, see set_local_author
+    local_author: Option<Author>,
+    // Prefix of the log messages, see PacemakerConfig::validator_label
+    log_prefix: String,
 }
 
-impl Pacemaker {
+/// Builder of a Pacemaker from the arguments every pacemaker requires.  The other arguments are
+/// optional: by default, the pacemaker starts from genesis (no certified round nor timeout
+/// certificate) with the default PacemakerConfig, and the remote timeouts are not verified.
+pub struct PacemakerBuilder {
+    persistent_liveness_storage: Box<dyn PersistentLivenessStorage>,
+    time_interval: Box<dyn PacemakerTimeInterval>,
+    time_service: Arc<dyn TimeService>,
+    new_round_events_sender: channel::Sender<NewRoundEvent>,
+    timeout_sender: channel::Sender<Round>,
+    pacemaker_timeout_quorum_size: usize,
+    highest_committed_round: Round,
+    highest_qc_round: Round,
+    highest_timeout_certificates: HighestTimeoutCertificates,
+    config: PacemakerConfig,
+    validator_verifier: Option<Arc<ValidatorVerifier<Ed25519PublicKey>>>,
+}
+
+impl PacemakerBuilder {
     pub fn new(
         persistent_liveness_storage: Box<dyn PersistentLivenessStorage>,
         time_interval: Box<dyn PacemakerTimeInterval>,
-        highest_committed_round: Round,
-        highest_qc_round: Round,
         time_service: Arc<dyn TimeService>,
         new_round_events_sender: channel::Sender<NewRoundEvent>,
         timeout_sender: channel::Sender<Round>,
         pacemaker_timeout_quorum_size: usize,
+    ) -> Self {
+        Self {
+            persistent_liveness_storage,
+            time_interval,
+            time_service,
+            new_round_events_sender,
+            timeout_sender,
+            pacemaker_timeout_quorum_size,
+            highest_committed_round: 0,
+            highest_qc_round: 0,
+            highest_timeout_certificates: HighestTimeoutCertificates::default(),
+            config: PacemakerConfig::default(),
+            validator_verifier: None,
+        }
+    }
+
+    /// Sets the highest committed round, e.g., recovered from the storage.
+    pub fn highest_committed_round(mut self, highest_committed_round: Round) -> Self {
+        self.highest_committed_round = highest_committed_round;
+        self
+    }
+
+    /// Sets the highest certified round, e.g., recovered from the storage.
+    pub fn highest_qc_round(mut self, highest_qc_round: Round) -> Self {
+        self.highest_qc_round = highest_qc_round;
+        self
+    }
+
+    /// Sets the highest timeout certificates, e.g., recovered from the storage.
+    pub fn highest_timeout_certificates(
+        mut self,
         highest_timeout_certificates: HighestTimeoutCertificates,
     ) -> Self {
+        self.highest_timeout_certificates = highest_timeout_certificates;
+        self
+    }
+
+    /// Sets the configuration of the optional behaviors of the pacemaker.
+    #[cfg(test)]
+    pub fn config(mut self, config: PacemakerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the verifier of the remote timeouts (see `Pacemaker::set_validator_verifier`), which
+    /// `try_build` also verifies the highest timeout certificates with.
+    #[cfg(test)]
+    pub fn validator_verifier(
+        mut self,
+        validator_verifier: Arc<ValidatorVerifier<Ed25519PublicKey>>,
+    ) -> Self {
+        self.validator_verifier = Some(validator_verifier);
+        self
+    }
+
+    /// Same as `try_build`, without checking the arguments.
+    pub fn build(self) -> Pacemaker {
+        Pacemaker::new(self)
+    }
+
+    /// Builds the pacemaker, but first runs a self-check of the time interval (see
+    /// `validate_time_interval`) and fails the construction if a misconfigured interval is given.
+    /// With a validator verifier, the given timeout certificates (e.g., recovered from a possibly
+    /// corrupted storage) are verified as well: the construction fails on an invalid certificate
+    /// rather than trusting it.
+    pub fn try_build(self) -> Result<Pacemaker, PacemakerError> {
+        validate_time_interval(self.time_interval.as_ref())?;
+        if let Some(validator_verifier) = self.validator_verifier.as_ref() {
+            self.highest_timeout_certificates
+                .verify(validator_verifier)
+                .map_err(PacemakerError::InvalidTimeoutCertificate)?;
+        }
+        Ok(self.build())
+    }
+}
+
+impl Pacemaker {
+    /// Creating the pacemaker has no side effects: the new round event of the first round is
+    /// emitted (and its timeout is scheduled) once `start` is called, which lets the callers
+    /// finish wiring the receivers first.
+    fn new(builder: PacemakerBuilder) -> Self {
+        let PacemakerBuilder {
+            persistent_liveness_storage,
+            time_interval,
+            time_service,
+            new_round_events_sender,
+            timeout_sender,
+            pacemaker_timeout_quorum_size,
+            highest_committed_round,
+            highest_qc_round,
+            highest_timeout_certificates,
+            config,
+            validator_verifier,
+        } = builder;
         assert!(pacemaker_timeout_quorum_size > 0);
         // Round numbers:
         // - are reset to 0 periodically.
@@ -204,46 +811,278 @@ impl Pacemaker {
         counters::QC_ROUNDS_COUNT.get();
         counters::TIMEOUT_ROUNDS_COUNT.get();
         counters::TIMEOUT_COUNT.get();
+        for result in &[
+            TimeoutIngestResult::Accepted,
+            TimeoutIngestResult::BadSignature,
+            TimeoutIngestResult::UnknownAuthor,
+            TimeoutIngestResult::WrongEpoch,
+            TimeoutIngestResult::CommittedRound,
+            TimeoutIngestResult::StaleRound,
+            TimeoutIngestResult::SelfEcho,
+            TimeoutIngestResult::FutureRound,
+            TimeoutIngestResult::Duplicate,
+            TimeoutIngestResult::BadQuorumCert,
+            TimeoutIngestResult::ImplausibleQcTimestamp,
+            TimeoutIngestResult::TooManyAuthors,
+            TimeoutIngestResult::BudgetExhausted,
+        ] {
+            result.counter().get();
+        }
 
-        let mut pacemaker = Self {
+        let decision_log = if config.record_decision_log {
+            Some(PacemakerDecisionLog::default())
+        } else {
+            None
+        };
+        let persistent_liveness_storage: Box<dyn PersistentLivenessStorage> =
+            match config.liveness_write_batch_interval {
+                Some(flush_interval) => Box::new(BatchingLivenessStorage::new(
+                    persistent_liveness_storage,
+                    flush_interval,
+                    time_service.clone(),
+                )),
+                None => persistent_liveness_storage,
+            };
+        let mut pacemaker_timeout_manager = PacemakerTimeoutManager::new(
+            pacemaker_timeout_quorum_size,
+            highest_timeout_certificates,
+            persistent_liveness_storage,
+        );
+        pacemaker_timeout_manager.set_time_service(time_service.clone());
+        pacemaker_timeout_manager.set_min_signers(config.timeout_certificate_min_signers);
+        pacemaker_timeout_manager.set_max_tracked_rounds(config.max_tracked_timeout_rounds);
+        pacemaker_timeout_manager.set_storage_retry(config.storage_retry);
+        pacemaker_timeout_manager.set_tie_break(config.timeout_certificate_tie_break);
+        let qc_validator = config
+            .qc_validator
+            .clone()
+            .unwrap_or_else(|| Arc::new(AcceptAnyQcValidator));
+        let timeout_qc_extractor = config
+            .timeout_qc_extractor
+            .clone()
+            .unwrap_or_else(|| Arc::new(AttachedQcExtractor));
+        let log_prefix = match &config.validator_label {
+            Some(label) => format!("[{}] ", label),
+            None => String::new(),
+        };
+        Self {
             time_interval,
+            pending_time_interval: None,
             highest_committed_round,
             highest_qc_round,
             current_round,
             current_round_deadline: Instant::now(),
+            current_round_start: Duration::from_millis(0),
+            current_round_timeout: Duration::from_millis(0),
+            round_start_anchor: None,
             time_service,
             new_round_events_sender,
             timeout_sender,
-            pacemaker_timeout_manager: PacemakerTimeoutManager::new(
-                pacemaker_timeout_quorum_size,
-                highest_timeout_certificates,
-                persistent_liveness_storage,
-            ),
-        };
+            pacemaker_timeout_manager,
+            config,
+            epoch: 0,
+            timeouts_sent_in_epoch: 0,
+            last_local_timeout_round: None,
+            bootstrap_round: current_round,
+            recovered: current_round > 1,
+            started: false,
+            start_time: None,
+            last_advance_time: None,
+            timeout_broadcasts_in_round: 0,
+            timeout_certificate_waiters: vec![],
+            pending_coalesced_timeout: None,
+            qc_validator,
+            timeout_qc_extractor,
+            decision_log,
+            validator_verifier,
+            validator_set_size: None,
+            round_history: RoundHistory::default(),
+            round_timeout_handle: None,
+            heartbeat_handles: vec![],
+            proposer_turn_handle: None,
+            early_timeout_round: None,
+            #[cfg(test)]
+            timeout_broadcaster: None,
+            certification_gap_exceeded_since: None,
+            certification_gap_alerted: false,
+            oscillation_alerted: false,
+            highest_remote_timeout_round: 0,
+            local_timeouts_first: VecDeque::new(),
+            fast_clock_alerted: false,
+            timed_out_rounds_since_qc: 0,
+            state_sync_triggered: false,
+            processed_inputs: 0,
+            last_round_duration: None,
+            metrics: PacemakerMetrics::default(),
+            epoch_proposers: EpochProposers::default(),
+            next_event_sequence: 0,
+            last_qc_advance_time: None,
+            catch_up: None,
+            halt_reason: None,
+            observers: PacemakerObservers::default(),
+            local_author: None,
+            log_prefix,
+        }
+    }
+
+    /// Returns the new round event that `start` would emit for a pacemaker created with the
+    /// given arguments.  The event is computed synchronously: no channel, storage or timer is
+    /// involved, which keeps the unit tests of a single round transition lightweight.
+    #[cfg(test)]
+    pub fn single_round(
+        time_interval: Box<dyn PacemakerTimeInterval>,
+        highest_committed_round: Round,
+        highest_qc_round: Round,
+        highest_timeout_certificates: HighestTimeoutCertificates,
+        config: PacemakerConfig,
+    ) -> NewRoundEvent {
+        let (new_round_events_sender, _) = channel::new_test(1);
+        let (timeout_sender, _) = channel::new_test(1);
+        let mut pacemaker = PacemakerBuilder::new(
+            Box::new(crate::chained_bft::test_utils::EmptyStorage),
+            time_interval,
+            Arc::new(crate::util::mock_time_service::SimulatedTimeService::new()),
+            new_round_events_sender,
+            timeout_sender,
+            1,
+        )
+        .highest_committed_round(highest_committed_round)
+        .highest_qc_round(highest_qc_round)
+        .highest_timeout_certificates(highest_timeout_certificates)
+        .config(config)
+        .build();
+        pacemaker.started = true;
+        pacemaker.new_round_event(NewRoundReason::QCReady, 0)
+    }
+
+    /// Emits the new round event of the first round (unless
+    /// `PacemakerConfig::suppress_initial_event`) and schedules its timeout.  Calling `start` on a
+    /// started pacemaker has no effect.
+    pub fn start(&mut self) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        self.record_input(|| PacemakerInput::Start);
+        if self.started {
+            warn!(
+                "{}Pacemaker is already started at round {}",
+                self.log_prefix, self.current_round
+            );
+            return future::ready(()).boxed();
+        }
+        self.started = true;
+        self.start_time = Some(self.time_service.get_current_timestamp());
+        if self.config.suppress_initial_event {
+            // The first round still times out, only its event is not sent
+            self.setup_timeout();
+            return future::ready(()).boxed();
+        }
         // To jump start the execution.
-        block_on(pacemaker.create_new_round_task(NewRoundReason::QCReady));
-        pacemaker
+        self.create_new_round_task(NewRoundReason::QCReady, 0)
+            .boxed()
     }
 
     /// Trigger an event to create a new round interval and ignore any events from previous round
     /// intervals.  The reason for the event is given by the caller, the timeout is
     /// deterministically determined by the reason and the internal state.
-    fn create_new_round_task(&mut self, reason: NewRoundReason) -> impl Future<Output = ()> + Send {
-        let round = self.current_round;
-        let timeout = self.setup_timeout();
+    fn create_new_round_task(
+        &mut self,
+        reason: NewRoundReason,
+        skipped: u64,
+    ) -> impl Future<Output = ()> + Send {
+        let event = self.new_round_event(reason, skipped);
+        let duration_change = self.check_round_duration_change(event.round, event.timeout);
         let mut sender = self.new_round_events_sender.clone();
-        async move {
-            if let Err(e) = sender
-                .send(NewRoundEvent {
-                    round,
-                    reason,
-                    timeout,
-                })
-                .await
-            {
-                debug!("Error in sending new round interval event: {:?}", e);
+        let log_prefix = self.log_prefix.clone();
+        let send_event = async move {
+            if let Err(e) = sender.send(event).await {
+                debug!(
+                    "{}Error in sending new round interval event: {:?}",
+                    log_prefix, e
+                );
+            }
+        }
+            .boxed();
+        let send_event = match self.config.event_executor.as_ref() {
+            Some(event_executor) => {
+                event_executor.spawn(send_event);
+                future::ready(()).boxed()
             }
+            None => send_event,
+        };
+        async move {
+            send_event.await;
+            duration_change.await;
+        }
+    }
+
+    /// Sends a `RoundDurationChange` if the duration of the entered round changed by more than
+    /// `PacemakerConfig::round_duration_change_threshold` percent from the previous round.
+    fn check_round_duration_change(
+        &mut self,
+        round: Round,
+        duration: Duration,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let previous_duration = self.last_round_duration.replace(duration);
+        let (threshold, previous_duration) = match (
+            self.config.round_duration_change_threshold,
+            previous_duration,
+        ) {
+            (Some(threshold), Some(previous_duration)) => (threshold, previous_duration),
+            _ => return future::ready(()).boxed(),
+        };
+        let previous_ms = previous_duration.as_millis();
+        let change_ms = if duration > previous_duration {
+            (duration - previous_duration).as_millis()
+        } else {
+            (previous_duration - duration).as_millis()
+        };
+        if change_ms * 100 <= u128::from(threshold) * previous_ms {
+            return future::ready(()).boxed();
+        }
+        debug!(
+            "{}Round duration changed from {} ms to {} ms in round {}",
+            self.log_prefix,
+            previous_ms,
+            duration.as_millis(),
+            round
+        );
+        let change = RoundDurationChange {
+            round,
+            previous_duration,
+            duration,
+        };
+        self.send_optional(
+            &self.observers.round_duration_change,
+            change,
+            "round duration change",
+        )
+    }
+
+    /// Enters the current round: schedules its timeout and returns its new round event.
+    fn new_round_event(&mut self, reason: NewRoundReason, skipped: u64) -> NewRoundEvent {
+        if let Some(time_interval) = self.pending_time_interval.take() {
+            self.time_interval = time_interval;
         }
+        let round = self.current_round;
+        self.last_advance_time = Some(self.time_service.get_current_timestamp());
+        self.timeout_broadcasts_in_round = 0;
+        self.observers.close_spans();
+        self.observers.open_span(PacemakerSpan::Round(round));
+        let timeout = self.setup_timeout();
+        self.schedule_proposer_turn(timeout);
+        let proposal_deadline = match self.config.proposal_deadline_fraction {
+            Some((numerator, denominator)) => timeout * numerator / denominator,
+            None => timeout,
+        };
+        let event = NewRoundEvent {
+            round,
+            reason,
+            timeout,
+            sequence: self.next_event_sequence,
+            proposal_deadline,
+            skipped,
+        };
+        self.next_event_sequence += 1;
+        self.record_output(|| PacemakerOutput::NewRound(event.clone()));
+        event
     }
 
     /// Setup the timeout task and return the duration of the current timeout
@@ -253,33 +1092,204 @@ impl Pacemaker {
         // Note that the timeout should not be driven sequentially with any other events as it can
         // become the head of the line blocker.
         trace!(
-            "Scheduling timeout of {} ms for round {}",
+            "{}Scheduling timeout of {} ms for round {}",
+            self.log_prefix,
             timeout.as_millis(),
             self.current_round
         );
-        self.time_service
-            .run_after(timeout, SendTask::make(timeout_sender, self.current_round));
+        // The timeout of the previous round must not fire once the round advanced.
+        if let Some(handle) = self.round_timeout_handle.take() {
+            handle.cancel();
+        }
+        if self.config.manual_advance {
+            return timeout;
+        }
+        let (task, handle) =
+            CancellableTask::make(SendTask::make(timeout_sender, self.current_round));
+        self.round_timeout_handle = Some(handle);
+        self.time_service.run_after(timeout, task);
+        self.schedule_heartbeats(timeout);
         timeout
     }
 
+    /// Schedules the heartbeats of the current round until its timeout, and cancels the ones of
+    /// the previous round.
+    fn schedule_heartbeats(&mut self, timeout: Duration) {
+        for handle in self.heartbeat_handles.drain(..) {
+            handle.cancel();
+        }
+        let (interval, sender) = match (self.config.heartbeat_interval, &self.observers.heartbeat) {
+            (Some(interval), Some(sender)) if interval > Duration::from_millis(0) => {
+                (interval, sender)
+            }
+            _ => return,
+        };
+        let mut elapsed = interval;
+        while elapsed < timeout {
+            let heartbeat = PacemakerHeartbeat {
+                round: self.current_round,
+                elapsed,
+            };
+            let (task, handle) = CancellableTask::make(SendTask::make(sender.clone(), heartbeat));
+            self.heartbeat_handles.push(handle);
+            self.time_service.run_after(elapsed, task);
+            elapsed += interval;
+        }
+    }
+
+    /// Schedules the proposer turn notification `PacemakerConfig::proposer_turn_lead_time` before
+    /// the timeout of the current round if the local node leads the next round, and cancels the
+    /// one of the previous round.
+    fn schedule_proposer_turn(&mut self, timeout: Duration) {
+        if let Some(handle) = self.proposer_turn_handle.take() {
+            handle.cancel();
+        }
+        let (lead_time, sender, local_author) = match (
+            self.config.proposer_turn_lead_time,
+            &self.observers.proposer_turn,
+            self.local_author,
+        ) {
+            (Some(lead_time), Some(sender), Some(local_author)) => {
+                (lead_time, sender.clone(), local_author)
+            }
+            _ => return,
+        };
+        let next_round = self.current_round + 1;
+        if !self.proposers(next_round).contains(&local_author) {
+            return;
+        }
+        let delay = timeout
+            .checked_sub(lead_time)
+            .unwrap_or_else(|| Duration::from_millis(0));
+        debug!(
+            "{}Local node leads round {}, notifying its turn in {} ms",
+            self.log_prefix,
+            next_round,
+            delay.as_millis()
+        );
+        let (task, handle) = CancellableTask::make(SendTask::make(sender, next_round));
+        self.proposer_turn_handle = Some(handle);
+        self.time_service.run_after(delay, task);
+    }
+
+    /// Returns the extra duration of the current round until its first local timeout, which gives
+    /// the local node a last chance to propose if it is a proposer of the round (see
+    /// `ProposerTimeoutPolicy::Delay`).  It is part of the duration of the round, hence of its
+    /// deadline, of its heartbeats and of its new round event.
+    fn proposer_timeout_delay(&self) -> Duration {
+        let delay = match self.config.proposer_timeout_policy {
+            ProposerTimeoutPolicy::TimeoutNormally => return Duration::from_millis(0),
+            ProposerTimeoutPolicy::Delay(delay) => delay,
+        };
+        let is_local_proposer = self.local_author.map_or(false, |local_author| {
+            self.proposers(self.current_round).contains(&local_author)
+        });
+        if is_local_proposer && self.last_local_timeout_round != Some(self.current_round) {
+            delay
+        } else {
+            Duration::from_millis(0)
+        }
+    }
+
+    /// Returns the index of the given round after the highest committed round, as expected by
+    /// `PacemakerTimeInterval::get_round_duration`.
+    fn round_index_after_committed_round(&self, round: Round) -> usize {
+        let round_index = if self.highest_committed_round == 0 {
+            // Genesis doesn't require the 3-chain rule for commit, hence start the index at
+            // the round after genesis.
+            round.saturating_sub(1)
+        } else if round < self.highest_committed_round + 3 {
+            0
+        } else {
+            round - self.highest_committed_round - 3
+        };
+        round_index as usize
+    }
+
     /// Setup the current round deadline and return the duration of the current round
     fn setup_deadline(&mut self) -> Duration {
-        let round_index_after_committed_round = {
-            if self.highest_committed_round == 0 {
-                // Genesis doesn't require the 3-chain rule for commit, hence start the index at
-                // the round after genesis.
-                self.current_round - 1
-            } else if self.current_round < self.highest_committed_round + 3 {
-                0
-            } else {
-                self.current_round - self.highest_committed_round - 3
-            }
-        } as usize;
-        let timeout = self
+        let round_index_after_committed_round =
+            self.round_index_after_committed_round(self.current_round);
+        let mut timeout = self
             .time_interval
-            .get_round_duration(round_index_after_committed_round);
-        self.current_round_deadline = Instant::now() + timeout;
-        timeout
+            .get_round_duration(round_index_after_committed_round)
+            + self.round_jitter();
+        if self.recovered && self.current_round == self.bootstrap_round {
+            timeout += self.config.post_recovery_grace;
+        }
+        if let Some(min_round_duration) = self.config.min_round_duration {
+            timeout = cmp::max(timeout, min_round_duration);
+        }
+        timeout += self.proposer_timeout_delay();
+        if let Some(sanity_bound) = self.config.round_duration_sanity_bound {
+            if timeout > sanity_bound {
+                warn!(
+                    "{}The duration {:?} of round {} exceeds the sanity bound, clamped to {:?}",
+                    self.log_prefix, timeout, self.current_round, sanity_bound
+                );
+                counters::ROUND_DURATION_CLAMPED_COUNT.inc();
+                timeout = sanity_bound;
+            }
+        }
+        let now = self.time_service.get_current_timestamp();
+        let start = self.anchored_round_start(now);
+        let remaining = timeout
+            .checked_sub(now - start)
+            .unwrap_or_else(|| Duration::from_millis(0));
+        self.current_round_deadline = Instant::now() + remaining;
+        self.current_round_start = start;
+        self.current_round_timeout = timeout;
+        remaining
+    }
+
+    /// Returns the start of the current round given the local time: the timestamp of the quorum
+    /// certificate the round is entered on, within `PacemakerConfig::max_round_start_skew` of the
+    /// local time, or the local time.
+    fn anchored_round_start(&mut self, now: Duration) -> Duration {
+        let (max_skew, anchor) = match (self.config.max_round_start_skew, self.round_start_anchor) {
+            (Some(max_skew), Some(anchor)) => (max_skew, anchor),
+            _ => return now,
+        };
+        self.round_start_anchor = None;
+        if anchor >= now {
+            return now;
+        }
+        let earliest = now
+            .checked_sub(max_skew)
+            .unwrap_or_else(|| Duration::from_millis(0));
+        if anchor < earliest {
+            debug!(
+                "{}The QuorumCert timestamp {:?} is more than {:?} behind the local time {:?}",
+                self.log_prefix, anchor, max_skew, now
+            );
+            return earliest;
+        }
+        anchor
+    }
+
+    /// Returns the deterministic jitter of the current round timeout in [0, max jitter]
+    fn round_jitter(&self) -> Duration {
+        let max_jitter = if self.current_round == self.bootstrap_round {
+            self.config.bootstrap_round_jitter
+        } else {
+            self.config.round_jitter
+        };
+        let max_jitter_ms = max_jitter.as_millis() as u64;
+        if max_jitter_ms == 0 {
+            return Duration::from_millis(0);
+        }
+        let mut hasher = PacemakerJitterHasher::default();
+        hasher.write(&self.config.jitter_seed.to_le_bytes());
+        if self.config.jitter_seed_policy == JitterSeedPolicy::EpochAndAuthor {
+            hasher.write(&self.epoch.to_le_bytes());
+            if let Some(local_author) = self.local_author.as_ref() {
+                hasher.write(local_author.as_ref());
+            }
+        }
+        hasher.write(&self.current_round.to_le_bytes());
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hasher.finish().to_vec()[..8]);
+        Duration::from_millis(u64::from_le_bytes(bytes) % (max_jitter_ms + 1))
     }
 
     /// Attempts to update highest_qc_certified_round when receiving QC for given round.
@@ -287,7 +1297,8 @@ impl Pacemaker {
     fn update_highest_qc_round(&mut self, round: Round) -> bool {
         if round > self.highest_qc_round {
             debug!(
-                "{}QuorumCertified at {}{}",
+                "{}{}QuorumCertified at {}{}",
+                self.log_prefix,
                 Fg(LightBlack),
                 round,
                 Fg(Reset)
@@ -301,16 +1312,32 @@ impl Pacemaker {
     /// Combines highest_qc_certified_round, highest_local_tc and highest_received_tc into
     /// effective round of this pacemaker.
     /// Generates new_round event if effective round changes and ensures it is
-    /// monotonically increasing
+    /// monotonically increasing.
+    /// When the QC and the TC are for the same round, `certificate_tie_break` picks the reason.
     fn update_current_round(&mut self) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        if self.check_storage_failure() {
+            return future::ready(()).boxed();
+        }
         let (mut best_round, mut best_reason) = (self.highest_qc_round, NewRoundReason::QCReady);
-        if let Some(highest_timeout_certificate) =
-            self.pacemaker_timeout_manager.highest_timeout_certificate()
+        if let Some(highest_timeout_certificate) = self
+            .pacemaker_timeout_manager
+            .highest_timeout_certificate()
+            .filter(|tc| !self.timeouts_suppressed(tc.round()))
         {
-            if highest_timeout_certificate.round() > best_round {
+            let tc_wins = match self.config.certificate_tie_break {
+                CertificateTieBreak::PreferQc => highest_timeout_certificate.round() > best_round,
+                CertificateTieBreak::PreferTimeout => {
+                    highest_timeout_certificate.round() >= best_round
+                }
+            };
+            if tc_wins {
                 best_round = highest_timeout_certificate.round();
                 best_reason = NewRoundReason::Timeout {
                     cert: highest_timeout_certificate.clone(),
+                    external: self
+                        .pacemaker_timeout_manager
+                        .highest_timeout_certificates()
+                        .is_highest_received(),
                 };
             }
         }
@@ -318,12 +1345,13 @@ impl Pacemaker {
         let new_round = best_round + 1;
         if self.current_round == new_round {
             debug!(
-                "{}Round did not change: {}{}",
+                "{}{}Round did not change: {}{}",
+                self.log_prefix,
                 Fg(LightBlack),
                 new_round,
                 Fg(Reset)
             );
-            return future::ready(()).boxed();
+            return self.check_certification_gap();
         }
         assert!(
             new_round > self.current_round,
@@ -331,31 +1359,493 @@ impl Pacemaker {
             self.current_round,
             new_round
         );
+        let round_end_notifications = if self.started {
+            let summary = self.round_summary(&best_reason);
+            if self.config.round_detail_sampling.samples(&summary) {
+                self.pacemaker_timeout_manager
+                    .save_round_details(summary.clone());
+            }
+            let round_summary = self.send_round_summary(summary);
+            self.record_completed_round(&best_reason);
+            if let (NewRoundReason::Timeout { .. }, Some(observer)) =
+                (&best_reason, self.observers.round_timeout_observer.as_ref())
+            {
+                for round in self.current_round..new_round {
+                    observer.round_timed_out(round);
+                }
+            }
+            let oscillation_alert = self.check_oscillation();
+            let state_sync_trigger = self.check_state_sync_trigger(&best_reason, new_round);
+            async move {
+                round_summary.await;
+                oscillation_alert.await;
+                state_sync_trigger.await;
+            }
+                .boxed()
+        } else {
+            future::ready(()).boxed()
+        };
+        let (new_round, epoch_change) = self.check_epoch_boundary(new_round);
         self.current_round = new_round;
-        self.create_new_round_task(best_reason).boxed()
+        if best_reason == NewRoundReason::QCReady {
+            self.last_qc_advance_time = Some(Instant::now());
+            self.metrics.qc_advances += 1;
+        } else {
+            self.metrics.timeout_advances += 1;
+        }
+        self.pacemaker_timeout_manager.save_current_round(new_round);
+        if let Some(catch_up) = self.catch_up.as_mut() {
+            // The event of the round is deferred to the end of the burst
+            catch_up.entered_rounds += 1;
+            catch_up.last_reason = Some(best_reason);
+            let certification_gap_alert = self.check_certification_gap();
+            return async move {
+                round_end_notifications.await;
+                epoch_change.await;
+                certification_gap_alert.await;
+            }
+                .boxed();
+        }
+        let new_round_task = self.create_new_round_task(best_reason, 0);
+        let certification_gap_alert = self.check_certification_gap();
+        async move {
+            new_round_task.await;
+            round_end_notifications.await;
+            epoch_change.await;
+            certification_gap_alert.await;
+        }
+            .boxed()
     }
 
-    /// Validate timeout certificate and update local state if it's correct
-    fn check_and_update_highest_received_tc(
+    /// Starts a new epoch if the new round reaches the boundary of
+    /// `PacemakerConfig::auto_epoch_rounds`: the rounds restart from the first round of the new
+    /// epoch, without the certificates of the previous one.  Returns the round to enter, along
+    /// with the send of the `EpochChange`.
+    fn check_epoch_boundary(
         &mut self,
-        tc: Option<&PacemakerTimeoutCertificate>,
-    ) -> bool {
-        if let Some(tc) = tc {
-            return self
-                .pacemaker_timeout_manager
-                .update_highest_received_timeout_certificate(tc);
+        new_round: Round,
+    ) -> (Round, Pin<Box<dyn Future<Output = ()> + Send>>) {
+        let epoch_rounds = match self.config.auto_epoch_rounds {
+            Some(epoch_rounds) if epoch_rounds > 1 => epoch_rounds,
+            _ => return (new_round, future::ready(()).boxed()),
+        };
+        if new_round < epoch_rounds {
+            return (new_round, future::ready(()).boxed());
         }
-        false
+        let epoch = self.epoch + 1;
+        self.start_new_epoch(epoch);
+        self.build_epoch_proposers();
+        self.highest_qc_round = 0;
+        self.highest_committed_round = 0;
+        self.highest_remote_timeout_round = 0;
+        self.last_local_timeout_round = None;
+        self.pacemaker_timeout_manager.reset_timeout_certificates();
+        info!(
+            "{}Round {} reached the boundary of epoch {}, restarting at round {}",
+            self.log_prefix, new_round, epoch, EPOCH_START_ROUND
+        );
+        let epoch_change = self.send_epoch_change(EpochChange {
+            epoch,
+            validator_set_size: self.validator_set_size.unwrap_or(0),
+            timeout_quorum_size: self.pacemaker_timeout_manager.quorum_size(),
+        });
+        (EPOCH_START_ROUND, epoch_change)
     }
 
-    /// Returns deadline for current round
-    pub fn current_round_deadline(&self) -> Instant {
-        self.current_round_deadline
+    /// Returns the `RoundSummary` of the current round, which is ending for the given reason.
+    fn round_summary(&self, reason: &NewRoundReason) -> RoundSummary {
+        let timeout_authors = match reason {
+            NewRoundReason::QCReady => vec![],
+            NewRoundReason::Timeout { cert, .. } => {
+                let mut authors: Vec<Author> = cert
+                    .timeouts()
+                    .iter()
+                    .map(PacemakerTimeout::author)
+                    .collect();
+                authors.sort();
+                authors
+            }
+        };
+        RoundSummary {
+            round: self.current_round,
+            start: self.current_round_start,
+            end: self.time_service.get_current_timestamp(),
+            reason: reason.clone(),
+            retransmit_count: self.retransmit_count(self.current_round),
+            timeout_authors,
+        }
     }
 
-    /// Synchronous function to return the current round.
-    pub fn current_round(&self) -> Round {
-        self.current_round
+    /// Sends the `RoundSummary` of the ending round.
+    fn send_round_summary(
+        &self,
+        summary: RoundSummary,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        self.send_optional(&self.observers.round_summary, summary, "round summary")
+    }
+
+    /// Counts the rounds timed out since the last advance on a quorum certificate (the current
+    /// round advancing to the new round for the given reason), and sends the `TriggerStateSync`
+    /// once they reach `PacemakerConfig::state_sync_trigger_threshold`.
+    fn check_state_sync_trigger(
+        &mut self,
+        reason: &NewRoundReason,
+        new_round: Round,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        match reason {
+            NewRoundReason::QCReady => {
+                self.timed_out_rounds_since_qc = 0;
+                self.state_sync_triggered = false;
+                return future::ready(()).boxed();
+            }
+            NewRoundReason::Timeout { .. } => {
+                self.timed_out_rounds_since_qc += new_round - self.current_round;
+            }
+        }
+        let threshold = match self.config.state_sync_trigger_threshold {
+            Some(threshold) => threshold,
+            None => return future::ready(()).boxed(),
+        };
+        if self.timed_out_rounds_since_qc < threshold || self.state_sync_triggered {
+            return future::ready(()).boxed();
+        }
+        self.state_sync_triggered = true;
+        warn!(
+            "{}{} rounds timed out without a quorum certificate (entering round {}, highest \
+             certified round {}), triggering state sync",
+            self.log_prefix, self.timed_out_rounds_since_qc, new_round, self.highest_qc_round
+        );
+        let trigger = TriggerStateSync {
+            round: new_round,
+            highest_qc_round: self.highest_qc_round,
+            timed_out_rounds: self.timed_out_rounds_since_qc,
+        };
+        self.send_optional(
+            &self.observers.state_sync_trigger,
+            trigger,
+            "state sync trigger",
+        )
+    }
+
+    /// Sends the oscillation alert once the advance reason alternated more than the threshold of
+    /// `PacemakerConfig::oscillation_alert` within its window.
+    fn check_oscillation(&mut self) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let (window, threshold) = match self.config.oscillation_alert {
+            Some(oscillation_alert) => oscillation_alert,
+            None => return future::ready(()).boxed(),
+        };
+        let recent = self.round_history.recent_timeouts(window);
+        let alternations = recent.windows(2).filter(|pair| pair[0] != pair[1]).count();
+        if alternations <= threshold {
+            self.oscillation_alerted = false;
+            return future::ready(()).boxed();
+        }
+        if self.oscillation_alerted {
+            return future::ready(()).boxed();
+        }
+        self.oscillation_alerted = true;
+        warn!(
+            "{}Advance reason alternated {} times within the last {} rounds (current round {})",
+            self.log_prefix,
+            alternations,
+            recent.len(),
+            self.current_round
+        );
+        self.send_optional(
+            &self.observers.oscillation_alert,
+            alternations,
+            "oscillation alert",
+        )
+    }
+
+    /// Applies the storage failure policy to the failed storage writes, and returns whether the
+    /// pacemaker is halted.
+    fn check_storage_failure(&mut self) -> bool {
+        if self.pacemaker_timeout_manager.take_write_failure()
+            && self.config.storage_failure_policy == StorageFailurePolicy::Halt
+        {
+            self.halt(HaltReason::StorageFailure);
+        }
+        self.is_halted()
+    }
+
+    /// Halts the pacemaker and fires the halt signal, unless it is already halted (the first
+    /// reason is kept).
+    fn halt(&mut self, reason: HaltReason) {
+        if self.halt_reason.is_some() {
+            return;
+        }
+        error!(
+            "{}Halting the pacemaker at round {}: {:?}",
+            self.log_prefix, self.current_round, reason
+        );
+        self.halt_reason = Some(reason);
+        self.observers.notify_halt(reason, &self.log_prefix);
+    }
+
+    /// Tracks how long the certification gap stays above the alert threshold, and sends the gap
+    /// on the alert channel once it stayed above for the configured period.  The alert is sent
+    /// once until the gap goes back to the threshold.
+    fn check_certification_gap(&mut self) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let (threshold, period) = match self.config.certification_gap_alert {
+            Some(certification_gap_alert) => certification_gap_alert,
+            None => return future::ready(()).boxed(),
+        };
+        let gap = self.certification_gap();
+        if gap <= threshold {
+            self.certification_gap_exceeded_since = None;
+            self.certification_gap_alerted = false;
+            return future::ready(()).boxed();
+        }
+        let now = self.time_service.get_current_timestamp();
+        let since = *self.certification_gap_exceeded_since.get_or_insert(now);
+        let sustained = now
+            .checked_sub(since)
+            .map_or(false, |elapsed| elapsed >= period);
+        if !sustained || self.certification_gap_alerted {
+            return future::ready(()).boxed();
+        }
+        self.certification_gap_alerted = true;
+        warn!(
+            "{}Certification gap of {} rounds (current round {}, highest certified round {})",
+            self.log_prefix, gap, self.current_round, self.highest_qc_round
+        );
+        self.send_optional(
+            &self.observers.certification_gap_alert,
+            gap,
+            "certification gap alert",
+        )
+    }
+
+    /// Validate timeout certificate and update local state if it's correct
+    fn check_and_update_highest_received_tc(
+        &mut self,
+        tc: Option<&PacemakerTimeoutCertificate>,
+    ) -> bool {
+        if let Some(tc) = tc {
+            let updated = self
+                .pacemaker_timeout_manager
+                .update_highest_received_timeout_certificate(tc);
+            if let Some(conflict_proof) = self.pacemaker_timeout_manager.take_conflict_proof() {
+                error!(
+                    "{}Received a timeout certificate conflicting with the highest one: {:?}",
+                    self.log_prefix, conflict_proof
+                );
+                counters::CONFLICTING_TIMEOUT_PROOF_COUNT.inc();
+                self.metrics.conflicting_timeouts += 1;
+            }
+            if updated {
+                self.notify_timeout_certificate_waiters();
+            }
+            return updated;
+        }
+        false
+    }
+
+    /// Returns a future resolved with the highest timeout certificate once one is formed (or
+    /// received) for the given round or a higher one: a higher certificate may form without the
+    /// certificate of the round itself.  It is resolved immediately if such a certificate is
+    /// already known, and never if the pacemaker is dropped before.
+    #[cfg(test)]
+    pub fn await_timeout_certificate(
+        &mut self,
+        round: Round,
+    ) -> Pin<Box<dyn Future<Output = PacemakerTimeoutCertificate> + Send>> {
+        if let Some(tc) = self.pacemaker_timeout_manager.highest_timeout_certificate() {
+            if tc.round() >= round {
+                return future::ready(tc.clone()).boxed();
+            }
+        }
+        let (sender, receiver) = oneshot::channel();
+        self.timeout_certificate_waiters.push((round, sender));
+        receiver
+            .then(|tc| match tc {
+                Ok(tc) => future::ready(tc).left_future(),
+                Err(_) => future::pending().right_future(),
+            })
+            .boxed()
+    }
+
+    /// Resolves the futures of `await_timeout_certificate` satisfied by the highest timeout
+    /// certificate.
+    fn notify_timeout_certificate_waiters(&mut self) {
+        let tc = match self.pacemaker_timeout_manager.highest_timeout_certificate() {
+            Some(tc) => tc.clone(),
+            None => return,
+        };
+        let (satisfied, pending): (Vec<_>, Vec<_>) = self
+            .timeout_certificate_waiters
+            .drain(..)
+            .partition(|(round, _)| *round <= tc.round());
+        self.timeout_certificate_waiters = pending;
+        for (round, sender) in satisfied {
+            if sender.send(tc.clone()).is_err() {
+                debug!(
+                    "{}Timeout certificate of round {} no longer awaited",
+                    self.log_prefix, round
+                );
+            }
+        }
+    }
+
+    /// Returns deadline for current round
+    pub fn current_round_deadline(&self) -> Instant {
+        self.current_round_deadline
+    }
+
+    /// Returns the time of the last round advance driven by a quorum certificate (see
+    /// `process_certificates`), if any: the rounds advanced by the timeout certificates do not
+    /// count.  A large gap since then indicates that the network is not certifying blocks.
+    #[cfg(test)]
+    pub fn last_qc_advance_time(&self) -> Option<Instant> {
+        self.last_qc_advance_time
+    }
+
+    /// Returns whether the pacemaker halted (see `HaltReason`).
+    pub fn is_halted(&self) -> bool {
+        self.halt_reason.is_some()
+    }
+
+    /// Returns the reason of the halt of the pacemaker, if it halted.
+    #[cfg(test)]
+    pub fn halt_reason(&self) -> Option<HaltReason> {
+        self.halt_reason
+    }
+
+    /// Captures the round and timeout state of the pacemaker.
+    #[cfg(test)]
+    pub fn snapshot(&self) -> PacemakerSnapshot {
+        PacemakerSnapshot {
+            current_round: self.current_round,
+            highest_qc_round: self.highest_qc_round,
+            highest_committed_round: self.highest_committed_round,
+            highest_timeout_certificates: self
+                .pacemaker_timeout_manager
+                .highest_timeout_certificates()
+                .clone(),
+            received_timeouts: self.pacemaker_timeout_manager.received_timeouts(),
+            timeout_quorum_size: self.pacemaker_timeout_manager.quorum_size(),
+            last_local_timeout_round: self.last_local_timeout_round,
+            epoch: self.epoch,
+            timeouts_sent_in_epoch: self.timeouts_sent_in_epoch,
+            processed_inputs: self.processed_inputs,
+            highest_remote_timeout_round: self.highest_remote_timeout_round,
+        }
+    }
+
+    /// Reconstructs a pacemaker in the state of the snapshot.  Like `PacemakerBuilder::build`, it
+    /// has no side effects until `start` is called.  The received timeouts of the snapshot are
+    /// screened by epoch like the remote timeouts: the ones of another epoch than the snapshot are
+    /// dropped.
+    #[cfg(test)]
+    pub fn from_snapshot(
+        snapshot: PacemakerSnapshot,
+        persistent_liveness_storage: Box<dyn PersistentLivenessStorage>,
+        time_interval: Box<dyn PacemakerTimeInterval>,
+        time_service: Arc<dyn TimeService>,
+        new_round_events_sender: channel::Sender<NewRoundEvent>,
+        timeout_sender: channel::Sender<Round>,
+        config: PacemakerConfig,
+    ) -> Self {
+        let mut pacemaker = PacemakerBuilder::new(
+            persistent_liveness_storage,
+            time_interval,
+            time_service,
+            new_round_events_sender,
+            timeout_sender,
+            snapshot.timeout_quorum_size,
+        )
+        .highest_committed_round(snapshot.highest_committed_round)
+        .highest_qc_round(snapshot.highest_qc_round)
+        .highest_timeout_certificates(snapshot.highest_timeout_certificates)
+        .config(config)
+        .build();
+        pacemaker.current_round = cmp::max(pacemaker.current_round, snapshot.current_round);
+        pacemaker.bootstrap_round = pacemaker.current_round;
+        pacemaker.recovered = pacemaker.current_round > 1;
+        pacemaker.last_local_timeout_round = snapshot.last_local_timeout_round;
+        pacemaker.epoch = snapshot.epoch;
+        pacemaker.timeouts_sent_in_epoch = snapshot.timeouts_sent_in_epoch;
+        pacemaker.processed_inputs = snapshot.processed_inputs;
+        pacemaker.highest_remote_timeout_round = snapshot.highest_remote_timeout_round;
+        for timeout in snapshot.received_timeouts {
+            if timeout.epoch() != pacemaker.epoch {
+                TimeoutIngestResult::WrongEpoch.counter().inc();
+                debug!(
+                    "{}Dropping restored timeout of {} for round {}: {:?}",
+                    pacemaker.log_prefix,
+                    timeout.author().short_str(),
+                    timeout.round(),
+                    TimeoutIngestResult::WrongEpoch
+                );
+                continue;
+            }
+            pacemaker
+                .pacemaker_timeout_manager
+                .update_received_timeout(timeout);
+        }
+        pacemaker
+    }
+
+    /// Returns the timeouts pending a timeout certificate (the highest timeout received from each
+    /// author) grouped by round, sorted by author within a round.  The state is left unchanged.
+    #[cfg(test)]
+    pub fn export_pending_timeouts(&self) -> PendingTimeoutsSnapshot {
+        let mut snapshot = PendingTimeoutsSnapshot::default();
+        for timeout in self.pacemaker_timeout_manager.received_timeouts() {
+            snapshot
+                .timeouts
+                .entry(timeout.round())
+                .or_insert_with(Vec::new)
+                .push(timeout);
+        }
+        for timeouts in snapshot.timeouts.values_mut() {
+            timeouts.sort_by_key(PacemakerTimeout::author);
+        }
+        snapshot
+    }
+
+    /// Adds the exported pending timeouts to the ones of this pacemaker, lowest rounds first: the
+    /// timeouts are not verified again, and the timeouts of an author lower than the one already
+    /// held are ignored.  The round advances if the imported timeouts
+    /// form a timeout certificate.
+    #[cfg(test)]
+    pub fn import_pending_timeouts(
+        &mut self,
+        snapshot: PendingTimeoutsSnapshot,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        debug!(
+            "{}Importing {} pending timeouts",
+            self.log_prefix,
+            snapshot.len()
+        );
+        for timeout in snapshot
+            .timeouts
+            .into_iter()
+            .flat_map(|(_, timeouts)| timeouts)
+        {
+            self.pacemaker_timeout_manager
+                .update_received_timeout(timeout);
+        }
+        self.update_current_round()
+    }
+
+    /// Returns the round of the highest quorum certificate.
+    #[cfg(test)]
+    pub fn highest_certified_round(&self) -> Round {
+        self.highest_qc_round
+    }
+
+    /// Synchronous function to return the current round.
+    pub fn current_round(&self) -> Round {
+        self.current_round
+    }
+
+    /// Returns the number of rounds between the highest certified round and the current round,
+    /// which is 1 while the rounds advance on quorum certificates.  A persistently large gap
+    /// indicates that the rounds time out without being certified.
+    pub fn certification_gap(&self) -> Round {
+        self.current_round.saturating_sub(self.highest_qc_round)
     }
 
     /// Return a optional reference to the highest timeout certificate (locally generated or
@@ -366,55 +1856,1446 @@ impl Pacemaker {
             .cloned()
     }
 
+    /// Installs the highest timeout certificate received after startup (e.g., from a peer by a
+    /// recovering node), which advances the current round if the certificate justifies a higher
+    /// one.  The certificate is verified against the validator verifier if set, and it must be
+    /// higher than the highest known timeout certificate.
+    #[cfg(test)]
+    pub fn install_highest_timeout_certificate(
+        &mut self,
+        timeout_certificate: PacemakerTimeoutCertificate,
+    ) -> Result<Pin<Box<dyn Future<Output = ()> + Send>>, PacemakerError> {
+        if let Some(validator_verifier) = self.validator_verifier.as_ref() {
+            timeout_certificate
+                .verify(validator_verifier)
+                .map_err(PacemakerError::InvalidTimeoutCertificate)?;
+        }
+        if let Some(timeout) = timeout_certificate
+            .timeouts()
+            .iter()
+            .find(|timeout| timeout.epoch() != self.epoch)
+        {
+            return Err(PacemakerError::TimeoutCertificateOfOtherEpoch {
+                epoch: timeout.epoch(),
+            });
+        }
+        let highest_round = self
+            .pacemaker_timeout_manager
+            .highest_timeout_certificate()
+            .map_or(0, PacemakerTimeoutCertificate::round);
+        if timeout_certificate.round() <= highest_round {
+            return Err(PacemakerError::StaleTimeoutCertificate {
+                round: timeout_certificate.round(),
+                highest_round,
+            });
+        }
+        Ok(self.process_certificates(self.highest_qc_round, None, Some(&timeout_certificate)))
+    }
+
+    /// Returns how many more timeouts of distinct authors are required to form the timeout
+    /// certificate of the given round: the received timeouts of the given round (or higher) count
+    /// toward the quorum.  Returns 0 once the quorum is reached.
+    #[cfg(test)]
+    pub fn timeouts_needed_for_quorum(&self, round: Round) -> usize {
+        self.pacemaker_timeout_manager.quorum_size().saturating_sub(
+            self.pacemaker_timeout_manager
+                .received_timeouts_count(round),
+        )
+    }
+
+    /// Returns the total voting power of the authors whose timeouts for the given round (or higher)
+    /// were received, e.g., to show the share of the voting power that timed out (see
+    /// `set_voting_power_quorum`, every author counts for 1 if the voting powers are not set).
+    #[cfg(test)]
+    pub fn pending_timeout_power(&self, round: Round) -> u128 {
+        self.pacemaker_timeout_manager
+            .received_timeouts_power(round)
+    }
+
+    /// Returns the timeout certificate of the given round, e.g., to justify a proposal, if the
+    /// pacemaker holds one.  Only the highest certificates are held (see
+    /// `PacemakerTimeoutManager::highest_timeout_certificates`): the certificates of the lower
+    /// rounds are dropped once superseded.
+    #[cfg(test)]
+    pub fn timeout_certificate(&self, round: Round) -> Option<PacemakerTimeoutCertificate> {
+        self.pacemaker_timeout_manager
+            .timeout_certificate(round)
+            .cloned()
+    }
+
+    /// Answers the request of a peer (e.g., catching up) for the timeout certificate of the given
+    /// round: returns the certificate in its wire (protobuf) encoding if the pacemaker holds one
+    /// (see `timeout_certificate`).  The requests are logged.
+    #[cfg(test)]
+    pub fn serve_timeout_certificate(&self, round: Round) -> failure::Result<Option<Vec<u8>>> {
+        let timeout_certificate = match self.pacemaker_timeout_manager.timeout_certificate(round) {
+            Some(timeout_certificate) => timeout_certificate,
+            None => {
+                debug!(
+                    "{}Requested the timeout certificate of round {}, which is not held",
+                    self.log_prefix, round
+                );
+                return Ok(None);
+            }
+        };
+        debug!(
+            "{}Serving the timeout certificate of round {}",
+            self.log_prefix, round
+        );
+        Ok(Some(timeout_certificate.clone().into_proto_bytes()?))
+    }
+
+    /// Return the rounds, in increasing order, for which the pacemaker holds a timeout certificate
+    /// or is accumulating timeouts.  A recovering node can use them to decide what to request
+    /// from its peers.
+    #[cfg(test)]
+    pub fn timeout_certificate_rounds(&self) -> Vec<Round> {
+        self.pacemaker_timeout_manager.timeout_certificate_rounds()
+    }
+
     /// Function to update current round based on received certificates.
     /// Both round of latest received QC and timeout certificates are taken into account.
     /// This function guarantees to update pacemaker state when promise that it returns is fulfilled
+    ///
+    /// A late QC for a round the pacemaker already advanced past on a timeout certificate updates
+    /// the highest certified round, but emits no new round event: the round already advanced.
     pub fn process_certificates(
         &mut self,
         qc_round: Round,
         highest_committed_round: Option<Round>,
         timeout_certificate: Option<&PacemakerTimeoutCertificate>,
     ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        if !self.consume_input_budget() {
+            return future::ready(()).boxed();
+        }
+        self.apply_certificates(qc_round, highest_committed_round, timeout_certificate)
+    }
+
+    fn apply_certificates(
+        &mut self,
+        qc_round: Round,
+        highest_committed_round: Option<Round>,
+        timeout_certificate: Option<&PacemakerTimeoutCertificate>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        self.assert_not_stalled();
+        self.record_input(|| PacemakerInput::Certificates {
+            qc_round,
+            highest_committed_round,
+            timeout_certificate: timeout_certificate.cloned(),
+        });
+        let (timeout_certificate, conflicting_tc_alert) =
+            self.screen_timeout_certificate(timeout_certificate);
         let tc_round_updated = self.check_and_update_highest_received_tc(timeout_certificate);
-        let qc_round_updated = self.update_highest_qc_round(qc_round);
+        let exceeds_max_qc_advance = self.config.max_qc_advance.map_or(false, |max_advance| {
+            qc_round >= self.current_round.saturating_add(max_advance)
+        });
+        let exceeds_max_qc_age = self.config.max_qc_age.map_or(false, |max_age| {
+            qc_round.saturating_add(max_age) < self.current_round
+        });
+        let qc_round_updated = if exceeds_max_qc_advance {
+            warn!(
+                "{}QuorumCert of round {} is too far ahead of round {}, requesting state sync",
+                self.log_prefix, qc_round, self.current_round
+            );
+            false
+        } else if exceeds_max_qc_age {
+            debug!(
+                "{}Dropping QuorumCert of round {}, too far below round {}",
+                self.log_prefix, qc_round, self.current_round
+            );
+            counters::OLD_QC_DROPPED_COUNT.inc();
+            false
+        } else {
+            self.update_highest_qc_round(qc_round)
+        };
+        if qc_round_updated && qc_round < self.current_round {
+            debug!(
+                "{}QuorumCert of round {} arrived after round {} was reached on a timeout \
+                 certificate, only recording it",
+                self.log_prefix, qc_round, self.current_round
+            );
+        }
         match highest_committed_round {
             Some(commit_round) if (commit_round > self.highest_committed_round) => {
                 self.highest_committed_round = commit_round;
             }
             _ => (),
         }
-        if tc_round_updated || qc_round_updated {
-            return self.update_current_round();
+        let new_round_processed = if tc_round_updated || qc_round_updated {
+            self.update_current_round()
+        } else {
+            future::ready(()).boxed()
+        };
+        let state_sync_requested = if exceeds_max_qc_advance {
+            self.request_state_sync(qc_round)
+        } else {
+            future::ready(()).boxed()
+        };
+        async move {
+            new_round_processed.await;
+            state_sync_requested.await;
+            conflicting_tc_alert.await;
+        }
+            .boxed()
+    }
+
+    /// Detects a received timeout certificate for the round of the highest quorum certificate
+    /// (only the highest one is held), and returns the certificate to process according to
+    /// `PacemakerConfig::conflicting_tc_policy` along with the alert to send.
+    fn screen_timeout_certificate<'a>(
+        &self,
+        timeout_certificate: Option<&'a PacemakerTimeoutCertificate>,
+    ) -> (
+        Option<&'a PacemakerTimeoutCertificate>,
+        Pin<Box<dyn Future<Output = ()> + Send>>,
+    ) {
+        let round = match timeout_certificate {
+            Some(tc) if self.highest_qc_round > 0 && tc.round() == self.highest_qc_round => {
+                tc.round()
+            }
+            _ => return (timeout_certificate, future::ready(()).boxed()),
+        };
+        counters::CONFLICTING_TIMEOUT_CERTIFICATE_COUNT.inc();
+        error!(
+            "{}Received a timeout certificate for round {}, which is certified by the highest \
+             QuorumCert ({:?})",
+            self.log_prefix, round, self.config.conflicting_tc_policy
+        );
+        let timeout_certificate = match self.config.conflicting_tc_policy {
+            ConflictingTcPolicy::Process => timeout_certificate,
+            ConflictingTcPolicy::Drop => None,
+        };
+        let alert = self.send_optional(
+            &self.observers.conflicting_tc_alert,
+            round,
+            "conflicting timeout certificate alert",
+        );
+        (timeout_certificate, alert)
+    }
+
+    /// Starts a catch-up burst, typically before feeding the many sequential quorum certificates
+    /// of a node catching up from far behind: the rounds entered until `end_catch_up` emit no new
+    /// round event (nor schedule their timeout), only the last of them does at the end of the
+    /// burst.  Starting a burst while one is in progress has no effect.
+    #[cfg(test)]
+    pub fn begin_catch_up(&mut self) {
+        self.record_input(|| PacemakerInput::BeginCatchUp);
+        if self.catch_up.is_none() {
+            self.catch_up = Some(CatchUp::default());
+        }
+    }
+
+    /// Ends the catch-up burst: the new round event of the last round entered during the burst
+    /// (if any) is emitted, with the number of the other rounds entered in `skipped`.
+    #[cfg(test)]
+    pub fn end_catch_up(&mut self) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        self.record_input(|| PacemakerInput::EndCatchUp);
+        let catch_up = match self.catch_up.take() {
+            Some(catch_up) => catch_up,
+            None => return future::ready(()).boxed(),
+        };
+        match catch_up.last_reason {
+            Some(reason) => {
+                debug!(
+                    "{}Caught up to round {}, skipped the events of {} rounds",
+                    self.log_prefix,
+                    self.current_round,
+                    catch_up.entered_rounds - 1
+                );
+                self.create_new_round_task(reason, catch_up.entered_rounds - 1)
+                    .boxed()
+            }
+            None => future::ready(()).boxed(),
+        }
+    }
+
+    /// Processes the inputs received in the same processing cycle (e.g., drained from the event
+    /// loop channels) in the order given by `PacemakerConfig::batch_ordering`, and returns the
+    /// rounds whose local timeout is to be broadcast (see `process_local_timeout`).
+    #[cfg(test)]
+    pub fn process_batch(
+        &mut self,
+        mut inputs: Vec<PacemakerInput>,
+    ) -> Pin<Box<dyn Future<Output = Vec<Round>> + Send>> {
+        if self.config.batch_ordering == BatchOrdering::LocalTimeoutsLast {
+            // The sort is stable: the inputs keep their order otherwise
+            inputs.sort_by_key(|input| match input {
+                PacemakerInput::LocalTimeout(_) => 1,
+                _ => 0,
+            });
+        }
+        let mut tasks = vec![];
+        let mut broadcast_rounds = vec![];
+        for input in inputs {
+            match input {
+                PacemakerInput::Start => tasks.push(self.start()),
+                PacemakerInput::Certificates {
+                    qc_round,
+                    highest_committed_round,
+                    timeout_certificate,
+                } => tasks.push(self.process_certificates(
+                    qc_round,
+                    highest_committed_round,
+                    timeout_certificate.as_ref(),
+                )),
+                PacemakerInput::RemoteTimeout(timeout) => {
+                    tasks.push(self.process_remote_timeout(timeout).map(|_| ()).boxed())
+                }
+                PacemakerInput::LocalTimeout(round) => {
+                    if self.process_local_timeout(round) {
+                        broadcast_rounds.push(round);
+                    }
+                }
+                PacemakerInput::BeginCatchUp => self.begin_catch_up(),
+                PacemakerInput::EndCatchUp => tasks.push(self.end_catch_up()),
+            }
+        }
+        async move {
+            for task in tasks {
+                task.await;
+            }
+            broadcast_rounds
+        }
+            .boxed()
+    }
+
+    /// Signals on the state sync channel that the given QC round must be reached by state sync.
+    fn request_state_sync(&self, qc_round: Round) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        if self.observers.state_sync.is_none() {
+            warn!(
+                "{}No state sync channel to request the sync to round {}",
+                self.log_prefix, qc_round
+            );
+        }
+        self.send_optional(&self.observers.state_sync, qc_round, "state sync request")
+    }
+
+    /// Same as `process_certificates` for the round of the given quorum certificate, unless the
+    /// QcValidator rejects it: the quorum certificate (and the commit it implies) is then ignored.
+    pub fn process_quorum_cert(
+        &mut self,
+        qc: &QuorumCert,
+        highest_committed_round: Option<Round>,
+        timeout_certificate: Option<&PacemakerTimeoutCertificate>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        if !self.qc_validator.is_valid(qc) {
+            warn!(
+                "{}QuorumCert {} is rejected by {:?}",
+                self.log_prefix, qc, self.qc_validator
+            );
+            return self.process_certificates(self.highest_qc_round, None, timeout_certificate);
+        }
+        if self.config.max_round_start_skew.is_some() {
+            self.round_start_anchor = Some(Duration::from_micros(
+                qc.ledger_info().ledger_info().timestamp_usecs(),
+            ));
+        }
+        let process = self.process_certificates(
+            qc.certified_block_round(),
+            highest_committed_round,
+            timeout_certificate,
+        );
+        // The anchor only applies to the round entered on this quorum certificate, if any
+        self.round_start_anchor = None;
+        process
+    }
+
+    /// Same as `process_quorum_cert` for the quorum certificate of a block proposed by the given
+    /// author, which is cross-checked against the proposer election (see
+    /// `set_proposer_election`): a mismatch is reported, and handled per
+    /// `PacemakerConfig::proposer_mismatch_policy`.  No check without a proposer election.
+    #[cfg(test)]
+    pub fn process_proposal_quorum_cert(
+        &mut self,
+        qc: &QuorumCert,
+        proposer: Author,
+        highest_committed_round: Option<Round>,
+        timeout_certificate: Option<&PacemakerTimeoutCertificate>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let round = qc.certified_block_round();
+        let expected_proposers = self.proposers(round);
+        if !self.epoch_proposers.is_set() || expected_proposers.contains(&proposer) {
+            return self.process_quorum_cert(qc, highest_committed_round, timeout_certificate);
+        }
+        counters::PROPOSER_MISMATCH_COUNT.inc();
+        warn!(
+            "{}Block of round {} proposed by {}, the proposer election elects {:?} ({:?})",
+            self.log_prefix,
+            round,
+            proposer.short_str(),
+            expected_proposers,
+            self.config.proposer_mismatch_policy
+        );
+        let certificates_processed = match self.config.proposer_mismatch_policy {
+            ProposerMismatchPolicy::Advance => {
+                self.process_quorum_cert(qc, highest_committed_round, timeout_certificate)
+            }
+            ProposerMismatchPolicy::IgnoreQc => {
+                self.process_certificates(self.highest_qc_round, None, timeout_certificate)
+            }
+        };
+        let mismatch = ProposerMismatch {
+            round,
+            proposer,
+            expected_proposers,
+        };
+        let mismatch_sent = self.send_optional(
+            &self.observers.proposer_mismatch,
+            mismatch,
+            "proposer mismatch",
+        );
+        async move {
+            certificates_processed.await;
+            mismatch_sent.await;
         }
-        future::ready(()).boxed()
+            .boxed()
     }
 
     /// The function is invoked upon receiving a remote timeout message from another validator.
+    /// The returned future resolves to the outcome of the ingestion of the timeout.
     pub fn process_remote_timeout(
         &mut self,
         pacemaker_timeout: PacemakerTimeout,
-    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
-        if self
-            .pacemaker_timeout_manager
-            .update_received_timeout(pacemaker_timeout)
+    ) -> Pin<Box<dyn Future<Output = TimeoutIngestResult> + Send>> {
+        self.process_remote_timeout_with_attached_qc(pacemaker_timeout, None)
+    }
+
+    /// Processes the quorum certificate the TimeoutQcExtractor extracts from the remote timeout
+    /// (if any) before the timeout itself.
+    fn process_remote_timeout_with_attached_qc(
+        &mut self,
+        pacemaker_timeout: PacemakerTimeout,
+        attached_qc: Option<&QuorumCert>,
+    ) -> Pin<Box<dyn Future<Output = TimeoutIngestResult> + Send>> {
+        if !self.consume_input_budget() {
+            let result = TimeoutIngestResult::BudgetExhausted;
+            result.counter().inc();
+            return future::ready(result).boxed();
+        }
+        self.assert_not_stalled();
+        match self
+            .timeout_qc_extractor
+            .extract_qc(&pacemaker_timeout, attached_qc)
         {
-            return self.update_current_round();
+            Some(quorum_cert) => self.process_timeout_and_qc(pacemaker_timeout, &quorum_cert),
+            None => self.process_timeout_without_qc(pacemaker_timeout),
         }
-        future::ready(()).boxed()
     }
 
-    /// To process the local round timeout triggered by TimeService and return whether it's the
-    /// current round.
-    pub fn process_local_timeout(&mut self, round: Round) -> bool {
-        if round != self.current_round {
-            return false;
+    fn process_timeout_without_qc(
+        &mut self,
+        pacemaker_timeout: PacemakerTimeout,
+    ) -> Pin<Box<dyn Future<Output = TimeoutIngestResult> + Send>> {
+        self.record_input(|| PacemakerInput::RemoteTimeout(pacemaker_timeout.clone()));
+        let result = self.ingest_remote_timeout(&pacemaker_timeout);
+        result.counter().inc();
+        if result == TimeoutIngestResult::SelfEcho {
+            return future::ready(result).boxed();
         }
-        warn!(
-            "Round {} has timed out, broadcasting new round message to all replicas",
-            round
+        if result != TimeoutIngestResult::Accepted {
+            debug!(
+                "{}Dropping timeout of {} for round {}: {:?}",
+                self.log_prefix,
+                pacemaker_timeout.author().short_str(),
+                pacemaker_timeout.round(),
+                result
+            );
+            return future::ready(result).boxed();
+        }
+        let attached_tc = if self.config.attach_highest_timeout_certificate {
+            pacemaker_timeout.highest_timeout_certificate()
+        } else {
+            None
+        };
+        let (attached_tc, conflicting_tc_alert) = self.screen_timeout_certificate(attached_tc);
+        let tc_round_updated = self.check_and_update_highest_received_tc(attached_tc);
+        if Some(pacemaker_timeout.author()) != self.local_author {
+            self.highest_remote_timeout_round =
+                cmp::max(self.highest_remote_timeout_round, pacemaker_timeout.round());
+        }
+        if pacemaker_timeout.round() >= self.current_round {
+            self.observers
+                .open_span(PacemakerSpan::TimeoutCertificateFormation(
+                    self.current_round,
+                ));
+        }
+        let timeout_updated = self
+            .pacemaker_timeout_manager
+            .update_received_timeout(pacemaker_timeout);
+        if timeout_updated {
+            self.notify_timeout_certificate_waiters();
+        }
+        let new_round_processed = if tc_round_updated || timeout_updated {
+            self.update_current_round()
+        } else {
+            future::ready(()).boxed()
+        };
+        self.check_early_timeout();
+        async move {
+            new_round_processed.await;
+            conflicting_tc_alert.await;
+            result
+        }
+            .boxed()
+    }
+
+    /// Triggers the local timeout of the current round without waiting for its timer once the
+    /// configured fraction of the timeout quorum timed out at the current round (or higher).
+    fn check_early_timeout(&mut self) {
+        let (numerator, denominator) = match self.config.early_timeout_fraction {
+            Some(fraction) => fraction,
+            None => return,
+        };
+        let round = self.current_round;
+        if self.early_timeout_round == Some(round) || self.last_local_timeout_round == Some(round) {
+            return;
+        }
+        let quorum_size = self.pacemaker_timeout_manager.quorum_size();
+        let threshold = (quorum_size * numerator + denominator - 1) / denominator;
+        if self
+            .pacemaker_timeout_manager
+            .received_timeouts_count(round)
+            < threshold
+        {
+            return;
+        }
+        debug!(
+            "{}Triggering the local timeout of round {} early",
+            self.log_prefix, round
+        );
+        self.early_timeout_round = Some(round);
+        self.time_service.run_after(
+            Duration::from_millis(0),
+            SendTask::make(self.timeout_sender.clone(), round),
+        );
+    }
+
+    /// Reports the verification of the remote timeout started at the given time if it took longer
+    /// than `PacemakerConfig::slow_verification_threshold`, which is a sign of CPU contention.
+    fn check_verification_time(
+        &self,
+        verification_start: Duration,
+        pacemaker_timeout: &PacemakerTimeout,
+    ) {
+        let threshold = match self.config.slow_verification_threshold {
+            Some(threshold) => threshold,
+            None => return,
+        };
+        let elapsed = self
+            .time_service
+            .get_current_timestamp()
+            .checked_sub(verification_start)
+            .unwrap_or_else(|| Duration::from_millis(0));
+        if elapsed > threshold {
+            counters::SLOW_TIMEOUT_VERIFICATION_COUNT.inc();
+            warn!(
+                "{}Verifying the timeout of {} for round {} took {:?}",
+                self.log_prefix,
+                pacemaker_timeout.author().short_str(),
+                pacemaker_timeout.round(),
+                elapsed
+            );
+        }
+    }
+
+    /// Same as `process_remote_timeout` for a timeout that comes with a quorum certificate (e.g.,
+    /// the highest quorum certificate of its author), which is processed first as by
+    /// `process_quorum_cert`.  If the QcValidator rejects the quorum certificate, the timeout is
+    /// handled according to `PacemakerConfig::bad_qc_policy`.
+    #[cfg(test)]
+    pub fn process_remote_timeout_with_qc(
+        &mut self,
+        pacemaker_timeout: PacemakerTimeout,
+        quorum_cert: &QuorumCert,
+    ) -> Pin<Box<dyn Future<Output = TimeoutIngestResult> + Send>> {
+        self.process_remote_timeout_with_attached_qc(pacemaker_timeout, Some(quorum_cert))
+    }
+
+    fn process_timeout_and_qc(
+        &mut self,
+        pacemaker_timeout: PacemakerTimeout,
+        quorum_cert: &QuorumCert,
+    ) -> Pin<Box<dyn Future<Output = TimeoutIngestResult> + Send>> {
+        if !self.is_plausible_qc_timestamp(quorum_cert) {
+            warn!(
+                "{}QuorumCert {} attached to the timeout of {} is timestamped too far ahead",
+                self.log_prefix,
+                quorum_cert,
+                pacemaker_timeout.author().short_str()
+            );
+            let result = TimeoutIngestResult::ImplausibleQcTimestamp;
+            result.counter().inc();
+            return future::ready(result).boxed();
+        }
+        if self.qc_validator.is_valid(quorum_cert) {
+            let certificates_processed =
+                self.apply_certificates(quorum_cert.certified_block_round(), None, None);
+            let timeout_processed = self.process_timeout_without_qc(pacemaker_timeout);
+            return async move {
+                certificates_processed.await;
+                timeout_processed.await
+            }
+                .boxed();
+        }
+        warn!(
+            "{}QuorumCert {} attached to the timeout of {} is rejected by {:?}",
+            self.log_prefix,
+            quorum_cert,
+            pacemaker_timeout.author().short_str(),
+            self.qc_validator
         );
+        match self.config.bad_qc_policy {
+            BadQcPolicy::DropTimeout => {
+                let result = TimeoutIngestResult::BadQuorumCert;
+                result.counter().inc();
+                future::ready(result).boxed()
+            }
+            BadQcPolicy::IgnoreQc => self.process_timeout_without_qc(pacemaker_timeout),
+        }
+    }
+
+    /// Returns whether the timestamp of the quorum certificate is within
+    /// `PacemakerConfig::max_qc_timestamp_skew` of the local clock.
+    fn is_plausible_qc_timestamp(&self, quorum_cert: &QuorumCert) -> bool {
+        match self.config.max_qc_timestamp_skew {
+            Some(max_skew) => {
+                let timestamp = Duration::from_micros(
+                    quorum_cert.ledger_info().ledger_info().timestamp_usecs(),
+                );
+                timestamp <= self.time_service.get_current_timestamp() + max_skew
+            }
+            None => true,
+        }
+    }
+
+    /// Decides whether the remote timeout is accepted or the reason why it is dropped.
+    fn ingest_remote_timeout(&self, pacemaker_timeout: &PacemakerTimeout) -> TimeoutIngestResult {
+        if let Some(validator_verifier) = self.validator_verifier.as_ref() {
+            let verification_start = self.time_service.get_current_timestamp();
+            let verification = pacemaker_timeout.verify(validator_verifier);
+            self.check_verification_time(verification_start, pacemaker_timeout);
+            match verification {
+                Ok(()) => (),
+                Err(TimeoutVerificationError::SigVerifyError(VerifyError::UnknownAuthor)) => {
+                    return TimeoutIngestResult::UnknownAuthor;
+                }
+                Err(_) => return TimeoutIngestResult::BadSignature,
+            }
+        }
+        if pacemaker_timeout.epoch() != self.epoch {
+            return TimeoutIngestResult::WrongEpoch;
+        }
+        let round = pacemaker_timeout.round();
+        if round < self.current_round && self.local_author == Some(pacemaker_timeout.author()) {
+            return TimeoutIngestResult::SelfEcho;
+        }
+        if round <= self.highest_committed_round {
+            return TimeoutIngestResult::CommittedRound;
+        }
+        if round.saturating_add(self.config.past_timeout_rounds) < self.current_round {
+            return TimeoutIngestResult::StaleRound;
+        }
+        if let Some(max_future_rounds) = self.config.max_future_timeout_rounds {
+            // The round may be in the past window (see past_timeout_rounds)
+            if round.saturating_sub(self.current_round) > max_future_rounds {
+                return TimeoutIngestResult::FutureRound;
+            }
+        }
+        if let Some(prev_round) = self
+            .pacemaker_timeout_manager
+            .received_timeout_round(pacemaker_timeout.author())
+        {
+            if prev_round >= round {
+                return TimeoutIngestResult::Duplicate;
+            }
+        }
+        if let Some(max_authors) = self.config.max_timeout_authors_per_round {
+            if self
+                .pacemaker_timeout_manager
+                .received_timeouts_at_round(round)
+                >= max_authors
+            {
+                return TimeoutIngestResult::TooManyAuthors;
+            }
+        }
+        TimeoutIngestResult::Accepted
+    }
+
+    /// To process the local round timeout triggered by TimeService and return whether a timeout
+    /// should be broadcast for the current round.
+    pub fn process_local_timeout(&mut self, round: Round) -> bool {
+        if !self.consume_input_budget() {
+            return false;
+        }
+        self.assert_not_stalled();
+        self.record_input(|| PacemakerInput::LocalTimeout(round));
+        let broadcast = self.decide_local_timeout(round);
+        if broadcast {
+            self.record_output(|| PacemakerOutput::BroadcastTimeout(round));
+        }
+        broadcast
+    }
+
+    fn decide_local_timeout(&mut self, round: Round) -> bool {
+        if round != self.current_round || self.check_storage_failure() {
+            return false;
+        }
+        self.check_timer_lag(round);
+        self.last_local_timeout_round = Some(round);
+        self.observers.open_span(PacemakerSpan::Timeout(round));
+        let coalescing_window = self.config.timeout_coalescing_window;
+        if coalescing_window > Duration::from_millis(0)
+            && !self.config.manual_advance
+            && self.pending_coalesced_timeout != Some(round)
+        {
+            // The same round is reported again at the end of the window, unless the node has
+            // moved to a higher round in the meantime.
+            self.pending_coalesced_timeout = Some(round);
+            self.time_service.run_after(
+                coalescing_window,
+                SendTask::make(self.timeout_sender.clone(), round),
+            );
+            return false;
+        }
+        self.pending_coalesced_timeout = None;
         counters::TIMEOUT_COUNT.inc();
+        self.metrics.local_timeouts += 1;
+        self.check_fast_clock(round);
         self.setup_timeout();
+        if self.in_startup_grace_period() {
+            warn!(
+                "{}Round {} has timed out during the startup grace period, not broadcasting it",
+                self.log_prefix, round
+            );
+            return false;
+        }
+        if self.timeouts_suppressed(round) {
+            warn!(
+                "{}Round {} has timed out, timeouts are suppressed until round {:?}",
+                self.log_prefix, round, self.config.timeout_suppressed_until_round
+            );
+            return false;
+        }
+        if let Some(max_timeouts) = self.config.max_timeouts_per_epoch {
+            if self.timeouts_sent_in_epoch >= max_timeouts {
+                warn!(
+                    "{}Round {} has timed out, but {} timeouts were already broadcast in epoch {}",
+                    self.log_prefix, round, max_timeouts, self.epoch
+                );
+                return false;
+            }
+        }
+        warn!(
+            "{}Round {} has timed out, broadcasting new round message to all replicas",
+            self.log_prefix, round
+        );
+        self.timeouts_sent_in_epoch += 1;
+        self.timeout_broadcasts_in_round += 1;
+        true
+    }
+
+    /// Measures the delay between the expected end of the current round and its local timeout,
+    /// and logs it if above `PacemakerConfig::timer_lag_warn_threshold`.  The end of a coalescing
+    /// window (see `PacemakerConfig::timeout_coalescing_window`) is not measured again, nor are
+    /// the manual advances.
+    fn check_timer_lag(&self, round: Round) {
+        let threshold = match self.config.timer_lag_warn_threshold {
+            Some(threshold) => threshold,
+            None => return,
+        };
+        if self.config.manual_advance || self.pending_coalesced_timeout == Some(round) {
+            return;
+        }
+        let expected = self.current_round_timeout;
+        let elapsed = self
+            .time_service
+            .get_current_timestamp()
+            .checked_sub(self.current_round_start)
+            .unwrap_or_else(|| Duration::from_millis(0));
+        let lag_ms = elapsed.as_millis() as i64 - expected.as_millis() as i64;
+        counters::TIMER_LAG_MS.observe(lag_ms as f64);
+        if lag_ms > threshold.as_millis() as i64 {
+            warn!(
+                "{}Round {} timed out {} ms after its expected duration of {:?}, the timer task \
+                 may be starved",
+                self.log_prefix, round, lag_ms, expected
+            );
+        }
+    }
+
+    /// Records whether the local timeout of the round fired before any remote timeout of the
+    /// round, and sends the fast clock alert once it did for at least the threshold of
+    /// `PacemakerConfig::fast_clock_alert` within its window.  The alert is sent without waiting,
+    /// as the local timeouts are processed synchronously: it is dropped if the channel is full.
+    fn check_fast_clock(&mut self, round: Round) {
+        let (window, threshold) = match self.config.fast_clock_alert {
+            Some(fast_clock_alert) => fast_clock_alert,
+            None => return,
+        };
+        if self.local_timeouts_first.len() >= window {
+            self.local_timeouts_first.pop_front();
+        }
+        self.local_timeouts_first
+            .push_back(self.highest_remote_timeout_round < round);
+        let local_first = self
+            .local_timeouts_first
+            .iter()
+            .filter(|local_first| **local_first)
+            .count();
+        if local_first < threshold {
+            self.fast_clock_alerted = false;
+            return;
+        }
+        if self.fast_clock_alerted {
+            return;
+        }
+        self.fast_clock_alerted = true;
+        warn!(
+            "{}{} of the last {} local timeouts fired before any remote one (round {}), the clock \
+             may be fast",
+            self.log_prefix,
+            local_first,
+            self.local_timeouts_first.len(),
+            round
+        );
+        if let Some(sender) = self.observers.fast_clock_alert.as_mut() {
+            if let Err(e) = sender.try_send(local_first) {
+                debug!(
+                    "{}Error in sending fast clock alert: {:?}",
+                    self.log_prefix, e
+                );
+            }
+        }
+    }
+
+    /// Counts an input against `PacemakerConfig::input_budget`, and returns whether it may be
+    /// processed.
+    fn consume_input_budget(&mut self) -> bool {
+        if let Some(input_budget) = self.config.input_budget {
+            if self.processed_inputs >= input_budget {
+                debug!(
+                    "{}Input budget of {} inputs exhausted, rejecting the input",
+                    self.log_prefix, input_budget
+                );
+                return false;
+            }
+        }
+        self.processed_inputs += 1;
         true
     }
+
+    /// Returns the final state of the pacemaker once the inputs of `PacemakerConfig::input_budget`
+    /// are processed, None before (or without a budget).
+    #[cfg(test)]
+    pub fn final_snapshot(&self) -> Option<PacemakerSnapshot> {
+        match self.config.input_budget {
+            Some(input_budget) if self.processed_inputs >= input_budget => Some(self.snapshot()),
+            _ => None,
+        }
+    }
+
+    /// Returns how many times the local timeout of the given round was retransmitted (i.e.,
+    /// broadcast again after its first broadcast) so far.  The count is reset once the round
+    /// advances: it is 0 for any round other than the current one.
+    pub fn retransmit_count(&self, round: Round) -> u32 {
+        if round == self.current_round {
+            self.timeout_broadcasts_in_round.saturating_sub(1)
+        } else {
+            0
+        }
+    }
+
+    /// Asserts (in debug builds) that the round advanced within
+    /// `PacemakerConfig::max_stall_interval`.
+    fn assert_not_stalled(&self) {
+        if let (Some(max_stall_interval), Some(last_advance_time)) =
+            (self.config.max_stall_interval, self.last_advance_time)
+        {
+            let stall = self
+                .time_service
+                .get_current_timestamp()
+                .checked_sub(last_advance_time)
+                .unwrap_or_else(|| Duration::from_millis(0));
+            debug_assert!(
+                stall <= max_stall_interval,
+                "{}Pacemaker stalled at round {} for {:?} (max {:?}): highest QC round {}, \
+                 highest committed round {}, highest timeout certificate round {:?}, \
+                 last local timeout round {:?}, halted {}",
+                self.log_prefix,
+                self.current_round,
+                stall,
+                max_stall_interval,
+                self.highest_qc_round,
+                self.highest_committed_round,
+                self.pacemaker_timeout_manager
+                    .highest_timeout_certificate()
+                    .map(|tc| tc.round()),
+                self.last_local_timeout_round,
+                self.is_halted()
+            );
+        }
+    }
+
+    /// Returns whether the pacemaker is within `PacemakerConfig::startup_grace_period` since it
+    /// started.
+    fn in_startup_grace_period(&self) -> bool {
+        match self.start_time {
+            Some(start_time) => {
+                self.time_service.get_current_timestamp()
+                    < start_time + self.config.startup_grace_period
+            }
+            None => false,
+        }
+    }
+
+    /// Returns whether the timeouts of the given round are suppressed, see
+    /// `PacemakerConfig::timeout_suppressed_until_round`.
+    fn timeouts_suppressed(&self, round: Round) -> bool {
+        self.config
+            .timeout_suppressed_until_round
+            .map_or(false, |until_round| round < until_round)
+    }
+
+    /// Triggers the local timeout of the given round as its timer would (see
+    /// `PacemakerConfig::manual_advance`): the round is sent on the timeout channel.
+    #[cfg(test)]
+    pub fn advance_on_timeout(&self, round: Round) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let mut sender = self.timeout_sender.clone();
+        let log_prefix = self.log_prefix.clone();
+        async move {
+            if let Err(e) = sender.send(round).await {
+                debug!("{}Error in sending the local timeout: {:?}", log_prefix, e);
+            }
+        }
+            .boxed()
+    }
+
+    /// Expires the timer of the current round immediately, whatever the clock: the current round
+    /// is sent on the timeout channel as its timer would.
+    #[cfg(test)]
+    pub fn expire_current_round_timer(&self) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        self.advance_on_timeout(self.current_round)
+    }
+
+    /// Returns the remaining time until the timeout of the current round according to the time
+    /// service, None if the pacemaker is not started.  The remaining time saturates at zero, and a
+    /// clock reporting a time before the start of the round is considered expired: the elapsed
+    /// time cannot be trusted anymore, so the round should rather time out promptly.
+    #[cfg(test)]
+    pub fn time_until_timeout(&self) -> Option<Duration> {
+        if !self.started {
+            return None;
+        }
+        let now = self.time_service.get_current_timestamp();
+        let remaining = now
+            .checked_sub(self.current_round_start)
+            .and_then(|elapsed| self.current_round_timeout.checked_sub(elapsed))
+            .unwrap_or_else(|| Duration::from_millis(0));
+        Some(remaining)
+    }
+
+    /// Returns the description of the time interval of the current round.
+    #[cfg(test)]
+    pub fn describe_time_interval(&self) -> IntervalDescription {
+        self.time_interval.describe()
+    }
+
+    /// Returns the given number of rounds from the current one on, each with the duration the
+    /// time interval gives it (without jitter, and assuming no further commit), which helps
+    /// visualizing the backoff.  A time interval set by `set_time_interval` applies from the next
+    /// round on.
+    #[cfg(test)]
+    pub fn projected_schedule(&self, count: usize) -> Vec<(Round, Duration)> {
+        let next_time_interval = self
+            .pending_time_interval
+            .as_ref()
+            .unwrap_or(&self.time_interval);
+        (self.current_round..)
+            .take(count)
+            .map(|round| {
+                let time_interval = if round == self.current_round {
+                    &self.time_interval
+                } else {
+                    next_time_interval
+                };
+                let round_index = self.round_index_after_committed_round(round);
+                (round, time_interval.get_round_duration(round_index))
+            })
+            .collect()
+    }
+
+    /// Returns the number of rounds per second estimated from the average duration of the recent
+    /// rounds (see `PacemakerConfig::round_rate_ewma_alpha`), or None if no round completed yet
+    /// (or they completed instantly).
+    #[cfg(test)]
+    pub fn estimated_round_rate(&self) -> Option<f64> {
+        self.round_history
+            .estimated_round_rate(self.config.round_rate_ewma_alpha)
+    }
+
+    /// Returns the reasons of the last (at most) `count` round advances, the oldest first, e.g.,
+    /// for a monitor to compute the ratio of the rounds ending in a timeout.  Only the last
+    /// ROUND_HISTORY_SIZE advances are kept.
+    #[cfg(test)]
+    pub fn recent_reasons(&self, count: usize) -> Vec<NewRoundReason> {
+        self.round_history.recent_reasons(count)
+    }
+
+    /// Returns the timestamp of the time service the given round started at, for the current
+    /// round and the completed rounds still in the round history (the last ROUND_HISTORY_SIZE
+    /// advances), e.g., for an explorer to correlate the rounds with the wall clock.  None for the
+    /// older rounds, and for the rounds skipped by an advance.
+    ///
+    /// It is a timestamp rather than an `Instant`: the rounds are started according to the time
+    /// service, whose clock is simulated in tests and may be anchored to the timestamps of the
+    /// quorum certificates (see `PacemakerConfig::max_round_start_skew`), neither of which can be
+    /// represented by an `Instant` of the local monotonic clock.
+    #[cfg(test)]
+    pub fn round_start_timestamp(&self, round: Round) -> Option<Duration> {
+        if self.started && round == self.current_round {
+            return Some(self.current_round_start);
+        }
+        self.round_history
+            .find(round)
+            .map(|completed_round| completed_round.start)
+    }
+
+    /// Returns the counts of the events of this pacemaker since its creation or the last
+    /// `reset_metrics`.
+    #[cfg(test)]
+    pub fn metrics_snapshot(&self) -> PacemakerMetrics {
+        self.metrics
+    }
+
+    /// Resets the counts of `metrics_snapshot`.  The global counters are not affected.
+    #[cfg(test)]
+    pub fn reset_metrics(&mut self) {
+        self.metrics = PacemakerMetrics::default();
+    }
+
+    /// Returns the reason the given round was exited for, for the completed rounds still in the
+    /// round history (the last ROUND_HISTORY_SIZE advances), e.g., to audit the provenance of the
+    /// blocks.  None for the current round, the older rounds, and the rounds skipped by an advance.
+    #[cfg(test)]
+    pub fn advance_reason(&self, round: Round) -> Option<NewRoundReason> {
+        self.round_history
+            .find(round)
+            .map(|completed_round| completed_round.reason.clone())
+    }
+
+    /// Appends the current round (which is completing for the given reason) to the round history.
+    fn record_completed_round(&mut self, reason: &NewRoundReason) {
+        let duration = self
+            .time_service
+            .get_current_timestamp()
+            .checked_sub(self.current_round_start)
+            .unwrap_or_else(|| Duration::from_millis(0));
+        self.round_history.push(CompletedRound {
+            round: self.current_round,
+            start: self.current_round_start,
+            duration,
+            reason: reason.clone(),
+        });
+    }
+
+    /// Returns the progress of the given round as observed by this pacemaker.
+    #[cfg(test)]
+    pub fn round_status(&self, round: Round) -> RoundStatus {
+        if round <= self.highest_qc_round {
+            return RoundStatus::Certified;
+        }
+        let highest_tc_round = self
+            .pacemaker_timeout_manager
+            .highest_timeout_certificate()
+            .map(PacemakerTimeoutCertificate::round);
+        if highest_tc_round.map_or(false, |tc_round| round <= tc_round) {
+            RoundStatus::TimeoutCertificateFormed
+        } else if self.last_local_timeout_round == Some(round) {
+            RoundStatus::LocalTimeout
+        } else {
+            RoundStatus::Active
+        }
+    }
+
+    /// Sets the verifier of the remote timeouts: the timeouts that fail the verification are
+    /// dropped as `TimeoutIngestResult::BadSignature` or `TimeoutIngestResult::UnknownAuthor`.
+    #[cfg(test)]
+    pub fn set_validator_verifier(
+        &mut self,
+        validator_verifier: Arc<ValidatorVerifier<Ed25519PublicKey>>,
+    ) {
+        self.validator_verifier = Some(validator_verifier);
+    }
+
+    /// Sets the voting powers of the authors and the voting power of a quorum: the timeout
+    /// certificates are then formed by stake rather than by the timeout quorum size.
+    #[cfg(test)]
+    pub fn set_voting_power_quorum(
+        &mut self,
+        voting_powers: HashMap<Author, u64>,
+        quorum_voting_power: u64,
+    ) {
+        self.pacemaker_timeout_manager
+            .set_voting_power_quorum(voting_powers, quorum_voting_power);
+    }
+
+    /// Sets the proposer election the pacemaker reports the proposers of the rounds from (see
+    /// `proposers`).
+    pub fn set_proposer_election<T: 'static>(
+        &mut self,
+        proposer_election: Box<dyn ProposerElection<T> + Send + Sync>,
+    ) {
+        self.epoch_proposers
+            .set_proposer_election(proposer_election);
+    }
+
+    /// Sets the factory building the proposer election of each new epoch from the epoch and its
+    /// validator set, invoked on each epoch change (see `reconfigure`, and
+    /// `PacemakerConfig::auto_epoch_rounds` if the validator set is known) in place of
+    /// `set_proposer_election`.  The proposer election of a `ReconfigParams` takes precedence.
+    #[cfg(test)]
+    pub fn set_proposer_election_factory<T: 'static, F>(&mut self, factory: F)
+    where
+        F: Fn(
+                u64,
+                &ValidatorVerifier<Ed25519PublicKey>,
+            ) -> Box<dyn ProposerElection<T> + Send + Sync>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.epoch_proposers.set_factory(factory);
+    }
+
+    /// Builds the proposer election of the current epoch with the factory, if any, and the known
+    /// validator set.
+    fn build_epoch_proposers(&mut self) {
+        let validator_verifier = match self.validator_verifier.as_ref() {
+            Some(validator_verifier) if self.epoch_proposers.has_factory() => validator_verifier,
+            _ => return,
+        };
+        debug!(
+            "{}Building the proposer election of epoch {}",
+            self.log_prefix, self.epoch
+        );
+        self.epoch_proposers.build(self.epoch, validator_verifier);
+    }
+
+    /// Sets the author of the local node, which lets the pacemaker know the rounds it is a
+    /// proposer of.
+    pub fn set_local_author(&mut self, local_author: Author) {
+        self.local_author = Some(local_author);
+    }
+
+    /// Returns the channels and observers of the events of the pacemaker, to subscribe to them.
+    #[cfg(test)]
+    pub fn observers_mut(&mut self) -> &mut PacemakerObservers {
+        &mut self.observers
+    }
+
+    /// Returns the valid proposers of the given round, none if no proposer election is set.
+    pub fn proposers(&self, round: Round) -> Vec<Author> {
+        self.epoch_proposers.proposers(round)
+    }
+
+    /// Returns whether the local timeout of the given round already fired (only the last timed
+    /// out round is tracked), which lets the callers avoid broadcasting or counting it twice.
+    #[cfg(test)]
+    pub fn has_local_timeout(&self, round: Round) -> bool {
+        self.last_local_timeout_round == Some(round)
+    }
+
+    /// Sets the time service used while the clock of the current one is implausible (see
+    /// `FallbackTimeService`), which guards against the failure of a single clock source.  The
+    /// receipt times of the timeouts are read from the same clock.
+    #[cfg(test)]
+    pub fn set_secondary_time_service(&mut self, secondary_time_service: Arc<dyn TimeService>) {
+        self.time_service = Arc::new(FallbackTimeService::new(
+            self.time_service.clone(),
+            secondary_time_service,
+        ));
+        self.pacemaker_timeout_manager
+            .set_time_service(self.time_service.clone());
+    }
+
+    /// Sets the transport of the local timeouts used by `broadcast_local_timeout`.
+    #[cfg(test)]
+    pub fn set_timeout_broadcaster(&mut self, timeout_broadcaster: Arc<dyn TimeoutBroadcaster>) {
+        self.timeout_broadcaster = Some(timeout_broadcaster);
+    }
+
+    /// Returns the validators no timeout of the given round (or of a higher one) was received
+    /// from, except the local node, to which the retransmissions of the local timeout are the
+    /// most useful.  None if the validators are unknown (see `set_validator_verifier`).
+    #[cfg(test)]
+    pub fn retransmission_targets(&self, round: Round) -> Option<Vec<Author>> {
+        let validator_verifier = self.validator_verifier.as_ref()?;
+        Some(
+            validator_verifier
+                .get_ordered_account_addresses()
+                .into_iter()
+                .filter(|author| Some(*author) != self.local_author)
+                .filter(|author| {
+                    self.pacemaker_timeout_manager
+                        .received_timeout_round(*author)
+                        .map_or(true, |received_round| received_round < round)
+                })
+                .collect(),
+        )
+    }
+
+    /// Broadcasts the local timeout through the TimeoutBroadcaster.  It is expected for a round
+    /// that timed out locally (see `process_local_timeout`), other timeouts are not broadcast.
+    /// With `PacemakerConfig::targeted_retransmission`, the retransmissions are only sent to the
+    /// `retransmission_targets`.  The `PacemakerConfig::outgoing_timeout_filter` applies first.
+    #[cfg(test)]
+    pub fn broadcast_local_timeout(
+        &self,
+        pacemaker_timeout: PacemakerTimeout,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        if !self.has_local_timeout(pacemaker_timeout.round()) {
+            warn!(
+                "{}Round {} did not time out locally, its timeout is not broadcast",
+                self.log_prefix,
+                pacemaker_timeout.round()
+            );
+            return future::ready(()).boxed();
+        }
+        let round = pacemaker_timeout.round();
+        let pacemaker_timeout = match self.config.outgoing_timeout_filter.as_ref() {
+            Some(filter) => match filter.filter(pacemaker_timeout) {
+                Some(pacemaker_timeout) => pacemaker_timeout,
+                None => {
+                    debug!(
+                        "{}The timeout of round {} is dropped by the outgoing timeout filter",
+                        self.log_prefix, round
+                    );
+                    return future::ready(()).boxed();
+                }
+            },
+            None => pacemaker_timeout,
+        };
+        let targets = if self.config.targeted_retransmission && self.retransmit_count(round) > 0 {
+            self.retransmission_targets(round)
+        } else {
+            None
+        };
+        match (self.timeout_broadcaster.as_ref(), targets) {
+            (Some(timeout_broadcaster), Some(targets)) => {
+                debug!(
+                    "{}Retransmitting the timeout of round {} to {} validators",
+                    self.log_prefix,
+                    round,
+                    targets.len()
+                );
+                timeout_broadcaster.send_to(pacemaker_timeout, targets)
+            }
+            (Some(timeout_broadcaster), None) => timeout_broadcaster.broadcast(pacemaker_timeout),
+            (None, _) => {
+                warn!(
+                    "{}No TimeoutBroadcaster to broadcast the timeout of round {}",
+                    self.log_prefix,
+                    pacemaker_timeout.round()
+                );
+                future::ready(()).boxed()
+            }
+        }
+    }
+
+    /// Returns the label of the local validator, see `PacemakerConfig::validator_label`
+    #[cfg(test)]
+    pub fn validator_label(&self) -> Option<&str> {
+        self.config.validator_label.as_ref().map(String::as_str)
+    }
+
+    /// Returns the prefix of the log messages of the pacemaker: the validator label in brackets,
+    /// or nothing if the validator is not labeled.
+    #[cfg(test)]
+    pub fn log_prefix(&self) -> &str {
+        &self.log_prefix
+    }
+
+    /// Returns the current epoch
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Returns the minimum quorum of timeouts to generate a timeout certificate.
+    #[cfg(test)]
+    pub fn quorum_size(&self) -> usize {
+        self.pacemaker_timeout_manager.quorum_size()
+    }
+
+    /// Returns the formation latency of the last timeout certificate generated from the received
+    /// timeouts, if measured.
+    #[cfg(test)]
+    pub fn last_formation_latency(&self) -> Option<Duration> {
+        self.pacemaker_timeout_manager.last_formation_latency()
+    }
+
+    /// Recomputes the quorum of timeouts from the configured `QuorumSpec` for a validator set of
+    /// the given size.  The pacemaker halts with `HaltReason::EmptyValidatorSet` if the validator
+    /// set is empty.
+    #[cfg(test)]
+    pub fn update_quorum_size(&mut self, validator_set_size: usize) {
+        if validator_set_size == 0 {
+            self.halt(HaltReason::EmptyValidatorSet);
+            return;
+        }
+        self.validator_set_size = Some(validator_set_size);
+        if let Some(quorum_spec) = self.config.quorum_spec {
+            self.pacemaker_timeout_manager
+                .update_quorum_size(quorum_spec.quorum_size(validator_set_size));
+        }
+    }
+
+    /// Returns the configured quorum of timeouts together with the effective one, which is not
+    /// obvious once a fractional `QuorumSpec` is combined with a minimum number of signers.
+    #[cfg(test)]
+    pub fn quorum_info(&self) -> QuorumInfo {
+        let quorum_size = self.pacemaker_timeout_manager.quorum_size();
+        let min_signers = self.config.timeout_certificate_min_signers;
+        QuorumInfo {
+            spec: self.config.quorum_spec,
+            validator_set_size: self.validator_set_size,
+            quorum_size,
+            min_signers,
+            effective_threshold: cmp::max(quorum_size, min_signers),
+        }
+    }
+
+    /// Replaces the time interval (after the same self-check as `PacemakerBuilder::try_build`).
+    /// The new interval applies from the next round on: the timeout of the current round is
+    /// unaffected.
+    #[cfg(test)]
+    pub fn set_time_interval(
+        &mut self,
+        time_interval: Box<dyn PacemakerTimeInterval>,
+    ) -> Result<(), PacemakerError> {
+        validate_time_interval(time_interval.as_ref())?;
+        self.pending_time_interval = Some(time_interval);
+        Ok(())
+    }
+
+    /// Returns the highest timeout certificate to attach to the local timeouts, if configured.
+    pub fn timeout_certificate_to_attach(&self) -> Option<PacemakerTimeoutCertificate> {
+        if self.config.attach_highest_timeout_certificate {
+            self.highest_timeout_certificate()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the decision log if `record_decision_log` is configured.
+    #[cfg(test)]
+    pub fn decision_log(&self) -> Option<&PacemakerDecisionLog> {
+        self.decision_log.as_ref()
+    }
+
+    fn record_input<F: FnOnce() -> PacemakerInput>(&mut self, input: F) {
+        if let Some(decision_log) = self.decision_log.as_mut() {
+            decision_log.record_input(self.time_service.get_current_timestamp(), input());
+        }
+    }
+
+    fn record_output<F: FnOnce() -> PacemakerOutput>(&mut self, output: F) {
+        if let Some(decision_log) = self.decision_log.as_mut() {
+            decision_log.record_output(output());
+        }
+    }
+
+    /// Moves the pacemaker to a new epoch, which resets the per epoch limits and drops the
+    /// timeouts received in the previous epoch.
+    pub fn start_new_epoch(&mut self, epoch: u64) {
+        assert!(
+            epoch > self.epoch,
+            "Epoch illegally decreased from {} to {}",
+            self.epoch,
+            epoch
+        );
+        self.epoch = epoch;
+        self.timeouts_sent_in_epoch = 0;
+        self.pacemaker_timeout_manager.clear_received_timeouts();
+    }
+
+    /// Applies the new epoch, validator set, quorum of timeouts, time interval and proposer
+    /// election of a reconfiguration at once, then sends a single `EpochChange`.  Nothing is
+    /// applied if the epoch is not higher than the current one or if the new time interval fails
+    /// the self-check of `PacemakerBuilder::try_build`.  The pacemaker halts with
+    /// `HaltReason::EmptyValidatorSet` instead if the new validator set is empty.
+    #[cfg(test)]
+    pub fn reconfigure<T: 'static>(
+        &mut self,
+        params: ReconfigParams<T>,
+    ) -> Result<Pin<Box<dyn Future<Output = ()> + Send>>, PacemakerError> {
+        if params.epoch <= self.epoch {
+            return Err(PacemakerError::StaleEpoch {
+                epoch: params.epoch,
+                current_epoch: self.epoch,
+            });
+        }
+        if let Some(time_interval) = params.time_interval.as_ref() {
+            validate_time_interval(time_interval.as_ref())?;
+        }
+        let validator_set_size = params.validator_verifier.len();
+        if validator_set_size == 0 {
+            self.halt(HaltReason::EmptyValidatorSet);
+            return Ok(future::ready(()).boxed());
+        }
+        let timeout_quorum_size = match self.config.quorum_spec {
+            Some(quorum_spec) => quorum_spec.quorum_size(validator_set_size),
+            None => params.validator_verifier.quorum_size(),
+        };
+        self.start_new_epoch(params.epoch);
+        self.pacemaker_timeout_manager
+            .update_quorum_size(timeout_quorum_size);
+        self.validator_set_size = Some(validator_set_size);
+        self.validator_verifier = Some(params.validator_verifier);
+        if let Some(time_interval) = params.time_interval {
+            self.pending_time_interval = Some(time_interval);
+        }
+        match params.proposer_election {
+            Some(proposer_election) => self.set_proposer_election(proposer_election),
+            None => self.build_epoch_proposers(),
+        }
+        info!(
+            "{}Reconfigured the pacemaker for epoch {} with {} validators (timeout quorum {})",
+            self.log_prefix, params.epoch, validator_set_size, timeout_quorum_size
+        );
+        Ok(self.send_epoch_change(EpochChange {
+            epoch: params.epoch,
+            validator_set_size,
+            timeout_quorum_size,
+        }))
+    }
+
+    fn send_epoch_change(
+        &self,
+        epoch_change: EpochChange,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        self.send_optional(&self.observers.epoch_change, epoch_change, "epoch change")
+    }
+
+    /// Sends the event on the given channel if it is set (e.g., one of the optional alerts), the
+    /// future is fulfilled once the event is sent.  The failure to send it is logged along with
+    /// its description.
+    fn send_optional<E: Send + 'static>(
+        &self,
+        sender: &Option<channel::Sender<E>>,
+        event: E,
+        what: &str,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let mut sender = match sender.clone() {
+            Some(sender) => sender,
+            None => return future::ready(()).boxed(),
+        };
+        let log_prefix = self.log_prefix.clone();
+        let what = what.to_string();
+        async move {
+            if let Err(e) = sender.send(event).await {
+                debug!("{}Error in sending {}: {:?}", log_prefix, what, e);
+            }
+        }
+            .boxed()
+    }
 }
@@ -0,0 +1,525 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::Round,
+    consensus_types::{quorum_cert::QuorumCert, timeout_msg::PacemakerTimeout},
+    liveness::{pacemaker::NewRoundReason, pacemaker_observers::RoundSummary},
+};
+use futures::Future;
+use std::{fmt, pin::Pin, sync::Arc, time::Duration};
+
+/// Decides whether a quorum certificate may advance the round of the pacemaker, which allows
+/// test harnesses and consensus variants to apply stricter rules than the regular verification.
+pub trait QcValidator: fmt::Debug + Send + Sync {
+    fn is_valid(&self, qc: &QuorumCert) -> bool;
+}
+
+/// The default QcValidator: any quorum certificate advances the round.
+#[derive(Debug)]
+pub struct AcceptAnyQcValidator;
+
+impl QcValidator for AcceptAnyQcValidator {
+    fn is_valid(&self, _qc: &QuorumCert) -> bool {
+        true
+    }
+}
+
+/// Executor onto which the pacemaker spawns the deliveries of its NewRoundEvents (see
+/// `PacemakerConfig::event_executor`), which decouples them from the processing of the inputs.
+pub trait EventExecutor: fmt::Debug + Send + Sync {
+    fn spawn(&self, task: Pin<Box<dyn Future<Output = ()> + Send>>);
+}
+
+/// Inspects the local timeouts before they are broadcast (see
+/// `PacemakerConfig::outgoing_timeout_filter`) and returns the timeout to broadcast, which may be
+/// modified (e.g., to add envelope metadata, or to simulate a Byzantine node in tests), or None
+/// to drop it.
+pub trait OutgoingTimeoutFilter: fmt::Debug + Send + Sync {
+    fn filter(&self, timeout: PacemakerTimeout) -> Option<PacemakerTimeout>;
+}
+
+/// Extracts the quorum certificate that comes with a remote timeout, which lets the consensus
+/// variants that attach different metadata to their timeouts reuse the pacemaker.
+pub trait TimeoutQcExtractor: fmt::Debug + Send + Sync {
+    /// Returns the quorum certificate of the timeout, given the one that came alongside it (see
+    /// `Pacemaker::process_remote_timeout_with_qc`), if any.
+    fn extract_qc(
+        &self,
+        pacemaker_timeout: &PacemakerTimeout,
+        attached_qc: Option<&QuorumCert>,
+    ) -> Option<QuorumCert>;
+}
+
+/// The default TimeoutQcExtractor: the standard optional quorum certificate that comes alongside
+/// the timeout.
+#[derive(Debug)]
+pub struct AttachedQcExtractor;
+
+impl TimeoutQcExtractor for AttachedQcExtractor {
+    fn extract_qc(
+        &self,
+        _pacemaker_timeout: &PacemakerTimeout,
+        attached_qc: Option<&QuorumCert>,
+    ) -> Option<QuorumCert> {
+        attached_qc.cloned()
+    }
+}
+
+/// The quorum of timeouts required to generate a timeout certificate.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QuorumSpec {
+    /// A fixed number of validators
+    Count(usize),
+    /// More than `num / den` of the validators (e.g., 2f+1 out of 3f+1 for 2/3)
+    Fraction { num: usize, den: usize },
+}
+
+impl QuorumSpec {
+    /// Returns the number of validators forming a quorum in a validator set of the given size.
+    pub fn quorum_size(&self, validator_set_size: usize) -> usize {
+        match *self {
+            QuorumSpec::Count(count) => count,
+            QuorumSpec::Fraction { num, den } => {
+                assert!(den > 0, "QuorumSpec fraction with a zero denominator");
+                validator_set_size * num / den + 1
+            }
+        }
+    }
+}
+
+/// Handling of a remote timeout that comes with a quorum certificate rejected by the QcValidator.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BadQcPolicy {
+    /// The whole timeout is dropped
+    DropTimeout,
+    /// The timeout is processed as if it came without the quorum certificate
+    IgnoreQc,
+}
+
+impl Default for BadQcPolicy {
+    fn default() -> Self {
+        BadQcPolicy::DropTimeout
+    }
+}
+
+/// Handling of a received timeout certificate for the round of the highest quorum certificate:
+/// the round both succeeded and timed out, which is worth an alert (see
+/// `PacemakerObservers::conflicting_tc_alert`) whatever the handling.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConflictingTcPolicy {
+    /// The timeout certificate is processed as usual
+    Process,
+    /// The timeout certificate is dropped
+    Drop,
+}
+
+impl Default for ConflictingTcPolicy {
+    fn default() -> Self {
+        ConflictingTcPolicy::Process
+    }
+}
+
+/// Handling of a quorum certificate whose block was proposed by an author the proposer election
+/// does not elect for its round (see `Pacemaker::process_proposal_quorum_cert`).  The mismatch is
+/// reported whatever the handling (see `PacemakerObservers::proposer_mismatch`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProposerMismatchPolicy {
+    /// The quorum certificate advances the round as usual
+    Advance,
+    /// The quorum certificate (and the commit it implies) is ignored
+    IgnoreQc,
+}
+
+impl Default for ProposerMismatchPolicy {
+    fn default() -> Self {
+        ProposerMismatchPolicy::Advance
+    }
+}
+
+/// Local timeout of a round the local node is a proposer of (see `Pacemaker::set_local_author`
+/// and `Pacemaker::set_proposer_election`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProposerTimeoutPolicy {
+    /// The proposer times out like the other nodes
+    TimeoutNormally,
+    /// The first local timeout of the round is delayed by the given duration, which gives the
+    /// proposer a last chance to propose
+    Delay(Duration),
+}
+
+impl Default for ProposerTimeoutPolicy {
+    fn default() -> Self {
+        ProposerTimeoutPolicy::TimeoutNormally
+    }
+}
+
+/// Inputs of the deterministic round jitter, besides the round.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JitterSeedPolicy {
+    /// The jitter only depends on `PacemakerConfig::jitter_seed`
+    Static,
+    /// The jitter also depends on the current epoch and on the local author (see
+    /// `Pacemaker::set_local_author`): it is reproducible within an epoch, but its pattern
+    /// changes at the epoch boundaries so that it cannot be predicted in the long term
+    EpochAndAuthor,
+}
+
+impl Default for JitterSeedPolicy {
+    fn default() -> Self {
+        JitterSeedPolicy::Static
+    }
+}
+
+/// Handling of the failures of the critical storage writes (the timeout certificates).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StorageFailurePolicy {
+    /// The pacemaker halts: the round does not advance anymore and no local timeout is sent,
+    /// which avoids equivocating after a restart from the stale storage
+    Halt,
+    /// The failure is logged and the pacemaker proceeds, which keeps it available
+    Continue,
+}
+
+impl Default for StorageFailurePolicy {
+    fn default() -> Self {
+        StorageFailurePolicy::Halt
+    }
+}
+
+/// Retries of the failed critical storage writes before the `StorageFailurePolicy` applies: a
+/// failed write is attempted again up to `max_retries` times, after a delay of `backoff` doubled
+/// on every retry.  The retries are scheduled on the time service, so the event loop does not
+/// wait for them.  The default does not retry.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Returns the delay before the given retry (starting at 1), saturating on overflow.
+    pub fn delay_before(&self, retry: u32) -> Duration {
+        let factor = 2u32
+            .checked_pow(retry.saturating_sub(1))
+            .unwrap_or(std::u32::MAX);
+        self.backoff
+            .checked_mul(factor)
+            .unwrap_or_else(|| Duration::from_secs(std::u64::MAX))
+    }
+}
+
+/// Rounds whose `RoundSummary` is persisted as detailed state (see
+/// `PersistentLivenessStorage::save_round_details`) besides the current round, which is always
+/// persisted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RoundDetailSampling {
+    /// No detailed state is persisted
+    Never,
+    /// The detailed state of every round is persisted
+    EveryRound,
+    /// The detailed state of the rounds multiple of the given period is persisted
+    EveryNthRound(u64),
+    /// The detailed state of the rounds ending on a timeout certificate is persisted
+    TimeoutRoundsOnly,
+}
+
+impl RoundDetailSampling {
+    /// Whether the detailed state of the ended round is persisted.
+    pub fn samples(self, summary: &RoundSummary) -> bool {
+        match self {
+            RoundDetailSampling::Never => false,
+            RoundDetailSampling::EveryRound => true,
+            RoundDetailSampling::EveryNthRound(period) => period > 0 && summary.round % period == 0,
+            RoundDetailSampling::TimeoutRoundsOnly => match summary.reason {
+                NewRoundReason::Timeout { .. } => true,
+                NewRoundReason::QCReady => false,
+            },
+        }
+    }
+}
+
+impl Default for RoundDetailSampling {
+    fn default() -> Self {
+        RoundDetailSampling::Never
+    }
+}
+
+/// Order in which `Pacemaker::process_batch` processes the inputs of a batch.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BatchOrdering {
+    /// The local timeouts are processed after the other inputs of the batch: a certificate of the
+    /// batch advancing the round wins over the local timeout of that round, which is not
+    /// broadcast
+    LocalTimeoutsLast,
+    /// The inputs are processed in the order of the batch
+    Arrival,
+}
+
+impl Default for BatchOrdering {
+    fn default() -> Self {
+        BatchOrdering::LocalTimeoutsLast
+    }
+}
+
+/// Certificate driving the round advance when the highest quorum certificate and the highest
+/// timeout certificate are for the same round (so both lead to the same new round, only the
+/// `NewRoundReason` differs).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CertificateTieBreak {
+    /// The round advances on the quorum certificate (`NewRoundReason::QCReady`)
+    PreferQc,
+    /// The round advances on the timeout certificate (`NewRoundReason::Timeout`)
+    PreferTimeout,
+}
+
+impl Default for CertificateTieBreak {
+    fn default() -> Self {
+        CertificateTieBreak::PreferQc
+    }
+}
+
+/// Choice between the timeout certificates of the same round formed (or received) with different
+/// signers.  On equal signer counts, the certificate with the lowest authors (in the order of the
+/// authors) is chosen, so that the choice is deterministic.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TimeoutCertificateTieBreak {
+    /// The locally formed certificates are made of a quorum of timeouts only, and a received
+    /// certificate does not replace the held certificate of its round
+    FewestSigners,
+    /// The certificate with the most signatures (the highest assurance) is chosen: the locally
+    /// formed certificates include all the timeouts received for their round, and a received
+    /// certificate replaces the held received certificate of its round if it has more signers
+    MostSigners,
+}
+
+impl Default for TimeoutCertificateTieBreak {
+    fn default() -> Self {
+        TimeoutCertificateTieBreak::FewestSigners
+    }
+}
+
+/// Optional behaviors of the Pacemaker.  The default configuration leaves all of them disabled.
+#[derive(Clone, Debug, Default)]
+pub struct PacemakerConfig {
+    /// Maximum number of local timeouts broadcast within an epoch, the pacemaker stays silent
+    /// (but keeps following the certificates) once the limit is reached.
+    pub max_timeouts_per_epoch: Option<u64>,
+    /// Maximum random delay added to the round timeouts, which avoids all the validators timing
+    /// out at the very same time.
+    pub round_jitter: Duration,
+    /// Maximum random delay added to the timeout of the first round instead of `round_jitter`: at
+    /// cold start all the validators begin the first round simultaneously.
+    pub bootstrap_round_jitter: Duration,
+    /// Seed of the jitter, it should differ between the validators.
+    pub jitter_seed: u64,
+    /// Inputs of the jitter combined with the seed.
+    pub jitter_seed_policy: JitterSeedPolicy,
+    /// Quorum of timeouts applied by `update_quorum_size` when the validator set changes.  The
+    /// quorum size given at construction is kept if not set.
+    pub quorum_spec: Option<QuorumSpec>,
+    /// Delay of the broadcast of a local timeout: if the node moves to a higher round and times
+    /// out again within the window, only the timeout of the highest round is broadcast.
+    pub timeout_coalescing_window: Duration,
+    /// Validity rule of the quorum certificates advancing the round, `AcceptAnyQcValidator` if
+    /// not set.
+    pub qc_validator: Option<Arc<dyn QcValidator>>,
+    /// Extraction of the quorum certificates of the remote timeouts, `AttachedQcExtractor` if not
+    /// set.
+    pub timeout_qc_extractor: Option<Arc<dyn TimeoutQcExtractor>>,
+    /// Executor onto which the sends of the NewRoundEvents are spawned, instead of being awaited
+    /// by the futures returned from processing the inputs.  The events are then delivered in the
+    /// order the executor runs the sends in.  Inline if not set.
+    pub event_executor: Option<Arc<dyn EventExecutor>>,
+    /// Filter of the local timeouts broadcast by `Pacemaker::broadcast_local_timeout`.  The
+    /// timeouts are broadcast as is if not set.
+    pub outgoing_timeout_filter: Option<Arc<dyn OutgoingTimeoutFilter>>,
+    /// Record the inputs and outputs of the pacemaker in a decision log (see `decision_log`).
+    pub record_decision_log: bool,
+    /// Attach the highest timeout certificate to the local timeouts, and advance on the
+    /// certificates attached to the remote timeouts.
+    pub attach_highest_timeout_certificate: bool,
+    /// Maximum number of rounds a remote timeout may be ahead of the current round, the remote
+    /// timeouts beyond are dropped as `TimeoutIngestResult::FutureRound`.  Unlimited if not set.
+    pub max_future_timeout_rounds: Option<Round>,
+    /// Number of rounds a remote timeout may be behind the current round and still be accepted
+    /// (e.g., 1 accepts the timeouts of `[current - 1, current]`), which helps forming a timeout
+    /// certificate the node missed.  The older timeouts are dropped as
+    /// `TimeoutIngestResult::StaleRound`.  Only the timeouts of the current round (or higher) are
+    /// accepted by default.
+    pub past_timeout_rounds: Round,
+    /// Handling of the remote timeouts that come with an invalid quorum certificate (see
+    /// `process_remote_timeout_with_qc`).
+    pub bad_qc_policy: BadQcPolicy,
+    /// Fraction (numerator, denominator) of the timeout quorum: once the remote timeouts of that
+    /// many authors are received for the current round, the local timeout is triggered
+    /// immediately instead of waiting for the full round duration.
+    pub early_timeout_fraction: Option<(usize, usize)>,
+    /// Maximum number of rounds a quorum certificate may advance the round by in a single step.
+    /// The quorum certificates beyond are not followed, the state sync to their round is requested
+    /// instead (see `PacemakerObservers::state_sync`).
+    pub max_qc_advance: Option<Round>,
+    /// Batch the non-critical liveness storage writes (the current round) and flush them at
+    /// this interval, the timeout certificates are still written synchronously.  Every write is
+    /// synchronous if not set.
+    pub liveness_write_batch_interval: Option<Duration>,
+    /// Certificate driving the advance when a quorum certificate and a timeout certificate are
+    /// available for the same round.
+    pub certificate_tie_break: CertificateTieBreak,
+    /// Choice between the timeout certificates of the same round with different signers, which
+    /// is the certificate persisted and advanced on.
+    pub timeout_certificate_tie_break: TimeoutCertificateTieBreak,
+    /// Threshold (in rounds) and period: once the certification gap (see `certification_gap`)
+    /// stays above the threshold for the period, an alert is sent (see
+    /// `PacemakerObservers::certification_gap_alert`).
+    pub certification_gap_alert: Option<(Round, Duration)>,
+    /// Window (in advances) and threshold: once the advance reason (quorum certificate or
+    /// timeout certificate) alternated more than the threshold within the window of the most
+    /// recent advances, e.g., on a network flapping between healthy and faulty, an alert is sent
+    /// (see `PacemakerObservers::oscillation_alert`).  The window is capped to the round history.
+    pub oscillation_alert: Option<(usize, usize)>,
+    /// Window (in local timeouts) and threshold: once at least the threshold of the most recent
+    /// local timeouts fired before any remote timeout of their round was received, the clock of
+    /// the local node may be fast or its round timeout too short, and an alert is sent (see
+    /// `PacemakerObservers::fast_clock_alert`).  The own timeouts echoed back (see
+    /// `set_local_author`) do not count as remote ones.
+    pub fast_clock_alert: Option<(usize, usize)>,
+    /// If set, the delay between the expected end of a round (its duration, plus the proposer
+    /// timeout delay) and its local timeout is measured (see `counters::TIMER_LAG_MS`), and a
+    /// delay above the threshold is logged: a consistently large delay means the timer tasks are
+    /// starved by the scheduler or the executor.
+    pub timer_lag_warn_threshold: Option<Duration>,
+    /// Number of rounds timed out without any advance on a quorum certificate after which a
+    /// `TriggerStateSync` is sent (see `PacemakerObservers::state_sync_trigger`), once until the
+    /// next advance on a quorum certificate.  Never sent if not set.
+    pub state_sync_trigger_threshold: Option<u64>,
+    /// Retransmit the local timeouts to the validators no timeout of the round was received from
+    /// yet (see `retransmission_targets`), rather than broadcasting them again to all.
+    pub targeted_retransmission: bool,
+    /// Maximum number of distinct authors whose timeouts are buffered per round, which bounds the
+    /// memory used against a flood of timeouts from spoofed authors.  The extra timeouts are
+    /// dropped as `TimeoutIngestResult::TooManyAuthors`.  Unlimited if not set.
+    pub max_timeout_authors_per_round: Option<usize>,
+    /// Handling of the failures of the critical storage writes.
+    pub storage_failure_policy: StorageFailurePolicy,
+    /// Retries of the failed critical storage writes, the storage failure policy only applies once
+    /// they are exhausted.
+    pub storage_retry: RetryPolicy,
+    /// Local timeout of the rounds the local node is a proposer of.
+    pub proposer_timeout_policy: ProposerTimeoutPolicy,
+    /// The verifications of the remote timeouts that take longer are reported (see
+    /// `set_validator_verifier`).  Not measured if not set.
+    pub slow_verification_threshold: Option<Duration>,
+    /// Fraction (numerator, denominator) of the round duration after which the proposal of the
+    /// round is due (see `NewRoundEvent::proposal_deadline`), at most 1.  The proposal is due by
+    /// the end of the round if not set.
+    pub proposal_deadline_fraction: Option<(u32, u32)>,
+    /// Human-readable label of the local validator (e.g., its name in the deployment), prefixed
+    /// to the log messages of the pacemaker to tell the validators apart when several of them
+    /// log to the same output.  The messages are not prefixed if not set.
+    pub validator_label: Option<String>,
+    /// Interval of the heartbeats sent while a round is active (see
+    /// `PacemakerObservers::heartbeat`), up to the timeout of the round.  No heartbeat is sent
+    /// if not set.
+    pub heartbeat_interval: Option<Duration>,
+    /// Never fire the local timeouts automatically (e.g., when replaying a captured log or
+    /// stepping through a debugger): the timeout timers are not scheduled (nor the coalescing of
+    /// `timeout_coalescing_window`), and the local timeouts are only triggered by
+    /// `Pacemaker::advance_on_timeout`.  The quorum and timeout certificates advance the round
+    /// as usual.
+    pub manual_advance: bool,
+    /// Weight in (0, 1] of the most recent round in `Pacemaker::estimated_round_rate`, which
+    /// then uses an exponentially weighted moving average of the round durations: the higher the
+    /// weight, the faster the recent slowdowns are reflected.  The simple average of the round
+    /// durations is used if not set.
+    pub round_rate_ewma_alpha: Option<f64>,
+    /// Minimum number of distinct signers of the timeout certificates formed locally, enforced on
+    /// top of the quorum (e.g., for auditability).  No minimum if 0.
+    pub timeout_certificate_min_signers: usize,
+    /// Period after `Pacemaker::start` during which the local timeouts are not broadcast (e.g.,
+    /// while the node catches up after a restart): the rounds keep timing out locally, and the
+    /// remote timeouts are recorded (and form timeout certificates) as usual.  No grace period if
+    /// zero.
+    pub startup_grace_period: Duration,
+    /// Round below which timeouts are suppressed (e.g., during the first epoch of a freshly
+    /// bootstrapped network, while the peers are still connecting): the local timeouts of the
+    /// rounds below it are not broadcast, and the timeout certificates of the rounds below it do
+    /// not advance the round, which only advances on quorum certificates.  No suppression if not
+    /// set.
+    pub timeout_suppressed_until_round: Option<Round>,
+    /// Length in rounds of the epochs started automatically (e.g., for the epoch boundary tests
+    /// of test networks): a new epoch starts, and an `EpochChange` is sent (see
+    /// `PacemakerObservers::epoch_change`), whenever the round reaches it.  The round is then
+    /// reset to the first round of the new epoch, the certificates of the previous epoch are
+    /// dropped, and the validator set is unchanged.  An advance past the boundary starts one
+    /// epoch.  No automatic epoch if not set or below 2.
+    pub auto_epoch_rounds: Option<Round>,
+    /// Maximum skew of the timestamp of the quorum certificates that come with the remote
+    /// timeouts ahead of the local clock: the timeouts with a quorum certificate timestamped
+    /// further in the future are dropped as `TimeoutIngestResult::ImplausibleQcTimestamp`.  Not
+    /// checked if not set.
+    pub max_qc_timestamp_skew: Option<Duration>,
+    /// Maximum interval of time without a round advance, asserted (in debug builds) whenever the
+    /// pacemaker processes an input: the pacemaker panics with its state once it stalls for
+    /// longer (e.g., for the liveness tests under simulated time).  Not asserted if not set.
+    pub max_stall_interval: Option<Duration>,
+    /// Maximum number of rounds a quorum certificate may be below the current round to be
+    /// recorded by `Pacemaker::process_certificates`: the quorum certificates from the current
+    /// round on advance the round, the ones within the window below are only recorded (as the
+    /// highest certified round), and the older ones are dropped.  No quorum certificate is dropped
+    /// if not set.
+    pub max_qc_age: Option<Round>,
+    /// Extra time added to the timeout of the first round of a pacemaker constructed from a
+    /// recovered state (i.e., past round 1, from a highest quorum certificate or timeout
+    /// certificate), which lets the peers reconnect and share their state before the recovered
+    /// node times out.  The pacemakers starting from round 1 are not affected, see
+    /// `startup_grace_period` for them.
+    pub post_recovery_grace: Duration,
+    /// Floor of the round duration, applied after all the modifiers of the duration given by the
+    /// time interval (jitter, post recovery grace): a time interval shrinking the rounds (e.g., on
+    /// a fast path) never makes the local timeouts fire earlier.  No floor if not set.
+    pub min_round_duration: Option<Duration>,
+    /// Sanity bound of the round duration (e.g., 1 hour), applied last: a longer duration (e.g.,
+    /// from a buggy custom time interval, which would effectively halt the node) is clamped to
+    /// it, with a warning.  No bound if not set.
+    pub round_duration_sanity_bound: Option<Duration>,
+    /// Maximum skew between the local clock and the timestamp of the quorum certificate
+    /// triggering a round: if set, the round entered on a (valid) quorum certificate starts at
+    /// its timestamp rather than at the local time, so the local timeout fires relative to the
+    /// network rather than to the local clock.  A timestamp further behind the local time than
+    /// the bound is brought back to the bound, and a timestamp ahead of the local time is ignored
+    /// (a round never starts in the future).  The rounds start at the local time if not set.
+    pub max_round_start_skew: Option<Duration>,
+    /// Whether `Pacemaker::start` sends the synthetic new round event of the first round: if
+    /// suppressed, the first round only times out as usual and the first event sent is the one
+    /// of a real round advance (on a certificate).
+    pub suppress_initial_event: bool,
+    /// Maximum number of distinct rounds the received timeouts accumulate for, which bounds the
+    /// memory used under adversarial conditions: beyond it, the timeouts of the lowest rounds are
+    /// evicted (see `PacemakerTimeoutManager::set_max_tracked_rounds`).  No cap if not set.
+    pub max_tracked_timeout_rounds: Option<usize>,
+    /// Percentage of change of the round duration (e.g., on a backoff, an override or a swap of
+    /// the time interval) between consecutive rounds entered above which a `RoundDurationChange`
+    /// is sent (see `PacemakerObservers::round_duration_change`).  Not sent if not set.
+    pub round_duration_change_threshold: Option<u64>,
+    /// Number of inputs (certificates, remote timeouts and local timeouts) the pacemaker
+    /// processes before it stops deterministically, e.g., to bound the iterations of a fuzz
+    /// harness: the further inputs are rejected, and the final state is exposed by
+    /// `Pacemaker::final_snapshot`.  Unlimited if not set.
+    pub input_budget: Option<u64>,
+    /// Lead time before the predicted start of a round led by the local node at which the round
+    /// is sent on the proposer turn channel (see `PacemakerObservers::proposer_turn`), e.g., for
+    /// the proposal generation to pre-warm.  The start is predicted as the timeout of the round
+    /// before, the notification is cancelled if that round ends earlier.  Not sent if not set.
+    pub proposer_turn_lead_time: Option<Duration>,
+    /// Handling of the received timeout certificates for the round of the highest quorum
+    /// certificate.
+    pub conflicting_tc_policy: ConflictingTcPolicy,
+    /// Handling of the quorum certificates of blocks proposed by authors the proposer election
+    /// does not elect for their round.
+    pub proposer_mismatch_policy: ProposerMismatchPolicy,
+    /// Rounds whose detailed state is persisted when they end.
+    pub round_detail_sampling: RoundDetailSampling,
+    /// Order of the inputs of the batches given to `Pacemaker::process_batch`.
+    pub batch_ordering: BatchOrdering,
+}
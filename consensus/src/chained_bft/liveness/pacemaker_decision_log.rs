@@ -0,0 +1,156 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+use crate::chained_bft::liveness::pacemaker::Pacemaker;
+use crate::chained_bft::{
+    common::Round,
+    consensus_types::timeout_msg::{PacemakerTimeout, PacemakerTimeoutCertificate},
+    liveness::pacemaker::NewRoundEvent,
+};
+#[cfg(test)]
+use failure::prelude::*;
+#[cfg(test)]
+use futures::executor::block_on;
+#[cfg(test)]
+use rmp_serde::{from_slice, to_vec_named};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// An input processed by the Pacemaker.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum PacemakerInput {
+    /// `Pacemaker::start`
+    Start,
+    /// `Pacemaker::process_certificates` (also reached by the accepted quorum certificates of
+    /// `Pacemaker::process_quorum_cert`)
+    Certificates {
+        qc_round: Round,
+        highest_committed_round: Option<Round>,
+        timeout_certificate: Option<PacemakerTimeoutCertificate>,
+    },
+    /// `Pacemaker::process_remote_timeout`
+    RemoteTimeout(PacemakerTimeout),
+    /// `Pacemaker::process_local_timeout`
+    LocalTimeout(Round),
+    /// `Pacemaker::begin_catch_up`
+    BeginCatchUp,
+    /// `Pacemaker::end_catch_up`
+    EndCatchUp,
+}
+
+/// An output of the Pacemaker.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum PacemakerOutput {
+    /// A new round event sent to the rest of the system
+    NewRound(NewRoundEvent),
+    /// The local timeout of the round is to be broadcast
+    BroadcastTimeout(Round),
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum DecisionLogEntry {
+    /// An input with the timestamp of the time service when it was processed
+    Input {
+        timestamp: Duration,
+        input: PacemakerInput,
+    },
+    Output(PacemakerOutput),
+}
+
+/// Append-only log of the inputs processed by a Pacemaker and of the outputs they caused, in
+/// order.  The log can be persisted (e.g., captured from a production incident) and replayed in a
+/// fresh Pacemaker for regression testing.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PacemakerDecisionLog {
+    entries: Vec<DecisionLogEntry>,
+}
+
+impl PacemakerDecisionLog {
+    #[cfg(test)]
+    pub fn entries(&self) -> &[DecisionLogEntry] {
+        &self.entries
+    }
+
+    pub fn record_input(&mut self, timestamp: Duration, input: PacemakerInput) {
+        self.entries
+            .push(DecisionLogEntry::Input { timestamp, input });
+    }
+
+    pub fn record_output(&mut self, output: PacemakerOutput) {
+        self.entries.push(DecisionLogEntry::Output(output));
+    }
+
+    /// Returns the outputs of the log in order.
+    #[cfg(test)]
+    pub fn outputs(&self) -> Vec<PacemakerOutput> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match entry {
+                DecisionLogEntry::Output(output) => Some(output.clone()),
+                DecisionLogEntry::Input { .. } => None,
+            })
+            .collect()
+    }
+
+    #[cfg(test)]
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(to_vec_named(self)?)
+    }
+
+    #[cfg(test)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(from_slice(bytes)?)
+    }
+}
+
+/// Feeds the inputs of the log to the given Pacemaker and returns the outputs it produced.  The
+/// Pacemaker is expected to be freshly created with the same construction arguments as the
+/// recorded one and with `record_decision_log` enabled, and with a time service whose clock is
+/// controlled by `set_time`: `set_time` is called with the recorded timestamp of every input right
+/// before it is fed, so that the time-dependent decisions (e.g., the startup grace period) are
+/// taken at the same time as in the recording, and the outputs then match the recorded outputs
+/// exactly.
+#[cfg(test)]
+pub fn replay<F>(
+    log: &PacemakerDecisionLog,
+    pacemaker: &mut Pacemaker,
+    mut set_time: F,
+) -> Result<Vec<PacemakerOutput>>
+where
+    F: FnMut(Duration),
+{
+    ensure!(
+        pacemaker.decision_log().is_some(),
+        "The replayed pacemaker does not record its decision log"
+    );
+    for entry in log.entries() {
+        if let DecisionLogEntry::Input { timestamp, input } = entry {
+            set_time(*timestamp);
+            match input {
+                PacemakerInput::Start => block_on(pacemaker.start()),
+                PacemakerInput::Certificates {
+                    qc_round,
+                    highest_committed_round,
+                    timeout_certificate,
+                } => block_on(pacemaker.process_certificates(
+                    *qc_round,
+                    *highest_committed_round,
+                    timeout_certificate.as_ref(),
+                )),
+                PacemakerInput::RemoteTimeout(timeout) => {
+                    block_on(pacemaker.process_remote_timeout(timeout.clone()));
+                }
+                PacemakerInput::LocalTimeout(round) => {
+                    pacemaker.process_local_timeout(*round);
+                }
+                PacemakerInput::BeginCatchUp => pacemaker.begin_catch_up(),
+                PacemakerInput::EndCatchUp => block_on(pacemaker.end_catch_up()),
+            }
+        }
+    }
+    Ok(pacemaker
+        .decision_log()
+        .map(PacemakerDecisionLog::outputs)
+        .unwrap_or_default())
+}
@@ -0,0 +1,129 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+use crate::chained_bft::liveness::pacemaker::PacemakerTimeInterval;
+use crate::chained_bft::{
+    common::{Author, Round},
+    liveness::proposer_election::ProposerElection,
+};
+use crypto::ed25519::*;
+#[cfg(test)]
+use std::sync::Arc;
+use types::validator_verifier::ValidatorVerifier;
+
+/// First round of the epochs started by `PacemakerConfig::auto_epoch_rounds`, the round following
+/// the genesis of the epoch.
+pub const EPOCH_START_ROUND: Round = 1;
+
+/// New configuration of the pacemaker applied at once by `Pacemaker::reconfigure` (e.g., on an
+/// epoch change).
+#[cfg(test)]
+pub struct ReconfigParams<T> {
+    /// The new epoch, higher than the current one
+    pub epoch: u64,
+    /// The new validator set, which remote timeouts are verified against.  The quorum of
+    /// timeouts follows `PacemakerConfig::quorum_spec` if set, the quorum of the validator set
+    /// otherwise.
+    pub validator_verifier: Arc<ValidatorVerifier<Ed25519PublicKey>>,
+    /// The new time interval, which applies from the next round on.  Unchanged if not set.
+    pub time_interval: Option<Box<dyn PacemakerTimeInterval>>,
+    /// The new proposer election (see `Pacemaker::set_proposer_election`).  Unchanged if not set.
+    pub proposer_election: Option<Box<dyn ProposerElection<T> + Send + Sync>>,
+}
+
+/// Event sent on the channel of `PacemakerObservers::epoch_change` once the pacemaker is
+/// reconfigured.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EpochChange {
+    pub epoch: u64,
+    pub validator_set_size: usize,
+    pub timeout_quorum_size: usize,
+}
+
+/// Proposers of the rounds, which erases the payload type of the ProposerElection.
+trait RoundProposers: Send + Sync {
+    fn proposers(&self, round: Round) -> Vec<Author>;
+}
+
+struct ElectionRoundProposers<T> {
+    proposer_election: Box<dyn ProposerElection<T> + Send + Sync>,
+}
+
+impl<T> RoundProposers for ElectionRoundProposers<T> {
+    fn proposers(&self, round: Round) -> Vec<Author> {
+        self.proposer_election.get_valid_proposers(round)
+    }
+}
+
+/// Builds the proposers of an epoch from the epoch and its validator set, see
+/// `Pacemaker::set_proposer_election_factory`.
+type RoundProposersFactory =
+    Box<dyn Fn(u64, &ValidatorVerifier<Ed25519PublicKey>) -> Box<dyn RoundProposers> + Send + Sync>;
+
+/// Proposers of the rounds of the current epoch, given by a proposer election whose payload type
+/// is erased, along with the factory building the proposer election of each new epoch.
+#[derive(Default)]
+pub struct EpochProposers {
+    round_proposers: Option<Box<dyn RoundProposers>>,
+    factory: Option<RoundProposersFactory>,
+}
+
+impl EpochProposers {
+    /// Sets the proposer election of the rounds.
+    pub fn set_proposer_election<T: 'static>(
+        &mut self,
+        proposer_election: Box<dyn ProposerElection<T> + Send + Sync>,
+    ) {
+        self.round_proposers = Some(Box::new(ElectionRoundProposers { proposer_election }));
+    }
+
+    /// Sets the factory building the proposer election of each new epoch from the epoch and its
+    /// validator set, see `build`.
+    #[cfg(test)]
+    pub fn set_factory<T: 'static, F>(&mut self, factory: F)
+    where
+        F: Fn(
+                u64,
+                &ValidatorVerifier<Ed25519PublicKey>,
+            ) -> Box<dyn ProposerElection<T> + Send + Sync>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.factory = Some(Box::new(
+            move |epoch: u64, validator_verifier: &ValidatorVerifier<Ed25519PublicKey>| {
+                let proposer_election = factory(epoch, validator_verifier);
+                let round_proposers: Box<dyn RoundProposers> =
+                    Box::new(ElectionRoundProposers { proposer_election });
+                round_proposers
+            },
+        ));
+    }
+
+    /// Returns whether a factory is set.
+    pub fn has_factory(&self) -> bool {
+        self.factory.is_some()
+    }
+
+    /// Replaces the proposer election with the one the factory builds for the given epoch and
+    /// validator set, if a factory is set.
+    pub fn build(&mut self, epoch: u64, validator_verifier: &ValidatorVerifier<Ed25519PublicKey>) {
+        if let Some(factory) = self.factory.as_ref() {
+            self.round_proposers = Some(factory(epoch, validator_verifier));
+        }
+    }
+
+    /// Returns whether a proposer election is set.
+    #[cfg(test)]
+    pub fn is_set(&self) -> bool {
+        self.round_proposers.is_some()
+    }
+
+    /// Returns the valid proposers of the given round, none if no proposer election is set.
+    pub fn proposers(&self, round: Round) -> Vec<Author> {
+        self.round_proposers
+            .as_ref()
+            .map_or_else(Vec::new, |round_proposers| round_proposers.proposers(round))
+    }
+}
@@ -0,0 +1,233 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+use crate::chained_bft::{
+    common::Author, consensus_types::timeout_msg::PacemakerTimeout,
+    liveness::pacemaker_timeout_manager::HighestTimeoutCertificates,
+};
+use crate::chained_bft::{common::Round, liveness::pacemaker::NewRoundReason};
+#[cfg(test)]
+use serde::{Deserialize, Serialize};
+#[cfg(test)]
+use std::collections::BTreeMap;
+use std::{collections::VecDeque, time::Duration};
+
+/// Number of the most recent completed rounds kept in the `RoundHistory`.
+const ROUND_HISTORY_SIZE: usize = 20;
+
+/// A completed round of the round history.
+pub struct CompletedRound {
+    pub round: Round,
+    /// Time service timestamp of the start of the round
+    pub start: Duration,
+    pub duration: Duration,
+    /// Reason for advancing past the round
+    pub reason: NewRoundReason,
+}
+
+/// The most recent completed rounds of a pacemaker (the last ROUND_HISTORY_SIZE advances), the
+/// oldest first.
+pub struct RoundHistory {
+    completed_rounds: VecDeque<CompletedRound>,
+}
+
+impl Default for RoundHistory {
+    fn default() -> Self {
+        Self {
+            completed_rounds: VecDeque::with_capacity(ROUND_HISTORY_SIZE),
+        }
+    }
+}
+
+impl RoundHistory {
+    /// Appends the completed round, dropping the oldest one if the history is full.
+    pub fn push(&mut self, completed_round: CompletedRound) {
+        if self.completed_rounds.len() == ROUND_HISTORY_SIZE {
+            self.completed_rounds.pop_front();
+        }
+        self.completed_rounds.push_back(completed_round);
+    }
+
+    /// Returns whether each of the last (at most) `count` completed rounds ended in a timeout,
+    /// the oldest first.
+    pub fn recent_timeouts(&self, count: usize) -> Vec<bool> {
+        self.recent(count)
+            .map(|completed_round| match completed_round.reason {
+                NewRoundReason::QCReady => false,
+                NewRoundReason::Timeout { .. } => true,
+            })
+            .collect()
+    }
+
+    /// Returns the reasons of the last (at most) `count` round advances, the oldest first.
+    #[cfg(test)]
+    pub fn recent_reasons(&self, count: usize) -> Vec<NewRoundReason> {
+        self.recent(count)
+            .map(|completed_round| completed_round.reason.clone())
+            .collect()
+    }
+
+    /// Returns the number of rounds per second estimated from the durations of the completed
+    /// rounds: their exponentially weighted moving average with the given weight of the most
+    /// recent round, or their plain average.  None if no round completed yet (or they completed
+    /// instantly).
+    #[cfg(test)]
+    pub fn estimated_round_rate(&self, ewma_alpha: Option<f64>) -> Option<f64> {
+        if let Some(alpha) = ewma_alpha {
+            return self.ewma_round_rate(alpha);
+        }
+        let total: Duration = self
+            .completed_rounds
+            .iter()
+            .map(|completed_round| completed_round.duration)
+            .sum();
+        if total == Duration::from_millis(0) {
+            return None;
+        }
+        let total_secs = total.as_micros() as f64 / 1_000_000.0;
+        Some(self.completed_rounds.len() as f64 / total_secs)
+    }
+
+    #[cfg(test)]
+    fn ewma_round_rate(&self, alpha: f64) -> Option<f64> {
+        let mut durations = self
+            .completed_rounds
+            .iter()
+            .map(|completed_round| completed_round.duration.as_micros() as f64 / 1_000_000.0);
+        let first_secs = durations.next()?;
+        let average_secs = durations.fold(first_secs, |average_secs, duration_secs| {
+            alpha * duration_secs + (1.0 - alpha) * average_secs
+        });
+        if average_secs > 0.0 {
+            Some(1.0 / average_secs)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the completed round of the history, if still in it.
+    #[cfg(test)]
+    pub fn find(&self, round: Round) -> Option<&CompletedRound> {
+        self.completed_rounds
+            .iter()
+            .find(|completed_round| completed_round.round == round)
+    }
+
+    fn recent(&self, count: usize) -> impl Iterator<Item = &CompletedRound> {
+        let skipped = self.completed_rounds.len().saturating_sub(count);
+        self.completed_rounds.iter().skip(skipped)
+    }
+}
+
+/// State of a pacemaker captured by `Pacemaker::snapshot`, from which `Pacemaker::from_snapshot`
+/// reconstructs an equivalent pacemaker (e.g., on a hot standby node).
+#[cfg(test)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PacemakerSnapshot {
+    pub current_round: Round,
+    pub highest_qc_round: Round,
+    pub highest_committed_round: Round,
+    pub highest_timeout_certificates: HighestTimeoutCertificates,
+    /// Highest timeout received from each author, pending a timeout certificate
+    pub received_timeouts: Vec<PacemakerTimeout>,
+    pub timeout_quorum_size: usize,
+    pub last_local_timeout_round: Option<Round>,
+    pub epoch: u64,
+    pub timeouts_sent_in_epoch: u64,
+    /// Number of inputs processed, see `PacemakerConfig::input_budget`
+    #[serde(default)]
+    pub processed_inputs: u64,
+    /// Highest round of the timeouts received from the other validators
+    #[serde(default)]
+    pub highest_remote_timeout_round: Round,
+}
+
+/// Timeouts pending a timeout certificate, grouped by round, exported by
+/// `Pacemaker::export_pending_timeouts` (e.g., for a point-in-time backup of the liveness state,
+/// or a transfer to a standby) and imported by `Pacemaker::import_pending_timeouts`.
+#[cfg(test)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PendingTimeoutsSnapshot {
+    /// Highest timeout received from each author, by round
+    pub timeouts: BTreeMap<Round, Vec<PacemakerTimeout>>,
+}
+
+#[cfg(test)]
+impl PendingTimeoutsSnapshot {
+    /// Returns the number of timeouts, one per author.
+    pub fn len(&self) -> usize {
+        self.timeouts.values().map(Vec::len).sum()
+    }
+
+    /// Returns true if there is no pending timeout.
+    pub fn is_empty(&self) -> bool {
+        self.timeouts.is_empty()
+    }
+}
+
+/// Counts of the events of a pacemaker since its creation or the last `Pacemaker::reset_metrics`,
+/// see `Pacemaker::metrics_snapshot`.  Unlike the global counters (see `counters`), which all the
+/// pacemakers of the process increase, they are specific to the pacemaker and can be reset (e.g.,
+/// for the isolation of the tests, or for a periodic report).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PacemakerMetrics {
+    /// Round advances on a quorum certificate
+    pub qc_advances: u64,
+    /// Round advances on a timeout certificate
+    pub timeout_advances: u64,
+    /// Local timeouts to be broadcast (see `Pacemaker::process_local_timeout`)
+    pub local_timeouts: u64,
+    /// Received timeout certificates proving that an author signed conflicting timeouts (see
+    /// `detect_conflicting_tc`)
+    pub conflicting_timeouts: u64,
+}
+
+/// Field-by-field differences between two `PacemakerSnapshot`s (see `diff_snapshots`): each
+/// differing field holds its values in the first and in the second snapshot, the equal fields are
+/// not set.
+#[cfg(test)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SnapshotDiff {
+    pub current_round: Option<(Round, Round)>,
+    pub highest_qc_round: Option<(Round, Round)>,
+    pub highest_committed_round: Option<(Round, Round)>,
+    /// Authors, sorted, of the received timeouts pending a timeout certificate
+    pub pending_timeout_authors: Option<(Vec<Author>, Vec<Author>)>,
+}
+
+#[cfg(test)]
+impl SnapshotDiff {
+    /// Whether the snapshots agree on all the compared fields.
+    pub fn is_empty(&self) -> bool {
+        *self == SnapshotDiff::default()
+    }
+}
+
+/// Compares two pacemaker snapshots (e.g., of two nodes that disagree), to root cause their
+/// divergence.
+#[cfg(test)]
+pub fn diff_snapshots(a: &PacemakerSnapshot, b: &PacemakerSnapshot) -> SnapshotDiff {
+    fn diff<T: PartialEq>(a: T, b: T) -> Option<(T, T)> {
+        if a == b {
+            None
+        } else {
+            Some((a, b))
+        }
+    }
+    fn pending_timeout_authors(snapshot: &PacemakerSnapshot) -> Vec<Author> {
+        let mut authors: Vec<Author> = snapshot
+            .received_timeouts
+            .iter()
+            .map(PacemakerTimeout::author)
+            .collect();
+        authors.sort();
+        authors
+    }
+    SnapshotDiff {
+        current_round: diff(a.current_round, b.current_round),
+        highest_qc_round: diff(a.highest_qc_round, b.highest_qc_round),
+        highest_committed_round: diff(a.highest_committed_round, b.highest_committed_round),
+        pending_timeout_authors: diff(pending_timeout_authors(a), pending_timeout_authors(b)),
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::{Author, Round},
+    liveness::{pacemaker::NewRoundReason, pacemaker_epoch::EpochChange},
+};
+use channel;
+use futures::channel::oneshot;
+use logger::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, time::Duration};
+
+/// Sent periodically while a round is active (see `PacemakerConfig::heartbeat_interval`), which
+/// tells the subscribers that the pacemaker is alive during the long rounds.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PacemakerHeartbeat {
+    /// Current round
+    pub round: Round,
+    /// Time elapsed since the start of the round
+    pub elapsed: Duration,
+}
+
+/// Sent when the duration of a round changed by more than the threshold of
+/// `PacemakerConfig::round_duration_change_threshold` from the previous round (see
+/// `PacemakerObservers::round_duration_change`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundDurationChange {
+    /// Round entered with the new duration
+    pub round: Round,
+    /// Duration of the previous round entered
+    pub previous_duration: Duration,
+    /// Duration of the round
+    pub duration: Duration,
+}
+
+/// Record of a round sent when it ends (see `PacemakerObservers::round_summary`), which gathers
+/// its lifecycle in one place for the diagnostics.
+#[derive(Deserialize, Serialize, Clone, Debug, Eq, PartialEq)]
+pub struct RoundSummary {
+    pub round: Round,
+    /// Time service timestamps of the start and of the end of the round
+    pub start: Duration,
+    pub end: Duration,
+    /// Reason for advancing past the round
+    pub reason: NewRoundReason,
+    /// Number of retransmissions of the local timeout of the round (see
+    /// `Pacemaker::retransmit_count`)
+    pub retransmit_count: u32,
+    /// Authors of the timeout certificate ending the round, sorted, none if a quorum certificate
+    /// ended it
+    pub timeout_authors: Vec<Author>,
+}
+
+/// Sent when the rounds timed out since the last advance on a quorum certificate reach
+/// `PacemakerConfig::state_sync_trigger_threshold`: the node likely lags behind and needs to state
+/// sync rather than keep timing out (see `PacemakerObservers::state_sync_trigger`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TriggerStateSync {
+    /// Round entered when the threshold was reached
+    pub round: Round,
+    /// Highest certified round known to the pacemaker
+    pub highest_qc_round: Round,
+    /// Number of rounds timed out since the last advance on a quorum certificate
+    pub timed_out_rounds: u64,
+}
+
+/// Sent when the block of a quorum certificate was proposed by an author the proposer election
+/// does not elect for its round.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposerMismatch {
+    /// Round of the certified block
+    pub round: Round,
+    /// Author of the certified block
+    pub proposer: Author,
+    /// Proposers of the round according to the proposer election
+    pub expected_proposers: Vec<Author>,
+}
+
+/// Reason of the halt of the pacemaker (see `PacemakerObservers::halt`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HaltReason {
+    /// A critical storage write failed under `StorageFailurePolicy::Halt`
+    StorageFailure,
+    /// The validator set became empty (see `Pacemaker::update_quorum_size`): no quorum can
+    /// ever be formed
+    EmptyValidatorSet,
+}
+
+/// Notified of the rounds that ended in a timeout (i.e., the round advanced on a timeout
+/// certificate), for example to track the proposers that fail to lead their rounds.
+pub trait RoundTimeoutObserver: Send + Sync {
+    fn round_timed_out(&self, round: Round);
+}
+
+/// A tracing span of the pacemaker.  The span of a round is the parent of the other spans of the
+/// round, they are all closed (children first) when the round ends.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PacemakerSpan {
+    /// The round, from its new round event to its end
+    Round(Round),
+    /// The local timeout of the round, from its firing to the end of the round
+    Timeout(Round),
+    /// The formation of the timeout certificate of the round, from the first accepted remote
+    /// timeout of the round to the end of the round
+    TimeoutCertificateFormation(Round),
+}
+
+/// Receives the tracing spans of the pacemaker (see `PacemakerObservers::tracer`), e.g., to
+/// correlate them with the spans of the rest of the consensus stack in a distributed tracing
+/// system.
+pub trait PacemakerTracer: Send + Sync {
+    fn open_span(&self, span: PacemakerSpan);
+    fn close_span(&self, span: PacemakerSpan);
+}
+
+/// Optional subscribers of a pacemaker (see `Pacemaker::observers_mut`): the channels of its
+/// notifications and alerts, and the observers of its rounds.  None of them is set by default,
+/// and the events without a subscriber are not sent.
+#[derive(Default)]
+pub struct PacemakerObservers {
+    /// Channel of the `RoundSummary` of each round, sent when the round ends
+    pub round_summary: Option<channel::Sender<RoundSummary>>,
+    /// Channel of the heartbeats sent every `PacemakerConfig::heartbeat_interval`
+    pub heartbeat: Option<channel::Sender<PacemakerHeartbeat>>,
+    /// Channel of the rounds led by the local node, sent
+    /// `PacemakerConfig::proposer_turn_lead_time` before their predicted start.  The proposers
+    /// are the ones of the proposer election (see `Pacemaker::set_proposer_election` and
+    /// `Pacemaker::set_local_author`).
+    pub proposer_turn: Option<channel::Sender<Round>>,
+    /// Channel of the rounds of the received timeout certificates conflicting with the highest
+    /// quorum certificate (see `PacemakerConfig::conflicting_tc_policy`)
+    pub conflicting_tc_alert: Option<channel::Sender<Round>>,
+    /// Channel of the mismatches between the proposers of the certified blocks and the proposer
+    /// election (see `Pacemaker::process_proposal_quorum_cert`)
+    pub proposer_mismatch: Option<channel::Sender<ProposerMismatch>>,
+    /// Channel of the rounds of the quorum certificates that are too far ahead (see
+    /// `PacemakerConfig::max_qc_advance`), to be reached by state sync
+    pub state_sync: Option<channel::Sender<Round>>,
+    /// Channel of the `TriggerStateSync`, sent once the rounds timed out without a quorum
+    /// certificate reach `PacemakerConfig::state_sync_trigger_threshold`
+    pub state_sync_trigger: Option<channel::Sender<TriggerStateSync>>,
+    /// Channel of the round duration changes above the threshold of
+    /// `PacemakerConfig::round_duration_change_threshold`
+    pub round_duration_change: Option<channel::Sender<RoundDurationChange>>,
+    /// Channel of the certification gap, sent once it stays above the threshold of
+    /// `PacemakerConfig::certification_gap_alert`
+    pub certification_gap_alert: Option<channel::Sender<Round>>,
+    /// Channel of the number of alternations of the advance reason, sent once it exceeds the
+    /// threshold of `PacemakerConfig::oscillation_alert`
+    pub oscillation_alert: Option<channel::Sender<usize>>,
+    /// Channel of the number of local timeouts that fired before any remote one, sent once it
+    /// reaches the threshold of `PacemakerConfig::fast_clock_alert`
+    pub fast_clock_alert: Option<channel::Sender<usize>>,
+    /// Channel of the `EpochChange`, sent once per reconfiguration (see `Pacemaker::reconfigure`
+    /// and `PacemakerConfig::auto_epoch_rounds`)
+    pub epoch_change: Option<channel::Sender<EpochChange>>,
+    /// One-shot signal fired with the halt reason when the pacemaker halts (e.g., for the
+    /// orchestration to trigger a recovery)
+    pub halt: Option<oneshot::Sender<HaltReason>>,
+    /// Observer notified of the rounds that end in a timeout
+    pub round_timeout_observer: Option<Arc<dyn RoundTimeoutObserver>>,
+    /// Tracer receiving the spans of the rounds, no span is traced if not set
+    pub tracer: Option<Arc<dyn PacemakerTracer>>,
+    // Spans opened in the current round, in the order of their opening
+    open_spans: Vec<PacemakerSpan>,
+}
+
+impl PacemakerObservers {
+    /// Opens the span, unless it is already open.
+    pub fn open_span(&mut self, span: PacemakerSpan) {
+        if let Some(tracer) = self.tracer.as_ref() {
+            if !self.open_spans.contains(&span) {
+                tracer.open_span(span);
+                self.open_spans.push(span);
+            }
+        }
+    }
+
+    /// Closes the open spans, the most recently opened first.
+    pub fn close_spans(&mut self) {
+        if let Some(tracer) = self.tracer.as_ref() {
+            while let Some(span) = self.open_spans.pop() {
+                tracer.close_span(span);
+            }
+        }
+    }
+
+    /// Fires the halt signal, if any.
+    pub fn notify_halt(&mut self, reason: HaltReason, log_prefix: &str) {
+        if let Some(halt) = self.halt.take() {
+            if halt.send(reason).is_err() {
+                debug!("{}Halt signal receiver dropped", log_prefix);
+            }
+        }
+    }
+}
@@ -3,24 +3,80 @@
 
 use crate::{
     chained_bft::{
-        consensus_types::timeout_msg::PacemakerTimeout,
+        common::{Author, Round},
+        consensus_types::{
+            block::Block,
+            quorum_cert::QuorumCert,
+            timeout_msg::{
+                PacemakerTimeout, PacemakerTimeoutCertificate,
+                PacemakerTimeoutCertificateVerificationError,
+            },
+        },
         liveness::{
+            multi_pacemaker_simulator::MultiPacemakerSimulator,
             pacemaker::{
-                ExponentialTimeInterval, NewRoundEvent, NewRoundReason, Pacemaker,
-                PacemakerTimeInterval,
+                validate_time_interval, ExponentialTimeInterval, IntervalDescription,
+                NewRoundEvent, NewRoundReason, Pacemaker, PacemakerBuilder, PacemakerError,
+                PacemakerTimeInterval, QuorumInfo, RoundStatus, TimeoutIngestResult,
+            },
+            pacemaker_config::{
+                BadQcPolicy, BatchOrdering, CertificateTieBreak, ConflictingTcPolicy,
+                EventExecutor, JitterSeedPolicy, OutgoingTimeoutFilter, PacemakerConfig,
+                ProposerMismatchPolicy, ProposerTimeoutPolicy, QcValidator, QuorumSpec,
+                RetryPolicy, RoundDetailSampling, StorageFailurePolicy, TimeoutQcExtractor,
+            },
+            pacemaker_decision_log::{
+                replay, PacemakerDecisionLog, PacemakerInput, PacemakerOutput,
+            },
+            pacemaker_epoch::{EpochChange, ReconfigParams},
+            pacemaker_history::{diff_snapshots, PacemakerMetrics, SnapshotDiff},
+            pacemaker_observers::{
+                HaltReason, PacemakerHeartbeat, PacemakerSpan, PacemakerTracer, ProposerMismatch,
+                RoundDurationChange, RoundSummary, RoundTimeoutObserver, TriggerStateSync,
             },
             pacemaker_timeout_manager::HighestTimeoutCertificates,
+            proposer_election::ProposerElection,
+            rotating_proposer_election::RotatingProposer,
+            timeout_broadcaster::{
+                ChannelTimeoutBroadcaster, MulticastTimeoutBroadcaster, TimeoutBroadcaster,
+            },
+            weighted_proposer_election::WeightedProposer,
         },
-        persistent_storage::PersistentStorage,
-        test_utils::{MockStorage, TestPayload},
+        persistent_storage::{PersistentLivenessStorage, PersistentStorage},
+        safety::vote_msg::VoteMsg,
+        test_utils::{
+            placeholder_certificate_for_block, placeholder_ledger_info, MockStorage, TestPayload,
+        },
+    },
+    counters,
+    state_replication::ExecutedState,
+    util::{
+        mock_time_service::{ManualTimeService, SimulatedTimeService},
+        time_service::{ScheduledTask, TimeService},
     },
-    util::mock_time_service::SimulatedTimeService,
 };
 use channel;
-use crypto::ed25519::*;
-use futures::{executor::block_on, StreamExt};
-use std::{sync::Arc, time::Duration, u64};
-use types::validator_signer::ValidatorSigner;
+use crypto::{ed25519::*, HashValue};
+use futures::{
+    channel::oneshot,
+    executor::block_on,
+    future::{self, Either},
+    Future, FutureExt, StreamExt,
+};
+use proto_conv::FromProtoBytes;
+use std::{
+    collections::HashMap,
+    fmt,
+    pin::Pin,
+    sync::{atomic::Ordering, Arc, Mutex},
+    time::Duration,
+    u64,
+};
+use types::{
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
+    validator_verifier::{ValidatorVerifier, VerifyError},
+};
 
 #[test]
 fn test_pacemaker_time_interval() {
@@ -35,6 +91,185 @@ fn test_pacemaker_time_interval() {
     assert_eq!(6750, interval.get_round_duration(1000).as_millis());
 }
 
+#[test]
+fn test_pacemaker_time_interval_ratio() {
+    let float_interval = ExponentialTimeInterval::new(Duration::from_millis(3000), 1.5, 2);
+    let ratio_interval = ExponentialTimeInterval::new_ratio(Duration::from_millis(3000), 3, 2, 2);
+    for round_index in &[0, 1, 2, 3, 1000] {
+        assert_eq!(
+            float_interval.get_round_duration(*round_index),
+            ratio_interval.get_round_duration(*round_index)
+        );
+    }
+    assert_eq!(6750, ratio_interval.get_round_duration(2).as_millis());
+    // Rounded up like the float multiplier
+    let interval = ExponentialTimeInterval::new_ratio(Duration::from_millis(1001), 3, 2, 1);
+    assert_eq!(1502, interval.get_round_duration(1).as_millis());
+    assert_eq!(validate_time_interval(&interval), Ok(()));
+
+    // The ratio is reduced, its powers no longer overflow
+    let interval = ExponentialTimeInterval::new_ratio(Duration::from_millis(1000), 16, 16, 31);
+    assert_eq!(1000, interval.get_round_duration(31).as_millis());
+    // The powers overflow, the float computation applies
+    let interval = ExponentialTimeInterval::new_ratio(
+        Duration::from_millis(1000),
+        std::u64::MAX,
+        std::u64::MAX - 1,
+        31,
+    );
+    let duration_ms = interval.get_round_duration(31).as_millis();
+    assert!(duration_ms >= 1000 && duration_ms <= 1001);
+    // The durations beyond the milliseconds of a u64 are clamped
+    let interval =
+        ExponentialTimeInterval::new_ratio(Duration::from_millis(std::u64::MAX), 3, 2, 1);
+    assert_eq!(
+        interval.get_round_duration(1),
+        Duration::from_millis(std::u64::MAX)
+    );
+}
+
+#[test]
+fn test_rounds_until_duration() {
+    // Durations of 1s, 2s, 4s, ... 128s
+    let interval = ExponentialTimeInterval::new(Duration::from_secs(1), 2.0, 7);
+    assert_eq!(
+        interval.rounds_until_duration(Duration::from_secs(1)),
+        Some(0)
+    );
+    assert_eq!(
+        interval.rounds_until_duration(Duration::from_secs(60)),
+        Some(6)
+    );
+    assert_eq!(
+        interval.rounds_until_duration(Duration::from_secs(64)),
+        Some(6)
+    );
+    assert_eq!(
+        interval.rounds_until_duration(Duration::from_secs(128)),
+        Some(7)
+    );
+    assert_eq!(
+        interval.rounds_until_duration(Duration::from_secs(129)),
+        None
+    );
+
+    let delayed_interval =
+        ExponentialTimeInterval::new_with_backoff_start_round(Duration::from_secs(1), 2.0, 7, 3);
+    assert_eq!(
+        delayed_interval.rounds_until_duration(Duration::from_secs(60)),
+        Some(9)
+    );
+
+    // Capped at 32s
+    let capped_interval = ExponentialTimeInterval::new(Duration::from_secs(1), 2.0, 5);
+    assert_eq!(
+        capped_interval.rounds_until_duration(Duration::from_secs(60)),
+        None
+    );
+}
+
+#[test]
+fn test_time_interval_description() {
+    let interval = ExponentialTimeInterval::new_with_backoff_start_round(
+        Duration::from_millis(3000),
+        1.5,
+        2,
+        3,
+    );
+    assert_eq!(
+        interval.describe(),
+        IntervalDescription {
+            type_name: "exponential".to_string(),
+            base_ms: Some(3000),
+            multiplier: Some(1.5),
+            multiplier_ratio: None,
+            max_exponent: Some(2),
+            backoff_start_round: Some(3),
+        }
+    );
+    let interval = ExponentialTimeInterval::new_ratio(Duration::from_millis(3000), 3, 2, 2);
+    assert_eq!(interval.describe().multiplier_ratio, Some((3, 2)));
+    assert_eq!(interval.describe().multiplier, Some(1.5));
+    let interval = ScriptedTimeInterval {
+        durations: vec![Duration::from_millis(1)],
+    };
+    assert_eq!(interval.describe().type_name, "custom");
+    assert_eq!(interval.describe().base_ms, None);
+
+    let (pm, _new_round_events_receiver) = make_pacemaker();
+    assert_eq!(pm.describe_time_interval().base_ms, Some(2));
+}
+
+#[test]
+fn test_pacemaker_time_interval_backoff_start_round() {
+    let interval = ExponentialTimeInterval::new_with_backoff_start_round(
+        Duration::from_millis(3000),
+        1.5,
+        2,
+        3,
+    );
+    for round_index in 0..=3 {
+        assert_eq!(3000, interval.get_round_duration(round_index).as_millis());
+    }
+    assert_eq!(4500, interval.get_round_duration(4).as_millis());
+    assert_eq!(6750, interval.get_round_duration(5).as_millis());
+    assert_eq!(6750, interval.get_round_duration(1000).as_millis());
+    assert_eq!(validate_time_interval(&interval), Ok(()));
+}
+
+/// Time interval that returns the given durations by round index (the last duration is repeated
+/// for all the following round indices)
+struct ScriptedTimeInterval {
+    durations: Vec<Duration>,
+}
+
+impl PacemakerTimeInterval for ScriptedTimeInterval {
+    fn get_round_duration(&self, round_index_after_committed_qc: usize) -> Duration {
+        let index = round_index_after_committed_qc.min(self.durations.len() - 1);
+        self.durations[index]
+    }
+}
+
+fn try_make_pacemaker(
+    time_interval: Box<dyn PacemakerTimeInterval>,
+) -> Result<Pacemaker, PacemakerError> {
+    let (new_round_events_sender, _) = channel::new_test(1_024);
+    let (pacemaker_timeout_tx, _) = channel::new_test(1_024);
+    PacemakerBuilder::new(
+        MockStorage::<TestPayload>::start_for_testing()
+            .0
+            .persistent_liveness_storage(),
+        time_interval,
+        Arc::new(SimulatedTimeService::new()),
+        new_round_events_sender,
+        pacemaker_timeout_tx,
+        3,
+    )
+    .try_build()
+}
+
+#[test]
+fn test_time_interval_self_check() {
+    let valid_interval = ExponentialTimeInterval::new(Duration::from_millis(3000), 1.5, 6);
+    assert!(try_make_pacemaker(Box::new(valid_interval)).is_ok());
+
+    let zero_interval = ScriptedTimeInterval {
+        durations: vec![Duration::from_millis(0)],
+    };
+    assert_eq!(
+        try_make_pacemaker(Box::new(zero_interval)).err(),
+        Some(PacemakerError::ZeroRoundDuration { round_index: 0 })
+    );
+
+    let decreasing_interval = ScriptedTimeInterval {
+        durations: vec![Duration::from_millis(10), Duration::from_millis(5)],
+    };
+    assert_eq!(
+        try_make_pacemaker(Box::new(decreasing_interval)).err(),
+        Some(PacemakerError::DecreasingRoundDuration { round_index: 1 })
+    );
+}
+
 #[test]
 /// Verify that Pacemaker properly outputs PacemakerTimeoutMsg upon timeout
 fn test_basic_timeout() {
@@ -43,19 +278,19 @@ fn test_basic_timeout() {
     let simulated_time = SimulatedTimeService::auto_advance_until(Duration::from_millis(4));
     let (new_round_events_sender, _new_round_events_receiver) = channel::new_test(1_024);
     let (external_timeout_sender, mut external_timeout_receiver) = channel::new_test(1_024);
-    let mut pm = Pacemaker::new(
+    let mut pm = PacemakerBuilder::new(
         MockStorage::<TestPayload>::start_for_testing()
             .0
             .persistent_liveness_storage(),
         time_interval,
-        0,
-        highest_certified_round,
         Arc::new(simulated_time.clone()),
         new_round_events_sender,
         external_timeout_sender,
         1,
-        HighestTimeoutCertificates::new(None, None),
-    );
+    )
+    .highest_qc_round(highest_certified_round)
+    .build();
+    block_on(pm.start());
 
     for _ in 0..2 {
         let round = block_on(external_timeout_receiver.next()).unwrap();
@@ -64,6 +299,39 @@ fn test_basic_timeout() {
         assert_eq!(2, round);
         pm.process_local_timeout(round);
     }
+    assert_eq!(pm.retransmit_count(2), 1);
+}
+
+#[test]
+/// Verify that a single validator advances the rounds with its QCs without any timeout
+fn test_single_validator() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let proposer_election: Box<dyn ProposerElection<TestPayload>> =
+        Box::new(RotatingProposer::new(vec![signer.author()], 1));
+    let (new_round_events_sender, mut new_round_events_receiver) = channel::new_test(1_024);
+    let (timeout_sender, mut timeout_receiver) = channel::new_test(1_024);
+    let mut pm = PacemakerBuilder::new(
+        MockStorage::<TestPayload>::start_for_testing()
+            .0
+            .persistent_liveness_storage(),
+        Box::new(ExponentialTimeInterval::fixed(Duration::from_millis(2))),
+        Arc::new(SimulatedTimeService::new()),
+        new_round_events_sender,
+        timeout_sender,
+        1,
+    )
+    .build();
+    block_on(pm.start());
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    for round in 1..10 {
+        assert_eq!(
+            proposer_election.get_valid_proposers(round),
+            vec![signer.author()]
+        );
+        block_on(pm.process_certificates(round, None, None));
+        block_on(expect_qc(round + 1, &mut new_round_events_receiver));
+    }
+    assert_eq!(try_next_event(&mut timeout_receiver), None);
 }
 
 #[test]
@@ -85,7 +353,7 @@ fn test_timeout_certificate() {
         // accumulated into single timeout certificate
         for round in 1..rounds {
             let signer = &signers[round - 1];
-            let pacemaker_timeout = PacemakerTimeout::new(round as u64, signer, None);
+            let pacemaker_timeout = PacemakerTimeout::new(0, round as u64, signer, None);
             pm.process_remote_timeout(pacemaker_timeout).await;
         }
         // Then timeout quorum for previous round (1,2,3) generates new round event for round 2
@@ -111,29 +379,495 @@ fn test_basic_qc() {
     });
 }
 
+#[test]
+fn test_duplicate_qc() {
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker();
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+
+    // QCs for the same round (e.g., from different peers) emit a single new round event
+    block_on(pm.process_certificates(3, None, None));
+    block_on(pm.process_certificates(3, None, None));
+    block_on(expect_qc(4, &mut new_round_events_receiver));
+    assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+}
+
+#[test]
+fn test_max_timeouts_per_epoch() {
+    let mut config = PacemakerConfig::default();
+    config.max_timeouts_per_epoch = Some(2);
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    // Only two timeouts are broadcast in the epoch, the node is silent afterwards
+    assert!(pm.process_local_timeout(1));
+    assert!(pm.process_local_timeout(1));
+    assert!(!pm.process_local_timeout(1));
+    // The silent node still follows the certificates
+    block_on(pm.process_certificates(1, None, None));
+    assert_eq!(pm.current_round(), 2);
+    assert!(!pm.process_local_timeout(2));
+    // The limit is reset in the next epoch
+    pm.start_new_epoch(1);
+    assert!(pm.process_local_timeout(2));
+    assert!(pm.process_local_timeout(2));
+    assert!(!pm.process_local_timeout(2));
+}
+
+#[test]
+fn test_round_status() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    assert_eq!(pm.round_status(1), RoundStatus::Active);
+
+    assert!(pm.process_local_timeout(1));
+    assert_eq!(pm.round_status(1), RoundStatus::LocalTimeout);
+    assert_eq!(pm.round_status(2), RoundStatus::Active);
+
+    // Timeouts from the quorum of 3 authors form the timeout certificate
+    for signer in &signers[..2] {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+        assert_eq!(pm.round_status(1), RoundStatus::LocalTimeout);
+    }
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, &signers[2], None)));
+    assert_eq!(pm.round_status(1), RoundStatus::TimeoutCertificateFormed);
+    assert_eq!(pm.current_round(), 2);
+
+    block_on(pm.process_certificates(2, None, None));
+    assert_eq!(pm.round_status(2), RoundStatus::Certified);
+    assert_eq!(pm.round_status(3), RoundStatus::Active);
+}
+
+#[test]
+fn test_bootstrap_round_jitter() {
+    // make_pacemaker uses a fixed round duration of 2ms
+    let base = Duration::from_millis(2);
+    let mut first_round_timeouts = vec![];
+    for jitter_seed in 0..10 {
+        let mut config = PacemakerConfig::default();
+        config.round_jitter = Duration::from_millis(10);
+        config.bootstrap_round_jitter = Duration::from_millis(1000);
+        config.jitter_seed = jitter_seed;
+        let (mut pm, mut new_round_events_receiver) = make_pacemaker_with_config(config);
+        block_on(async {
+            let first_round = new_round_events_receiver.next().await.unwrap();
+            assert_eq!(first_round.round, 1);
+            assert!(first_round.timeout >= base);
+            assert!(first_round.timeout <= base + Duration::from_millis(1000));
+            first_round_timeouts.push(first_round.timeout);
+
+            for round in 1..5 {
+                pm.process_certificates(round, None, None).await;
+                let event = new_round_events_receiver.next().await.unwrap();
+                assert_eq!(event.round, round + 1);
+                assert!(event.timeout >= base);
+                assert!(event.timeout <= base + Duration::from_millis(10));
+            }
+        });
+    }
+    // The first round timeouts are spread across the validators
+    let min_timeout = first_round_timeouts.iter().min().unwrap();
+    let max_timeout = first_round_timeouts.iter().max().unwrap();
+    assert!(*max_timeout - *min_timeout > Duration::from_millis(10));
+}
+
+#[test]
+fn test_epoch_and_author_jitter_seed() {
+    let author = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]).author();
+    // Returns the timeouts of rounds 2 to 9 of a pacemaker started in the given epoch
+    let round_timeouts = |epoch: u64, author: Author| {
+        let mut config = PacemakerConfig::default();
+        config.round_jitter = Duration::from_millis(1000);
+        config.jitter_seed_policy = JitterSeedPolicy::EpochAndAuthor;
+        let (mut pm, mut new_round_events_receiver) = make_unstarted_pacemaker(config);
+        pm.set_local_author(author);
+        pm.start_new_epoch(epoch);
+        block_on(pm.start());
+        block_on(expect_qc(1, &mut new_round_events_receiver));
+        (1..9)
+            .map(|round| {
+                block_on(pm.process_certificates(round, None, None));
+                try_next_event(&mut new_round_events_receiver)
+                    .unwrap()
+                    .timeout
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Reproducible within an epoch, but shifted at the epoch boundary
+    assert_eq!(round_timeouts(1, author), round_timeouts(1, author));
+    assert_ne!(round_timeouts(1, author), round_timeouts(2, author));
+    // The validators jitter differently in the same epoch
+    let other_author = ValidatorSigner::<Ed25519PrivateKey>::random([1; 32]).author();
+    assert_ne!(round_timeouts(1, author), round_timeouts(1, other_author));
+}
+
+#[test]
+fn test_explicit_start() {
+    let (mut pm, mut new_round_events_receiver) =
+        make_unstarted_pacemaker(PacemakerConfig::default());
+    assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+
+    block_on(pm.start());
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+
+    // Starting again has no effect
+    block_on(pm.start());
+    assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+}
+
+#[test]
+fn test_quorum_fraction() {
+    let two_thirds = QuorumSpec::Fraction { num: 2, den: 3 };
+    assert_eq!(two_thirds.quorum_size(4), 3);
+    assert_eq!(two_thirds.quorum_size(7), 5);
+    assert_eq!(two_thirds.quorum_size(10), 7);
+    assert_eq!(QuorumSpec::Count(2).quorum_size(10), 2);
+
+    let mut config = PacemakerConfig::default();
+    config.quorum_spec = Some(two_thirds);
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    assert_eq!(pm.quorum_size(), 3);
+    pm.update_quorum_size(10);
+    assert_eq!(pm.quorum_size(), 7);
+    pm.update_quorum_size(7);
+    assert_eq!(pm.quorum_size(), 5);
+}
+
+#[test]
+fn test_timeout_coalescing() {
+    let mut config = PacemakerConfig::default();
+    config.timeout_coalescing_window = Duration::from_millis(100);
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    block_on(pm.process_certificates(2, None, None));
+
+    // Rounds 3, 4 and 5 time out within the window (the node catches up with the certificates of
+    // rounds 3 and 4 in the meantime): nothing is broadcast yet
+    for round in 3..=5 {
+        assert_eq!(pm.current_round(), round);
+        assert!(!pm.process_local_timeout(round));
+        if round < 5 {
+            block_on(pm.process_certificates(round, None, None));
+        }
+    }
+
+    // The windows expire: only the timeout of round 5 is broadcast
+    let broadcast: Vec<u64> = (3..=5)
+        .filter(|round| pm.process_local_timeout(*round))
+        .collect();
+    assert_eq!(broadcast, vec![5]);
+}
+
+#[derive(Debug)]
+struct MinSignersQcValidator {
+    min_signers: usize,
+}
+
+impl QcValidator for MinSignersQcValidator {
+    fn is_valid(&self, qc: &QuorumCert) -> bool {
+        qc.ledger_info().signatures().len() >= self.min_signers
+    }
+}
+
+#[test]
+fn test_qc_validator() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let qc_signed_by = |num_signers: usize| {
+        placeholder_certificate_for_block(
+            signers[..num_signers].iter().collect(),
+            HashValue::zero(),
+            1,
+            HashValue::zero(),
+            0,
+            HashValue::zero(),
+            0,
+        )
+    };
+    let mut config = PacemakerConfig::default();
+    config.qc_validator = Some(Arc::new(MinSignersQcValidator { min_signers: 3 }));
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+
+    block_on(pm.process_quorum_cert(&qc_signed_by(2), None, None));
+    assert_eq!(pm.current_round(), 1);
+    block_on(pm.process_quorum_cert(&qc_signed_by(3), None, None));
+    assert_eq!(pm.current_round(), 2);
+}
+
+#[test]
+fn test_non_monotonic_clock() {
+    let time_service = Arc::new(ManualTimeService::default());
+    *time_service.now.lock().unwrap() = Duration::from_secs(100);
+    let (mut pm, _new_round_events_receiver) = make_unstarted_pacemaker_with_time_service(
+        PacemakerConfig::default(),
+        time_service.clone(),
+    );
+    assert_eq!(pm.time_until_timeout(), None);
+    block_on(pm.start());
+    assert_eq!(pm.time_until_timeout(), Some(Duration::from_millis(2)));
+    *time_service.now.lock().unwrap() = Duration::from_secs(100) + Duration::from_millis(5);
+    assert_eq!(pm.time_until_timeout(), Some(Duration::from_millis(0)));
+
+    // The clock goes back before the start of the round
+    *time_service.now.lock().unwrap() = Duration::from_secs(99);
+    assert_eq!(pm.time_until_timeout(), Some(Duration::from_millis(0)));
+    assert!(pm.process_local_timeout(1));
+}
+
+#[test]
+fn test_decision_log_replay() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let mut config = PacemakerConfig::default();
+    config.record_decision_log = true;
+    config.startup_grace_period = Duration::from_millis(10);
+
+    // Capture a short session, whose first local timeout is within the startup grace period
+    let time_service = Arc::new(ManualTimeService::default());
+    let (mut pm, mut new_round_events_receiver) =
+        make_unstarted_pacemaker_with_time_service(config.clone(), time_service.clone());
+    block_on(pm.start());
+    *time_service.now.lock().unwrap() = Duration::from_millis(5);
+    assert!(!pm.process_local_timeout(1));
+    *time_service.now.lock().unwrap() = Duration::from_millis(20);
+    assert!(pm.process_local_timeout(1));
+    for signer in &signers {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+    }
+    block_on(pm.process_certificates(2, None, None));
+    assert!(!pm.process_local_timeout(2));
+    let mut captured_events = vec![];
+    while let Some(event) = try_next_event(&mut new_round_events_receiver) {
+        captured_events.push(event);
+    }
+    assert_eq!(captured_events.len(), 3);
+
+    let log = pm.decision_log().unwrap();
+    let recorded_events: Vec<NewRoundEvent> = log
+        .outputs()
+        .into_iter()
+        .filter_map(|output| match output {
+            PacemakerOutput::NewRound(event) => Some(event),
+            PacemakerOutput::BroadcastTimeout(_) => None,
+        })
+        .collect();
+    assert_eq!(recorded_events, captured_events);
+
+    // Replay the persisted log in a fresh pacemaker, whose clock follows the recorded timestamps
+    let log = PacemakerDecisionLog::from_bytes(&log.to_bytes().unwrap()).unwrap();
+    let mut unrecorded_config = config.clone();
+    unrecorded_config.record_decision_log = false;
+    let (mut unrecorded_pm, _) = make_unstarted_pacemaker(unrecorded_config);
+    assert!(replay(&log, &mut unrecorded_pm, |_| ()).is_err());
+    let replayed_time_service = Arc::new(ManualTimeService::default());
+    let (mut replayed_pm, mut replayed_events_receiver) =
+        make_unstarted_pacemaker_with_time_service(config, replayed_time_service.clone());
+    let replayed_outputs = replay(&log, &mut replayed_pm, |timestamp| {
+        *replayed_time_service.now.lock().unwrap() = timestamp
+    })
+    .unwrap();
+    assert_eq!(replayed_outputs, log.outputs());
+    let mut replayed_events = vec![];
+    while let Some(event) = try_next_event(&mut replayed_events_receiver) {
+        replayed_events.push(event);
+    }
+    assert_eq!(replayed_events, captured_events);
+}
+
+#[test]
+fn test_advance_on_attached_timeout_certificate() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let tc = PacemakerTimeoutCertificate::new(
+        8,
+        signers
+            .iter()
+            .map(|signer| PacemakerTimeout::new(0, 8, signer, None))
+            .collect(),
+    );
+    let timeout =
+        PacemakerTimeout::new_with_highest_timeout_certificate(0, 8, &signers[0], None, Some(tc));
+
+    // The attached certificate is ignored unless configured
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    block_on(pm.process_remote_timeout(timeout.clone()));
+    assert_eq!(pm.current_round(), 1);
+
+    let mut config = PacemakerConfig::default();
+    config.attach_highest_timeout_certificate = true;
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker_with_config(config);
+    block_on(async move {
+        expect_qc(1, &mut new_round_events_receiver).await;
+        // A single timeout is received, the attached certificate is enough to advance
+        pm.process_remote_timeout(timeout).await;
+        assert_eq!(pm.current_round(), 9);
+        expect_timeout(9, &mut new_round_events_receiver).await;
+        assert!(pm.timeout_certificate_to_attach().is_some());
+    });
+}
+
+#[test]
+fn test_set_time_interval() {
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker();
+    assert_eq!(
+        pm.set_time_interval(Box::new(ScriptedTimeInterval {
+            durations: vec![Duration::from_millis(0)],
+        })),
+        Err(PacemakerError::ZeroRoundDuration { round_index: 0 })
+    );
+    pm.set_time_interval(Box::new(ExponentialTimeInterval::fixed(
+        Duration::from_millis(100),
+    )))
+    .unwrap();
+    block_on(async move {
+        let event = new_round_events_receiver.next().await.unwrap();
+        assert_eq!(event.timeout, Duration::from_millis(2));
+        // The current round keeps its interval, also for the timeout retries
+        assert!(pm.process_local_timeout(1));
+        assert_eq!(pm.time_until_timeout(), Some(Duration::from_millis(2)));
+
+        pm.process_certificates(1, None, None).await;
+        let event = new_round_events_receiver.next().await.unwrap();
+        assert_eq!(event.round, 2);
+        assert_eq!(event.timeout, Duration::from_millis(100));
+    });
+}
+
+#[test]
+fn test_projected_schedule() {
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    let interval = ExponentialTimeInterval::new(Duration::from_millis(100), 2.0, 3);
+    pm.set_time_interval(Box::new(interval.clone())).unwrap();
+    // The new interval applies from the next round on
+    assert_eq!(
+        pm.projected_schedule(2),
+        vec![
+            (1, Duration::from_millis(2)),
+            (2, interval.get_round_duration(1))
+        ]
+    );
+
+    // The round indices start after the highest committed round
+    block_on(pm.process_certificates(5, Some(2), None));
+    let schedule = pm.projected_schedule(6);
+    assert_eq!(schedule.len(), 6);
+    for (i, (round, duration)) in schedule.into_iter().enumerate() {
+        assert_eq!(round, 6 + i as Round);
+        assert_eq!(duration, interval.get_round_duration(round as usize - 5));
+    }
+    assert!(pm.projected_schedule(0).is_empty());
+}
+
 fn make_pacemaker() -> (Pacemaker, channel::Receiver<NewRoundEvent>) {
+    make_pacemaker_with_config(PacemakerConfig::default())
+}
+
+fn make_pacemaker_with_config(
+    config: PacemakerConfig,
+) -> (Pacemaker, channel::Receiver<NewRoundEvent>) {
+    let (mut pm, new_round_events_receiver) = make_unstarted_pacemaker(config);
+    block_on(pm.start());
+    (pm, new_round_events_receiver)
+}
+
+fn make_pacemaker_with_election(
+    proposer_election: Box<dyn ProposerElection<TestPayload> + Send + Sync>,
+) -> (Pacemaker, channel::Receiver<NewRoundEvent>) {
+    let (mut pm, new_round_events_receiver) = make_unstarted_pacemaker(PacemakerConfig::default());
+    pm.set_proposer_election(proposer_election);
+    block_on(pm.start());
+    (pm, new_round_events_receiver)
+}
+
+fn make_unstarted_pacemaker(
+    config: PacemakerConfig,
+) -> (Pacemaker, channel::Receiver<NewRoundEvent>) {
+    make_unstarted_pacemaker_with_time_service(config, Arc::new(SimulatedTimeService::new()))
+}
+
+fn make_unstarted_pacemaker_with_time_service(
+    config: PacemakerConfig,
+    time_service: Arc<dyn TimeService>,
+) -> (Pacemaker, channel::Receiver<NewRoundEvent>) {
+    let (pm, new_round_events_receiver, _) =
+        make_unstarted_pacemaker_with_timeout_receiver(config, time_service);
+    (pm, new_round_events_receiver)
+}
+
+/// Same as `make_unstarted_pacemaker_with_time_service`, but also returns the receiver of the
+/// local timeouts scheduled by the pacemaker.
+fn make_unstarted_pacemaker_with_timeout_receiver(
+    config: PacemakerConfig,
+    time_service: Arc<dyn TimeService>,
+) -> (
+    Pacemaker,
+    channel::Receiver<NewRoundEvent>,
+    channel::Receiver<Round>,
+) {
+    make_unstarted_pacemaker_with_storage(
+        config,
+        time_service,
+        MockStorage::<TestPayload>::start_for_testing()
+            .0
+            .persistent_liveness_storage(),
+    )
+}
+
+fn make_unstarted_pacemaker_with_storage(
+    config: PacemakerConfig,
+    time_service: Arc<dyn TimeService>,
+    persistent_liveness_storage: Box<dyn PersistentLivenessStorage>,
+) -> (
+    Pacemaker,
+    channel::Receiver<NewRoundEvent>,
+    channel::Receiver<Round>,
+) {
     let time_interval = Box::new(ExponentialTimeInterval::fixed(Duration::from_millis(2)));
-    let simulated_time = SimulatedTimeService::new();
     let (new_round_events_sender, new_round_events_receiver) = channel::new_test(1_024);
-    let (pacemaker_timeout_tx, _) = channel::new_test(1_024);
+    let (pacemaker_timeout_tx, pacemaker_timeout_rx) = channel::new_test(1_024);
     (
-        Pacemaker::new(
-            MockStorage::<TestPayload>::start_for_testing()
-                .0
-                .persistent_liveness_storage(),
+        PacemakerBuilder::new(
+            persistent_liveness_storage,
             time_interval,
-            0,
-            0,
-            Arc::new(simulated_time.clone()),
+            time_service,
             new_round_events_sender,
             pacemaker_timeout_tx,
             3,
-            HighestTimeoutCertificates::new(None, None),
-        ),
+        )
+        .config(config)
+        .build(),
         new_round_events_receiver,
+        pacemaker_timeout_rx,
     )
 }
 
+/// Advances the simulated clock by the given duration (running the tasks due in the meantime),
+/// and fails if any new round event arrives in the meantime.
+fn assert_no_event_before(
+    rx: &mut channel::Receiver<NewRoundEvent>,
+    time_service: &mut SimulatedTimeService,
+    duration: Duration,
+) {
+    if let Some(event) = try_next_event(rx) {
+        panic!("Unexpected event before advancing the clock: {}", event);
+    }
+    time_service.update_auto_advance_limit(duration);
+    if let Some(event) = try_next_event(rx) {
+        panic!("Unexpected event within {:?}: {}", duration, event);
+    }
+}
+
+/// Returns the next message if one is already available, without waiting for it.
+fn try_next_event<T>(rx: &mut channel::Receiver<T>) -> Option<T> {
+    match block_on(future::select(rx.next(), future::ready(()))) {
+        Either::Left((event, _)) => event,
+        Either::Right(_) => None,
+    }
+}
+
 async fn expect_qc(round: u64, rx: &mut channel::Receiver<NewRoundEvent>) {
     let event: NewRoundEvent = rx.next().await.unwrap();
     assert_eq!(round, event.round);
@@ -148,3 +882,3386 @@ async fn expect_timeout(round: u64, rx: &mut channel::Receiver<NewRoundEvent>) {
         x => panic!("Expected timeout for round {}, got {:?}", round, x),
     };
 }
+
+/// Processes the remote timeout and checks both its outcome and the matching counter.
+fn expect_ingest_result(
+    pm: &mut Pacemaker,
+    pacemaker_timeout: PacemakerTimeout,
+    expected: TimeoutIngestResult,
+) {
+    // The counters are global, other tests may increase them concurrently
+    let count = expected.counter().get();
+    assert_eq!(
+        block_on(pm.process_remote_timeout(pacemaker_timeout)),
+        expected
+    );
+    assert!(expected.counter().get() > count);
+}
+
+#[test]
+fn test_timeout_ingest_result() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..4u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    // The last signer is not a validator, and the key of the third one is mismatched
+    let mut author_to_public_keys: HashMap<_, _> = signers[..2]
+        .iter()
+        .map(|signer| (signer.author(), signer.public_key()))
+        .collect();
+    author_to_public_keys.insert(signers[2].author(), signers[3].public_key());
+    let mut config = PacemakerConfig::default();
+    config.max_future_timeout_rounds = Some(5);
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    pm.set_validator_verifier(Arc::new(ValidatorVerifier::new(author_to_public_keys)));
+
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 1, &signers[3], None),
+        TimeoutIngestResult::UnknownAuthor,
+    );
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 1, &signers[2], None),
+        TimeoutIngestResult::BadSignature,
+    );
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 7, &signers[0], None),
+        TimeoutIngestResult::FutureRound,
+    );
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 2, &signers[0], None),
+        TimeoutIngestResult::Accepted,
+    );
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 1, &signers[0], None),
+        TimeoutIngestResult::Duplicate,
+    );
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 2, &signers[0], None),
+        TimeoutIngestResult::Duplicate,
+    );
+
+    block_on(pm.process_certificates(2, None, None));
+    assert_eq!(pm.current_round(), 3);
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 2, &signers[1], None),
+        TimeoutIngestResult::StaleRound,
+    );
+    // The horizon of the future rounds moves with the current round
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 8, &signers[1], None),
+        TimeoutIngestResult::Accepted,
+    );
+}
+
+#[test]
+fn test_single_round() {
+    let event = Pacemaker::single_round(
+        Box::new(ExponentialTimeInterval::fixed(Duration::from_millis(2))),
+        0,
+        0,
+        HighestTimeoutCertificates::new(None, None),
+        PacemakerConfig::default(),
+    );
+    assert_eq!(
+        event,
+        NewRoundEvent {
+            round: 1,
+            reason: NewRoundReason::QCReady,
+            timeout: Duration::from_millis(2),
+            sequence: 0,
+            proposal_deadline: Duration::from_millis(2),
+            skipped: 0,
+        }
+    );
+
+    // The first round follows the highest timeout certificate, with the matching backoff
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let tc = PacemakerTimeoutCertificate::new(
+        4,
+        signers
+            .iter()
+            .map(|signer| PacemakerTimeout::new(0, 4, signer, None))
+            .collect(),
+    );
+    let event = Pacemaker::single_round(
+        Box::new(ExponentialTimeInterval::new(
+            Duration::from_millis(100),
+            2.0,
+            5,
+        )),
+        0,
+        2,
+        HighestTimeoutCertificates::new(None, Some(tc)),
+        PacemakerConfig::default(),
+    );
+    assert_eq!(event.round, 5);
+    assert_eq!(event.reason, NewRoundReason::QCReady);
+    assert_eq!(event.timeout, Duration::from_millis(1600));
+}
+
+/// Sends the timeouts of round 1 from a quorum of 3 authors, the last one with a quorum
+/// certificate for round 5 that is rejected by the QcValidator.
+fn timeouts_with_bad_qc(bad_qc_policy: BadQcPolicy) -> (Pacemaker, TimeoutIngestResult) {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let bad_qc = placeholder_certificate_for_block(
+        signers[..2].iter().collect(),
+        HashValue::zero(),
+        5,
+        HashValue::zero(),
+        4,
+        HashValue::zero(),
+        3,
+    );
+    let mut config = PacemakerConfig::default();
+    config.qc_validator = Some(Arc::new(MinSignersQcValidator { min_signers: 3 }));
+    config.bad_qc_policy = bad_qc_policy;
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    for signer in &signers[..2] {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+    }
+    let result = block_on(
+        pm.process_remote_timeout_with_qc(PacemakerTimeout::new(0, 1, &signers[2], None), &bad_qc),
+    );
+    (pm, result)
+}
+
+#[test]
+fn test_bad_qc_policy_drop_timeout() {
+    let count = TimeoutIngestResult::BadQuorumCert.counter().get();
+    let (pm, result) = timeouts_with_bad_qc(BadQcPolicy::DropTimeout);
+    assert_eq!(result, TimeoutIngestResult::BadQuorumCert);
+    assert!(TimeoutIngestResult::BadQuorumCert.counter().get() > count);
+    // Neither the quorum certificate nor the timeout is taken into account
+    assert_eq!(pm.current_round(), 1);
+    assert!(pm.highest_timeout_certificate().is_none());
+}
+
+#[test]
+fn test_bad_qc_policy_ignore_qc() {
+    let (pm, result) = timeouts_with_bad_qc(BadQcPolicy::IgnoreQc);
+    assert_eq!(result, TimeoutIngestResult::Accepted);
+    // The timeout completes the quorum of round 1, the quorum certificate of round 5 is discarded
+    assert_eq!(pm.highest_timeout_certificate().unwrap().round(), 1);
+    assert_eq!(pm.current_round(), 2);
+}
+
+#[test]
+fn test_estimated_round_rate() {
+    let time_service = Arc::new(ManualTimeService::default());
+    let (mut pm, _new_round_events_receiver) = make_unstarted_pacemaker_with_time_service(
+        PacemakerConfig::default(),
+        time_service.clone(),
+    );
+    block_on(pm.start());
+    assert_eq!(pm.estimated_round_rate(), None);
+
+    // Every round is certified 250ms after it starts
+    for round in 1..5 {
+        *time_service.now.lock().unwrap() += Duration::from_millis(250);
+        block_on(pm.process_certificates(round, None, None));
+    }
+    assert_eq!(pm.current_round(), 5);
+    let rate = pm.estimated_round_rate().unwrap();
+    assert!((rate - 4.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_ewma_round_rate() {
+    // Returns the estimated rate after 8 rounds of 100ms followed by 4 rounds of 1s
+    let estimated_rate = |round_rate_ewma_alpha: Option<f64>| {
+        let time_service = Arc::new(ManualTimeService::default());
+        let mut config = PacemakerConfig::default();
+        config.round_rate_ewma_alpha = round_rate_ewma_alpha;
+        let (mut pm, _new_round_events_receiver) =
+            make_unstarted_pacemaker_with_time_service(config, time_service.clone());
+        block_on(pm.start());
+        for round in 1..13 {
+            let duration = if round <= 8 { 100 } else { 1000 };
+            *time_service.now.lock().unwrap() += Duration::from_millis(duration);
+            block_on(pm.process_certificates(round, None, None));
+        }
+        pm.estimated_round_rate().unwrap()
+    };
+
+    // The simple average is 400ms per round, the moving average follows the slowdown to 1 round
+    // per second much closer
+    let average_rate = estimated_rate(None);
+    assert!((average_rate - 2.5).abs() < 1e-9);
+    let ewma_rate = estimated_rate(Some(0.5));
+    assert!((ewma_rate - 1.0 / 0.94375).abs() < 1e-9);
+    assert!((ewma_rate - 1.0).abs() < (average_rate - 1.0).abs());
+}
+
+#[test]
+fn test_recent_reasons() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let make_tc = |round: Round| {
+        PacemakerTimeoutCertificate::new(
+            round,
+            vec![PacemakerTimeout::new(0, round, &signer, None)],
+        )
+    };
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    assert!(pm.recent_reasons(3).is_empty());
+
+    block_on(pm.process_certificates(1, None, None));
+    block_on(pm.process_certificates(1, None, Some(&make_tc(2))));
+    block_on(pm.process_certificates(3, None, None));
+    block_on(pm.process_certificates(3, None, Some(&make_tc(4))));
+    block_on(pm.process_certificates(3, None, Some(&make_tc(5))));
+    assert_eq!(pm.current_round(), 6);
+
+    let timeout_reason = |round: Round| NewRoundReason::Timeout {
+        cert: make_tc(round),
+        external: true,
+    };
+    assert_eq!(
+        pm.recent_reasons(3),
+        vec![
+            NewRoundReason::QCReady,
+            timeout_reason(4),
+            timeout_reason(5)
+        ]
+    );
+    assert_eq!(
+        pm.recent_reasons(10),
+        vec![
+            NewRoundReason::QCReady,
+            timeout_reason(2),
+            NewRoundReason::QCReady,
+            timeout_reason(4),
+            timeout_reason(5)
+        ]
+    );
+    assert!(pm.recent_reasons(0).is_empty());
+}
+
+#[test]
+fn test_qc_cancels_round_timeout() {
+    let mut time_service = SimulatedTimeService::new();
+    let (mut pm, _new_round_events_receiver, mut timeout_receiver) =
+        make_unstarted_pacemaker_with_timeout_receiver(
+            PacemakerConfig::default(),
+            Arc::new(time_service.clone()),
+        );
+    block_on(pm.start());
+
+    // The QC arrives before the timeout of round 1 fires
+    block_on(pm.process_certificates(1, None, None));
+    assert_eq!(pm.current_round(), 2);
+    time_service.update_auto_advance_limit(Duration::from_millis(10));
+    assert_eq!(try_next_event(&mut timeout_receiver), Some(2));
+    assert_eq!(try_next_event(&mut timeout_receiver), None);
+}
+
+#[test]
+fn test_early_timeout() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..2u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let mut config = PacemakerConfig::default();
+    config.early_timeout_fraction = Some((2, 3));
+    // The time does not advance: the timer of round 1 never fires
+    let (mut pm, _new_round_events_receiver, mut timeout_receiver) =
+        make_unstarted_pacemaker_with_timeout_receiver(
+            config,
+            Arc::new(SimulatedTimeService::new()),
+        );
+    block_on(pm.start());
+
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, &signers[0], None)));
+    assert_eq!(try_next_event(&mut timeout_receiver), None);
+    // 2 of the quorum of 3 timed out, the local timeout is triggered right away
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, &signers[1], None)));
+    assert_eq!(try_next_event(&mut timeout_receiver), Some(1));
+    assert!(pm.process_local_timeout(1));
+    assert_eq!(try_next_event(&mut timeout_receiver), None);
+}
+
+#[test]
+fn test_new_round_event_serialization() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let cert = PacemakerTimeoutCertificate::new(
+        3,
+        signers
+            .iter()
+            .map(|signer| PacemakerTimeout::new(0, 3, signer, None))
+            .collect(),
+    );
+    let events = vec![
+        (
+            NewRoundEvent {
+                round: 2,
+                reason: NewRoundReason::QCReady,
+                timeout: Duration::from_millis(1000),
+                sequence: 0,
+                proposal_deadline: Duration::from_millis(1000),
+                skipped: 0,
+            },
+            "qc_ready",
+        ),
+        (
+            NewRoundEvent {
+                round: 4,
+                reason: NewRoundReason::Timeout {
+                    cert,
+                    external: true,
+                },
+                timeout: Duration::from_millis(1500),
+                sequence: 3,
+                proposal_deadline: Duration::from_millis(1500),
+                skipped: 0,
+            },
+            "timeout",
+        ),
+    ];
+    for (event, tag) in events {
+        let bytes = event.to_bytes().unwrap();
+        // The variant is identified by its explicit tag
+        assert!(bytes
+            .windows(tag.len())
+            .any(|window| window == tag.as_bytes()));
+        assert_eq!(NewRoundEvent::from_bytes(&bytes).unwrap(), event);
+    }
+}
+
+#[test]
+fn test_max_qc_advance() {
+    let mut config = PacemakerConfig::default();
+    config.max_qc_advance = Some(10);
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker_with_config(config);
+    let (state_sync_sender, mut state_sync_receiver) = channel::new_test(1_024);
+    pm.observers_mut().state_sync = Some(state_sync_sender);
+    assert_eq!(
+        try_next_event(&mut new_round_events_receiver)
+            .unwrap()
+            .round,
+        1
+    );
+
+    // The QC of round 1000 is not followed, the state sync to round 1000 is requested instead
+    block_on(pm.process_certificates(1000, None, None));
+    assert_eq!(pm.current_round(), 1);
+    assert!(try_next_event(&mut new_round_events_receiver).is_none());
+    assert_eq!(try_next_event(&mut state_sync_receiver), Some(1000));
+
+    // A QC within the limit still advances the round at once
+    block_on(pm.process_certificates(10, None, None));
+    assert_eq!(pm.current_round(), 11);
+    assert!(try_next_event(&mut state_sync_receiver).is_none());
+}
+
+#[test]
+fn test_has_local_timeout() {
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    assert!(!pm.has_local_timeout(1));
+
+    assert!(pm.process_local_timeout(1));
+    assert!(pm.has_local_timeout(1));
+    assert!(!pm.has_local_timeout(2));
+}
+
+/// Captures the broadcast timeouts, and the timeouts sent to targeted validators.
+#[derive(Default)]
+struct MockTimeoutBroadcaster {
+    timeouts: Mutex<Vec<PacemakerTimeout>>,
+    targeted_timeouts: Mutex<Vec<(PacemakerTimeout, Vec<Author>)>>,
+}
+
+impl TimeoutBroadcaster for MockTimeoutBroadcaster {
+    fn broadcast(&self, timeout: PacemakerTimeout) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        self.timeouts.lock().unwrap().push(timeout);
+        future::ready(()).boxed()
+    }
+
+    fn send_to(
+        &self,
+        timeout: PacemakerTimeout,
+        recipients: Vec<Author>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        self.targeted_timeouts
+            .lock()
+            .unwrap()
+            .push((timeout, recipients));
+        future::ready(()).boxed()
+    }
+}
+
+#[test]
+fn test_timeout_broadcaster() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0u8; 32]);
+    let broadcaster = Arc::new(MockTimeoutBroadcaster::default());
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    pm.set_timeout_broadcaster(broadcaster.clone());
+
+    // Round 1 did not time out yet
+    block_on(pm.broadcast_local_timeout(PacemakerTimeout::new(0, 1, &signer, None)));
+    assert!(broadcaster.timeouts.lock().unwrap().is_empty());
+
+    assert!(pm.process_local_timeout(1));
+    let timeout = PacemakerTimeout::new(0, 1, &signer, None);
+    block_on(pm.broadcast_local_timeout(timeout.clone()));
+    assert_eq!(*broadcaster.timeouts.lock().unwrap(), vec![timeout]);
+}
+
+/// Records the rounds that ended in a timeout.
+#[derive(Default)]
+struct RecordingRoundTimeoutObserver {
+    rounds: Mutex<Vec<Round>>,
+}
+
+impl RoundTimeoutObserver for RecordingRoundTimeoutObserver {
+    fn round_timed_out(&self, round: Round) {
+        self.rounds.lock().unwrap().push(round);
+    }
+}
+
+#[test]
+fn test_round_timeout_observer() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let make_tc = |round| {
+        PacemakerTimeoutCertificate::new(
+            round,
+            signers
+                .iter()
+                .map(|signer| PacemakerTimeout::new(0, round, signer, None))
+                .collect(),
+        )
+    };
+    let observer = Arc::new(RecordingRoundTimeoutObserver::default());
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    pm.observers_mut().round_timeout_observer = Some(observer.clone());
+
+    block_on(pm.process_certificates(0, None, Some(&make_tc(1))));
+    // The rounds advancing on a QC did not time out
+    block_on(pm.process_certificates(3, None, None));
+    block_on(pm.process_certificates(3, None, Some(&make_tc(6))));
+    assert_eq!(pm.current_round(), 7);
+    assert_eq!(*observer.rounds.lock().unwrap(), vec![1, 4, 5, 6]);
+}
+
+#[test]
+fn test_certificate_tie_break() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let make_tc = |round| {
+        PacemakerTimeoutCertificate::new(
+            round,
+            signers
+                .iter()
+                .map(|signer| PacemakerTimeout::new(0, round, signer, None))
+                .collect(),
+        )
+    };
+
+    // The quorum certificate wins by default
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker();
+    block_on(async move {
+        expect_qc(1, &mut new_round_events_receiver).await;
+        for round in &[1, 3, 5] {
+            pm.process_certificates(*round, None, Some(&make_tc(*round)))
+                .await;
+            expect_qc(round + 1, &mut new_round_events_receiver).await;
+        }
+    });
+
+    let mut config = PacemakerConfig::default();
+    config.certificate_tie_break = CertificateTieBreak::PreferTimeout;
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker_with_config(config);
+    block_on(async move {
+        expect_qc(1, &mut new_round_events_receiver).await;
+        for round in &[1, 3, 5] {
+            pm.process_certificates(*round, None, Some(&make_tc(*round)))
+                .await;
+            expect_timeout(round + 1, &mut new_round_events_receiver).await;
+        }
+    });
+}
+
+#[test]
+fn test_timeout_certificate_rounds() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    assert!(pm.timeout_certificate_rounds().is_empty());
+
+    // A timeout certificate is formed for round 3, the timeouts of round 5 are accumulating
+    for signer in &signers {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 3, signer, None)));
+    }
+    assert_eq!(pm.current_round(), 4);
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 5, &signers[0], None)));
+    assert_eq!(pm.timeout_certificate_rounds(), vec![3, 5]);
+}
+
+#[test]
+fn test_committed_round_timeout() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    block_on(pm.process_certificates(5, Some(5), None));
+    assert_eq!(pm.current_round(), 6);
+
+    // Dropped and counted apart from the merely stale timeouts
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 4, &signer, None),
+        TimeoutIngestResult::CommittedRound,
+    );
+    // The committed round itself is not worth a timeout either
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 5, &signer, None),
+        TimeoutIngestResult::CommittedRound,
+    );
+}
+
+#[test]
+fn test_timeout_of_previous_epoch() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let mut author_to_public_keys = HashMap::new();
+    author_to_public_keys.insert(signer.author(), signer.public_key());
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    pm.set_validator_verifier(Arc::new(ValidatorVerifier::new(author_to_public_keys)));
+    pm.start_new_epoch(1);
+    pm.start_new_epoch(2);
+
+    // The signature is valid and the round matches, but the timeout is replayed from epoch 1
+    let replayed_timeout = PacemakerTimeout::new(1, 1, &signer, None);
+    assert!(replayed_timeout
+        .verify(&ValidatorVerifier::new_single(
+            signer.author(),
+            signer.public_key()
+        ))
+        .is_ok());
+    expect_ingest_result(&mut pm, replayed_timeout, TimeoutIngestResult::WrongEpoch);
+    // The same timeout signed for the current epoch is accepted
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(2, 1, &signer, None),
+        TimeoutIngestResult::Accepted,
+    );
+}
+
+#[test]
+fn test_epoch_change_drops_received_timeouts() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    for signer in &signers[..2] {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+    }
+    assert_eq!(pm.timeouts_needed_for_quorum(1), 1);
+
+    // The timeouts of epoch 0 do not count toward a certificate with the ones of epoch 1
+    pm.start_new_epoch(1);
+    assert_eq!(pm.timeouts_needed_for_quorum(1), 3);
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(1, 1, &signers[2], None)));
+    assert_eq!(pm.current_round(), 1);
+    assert_eq!(pm.timeouts_needed_for_quorum(1), 2);
+
+    // Neither does a certificate mixing the epochs verify
+    let verifier = ValidatorVerifier::new(
+        signers
+            .iter()
+            .map(|signer| (signer.author(), signer.public_key()))
+            .collect(),
+    );
+    let mixed_tc = PacemakerTimeoutCertificate::new(
+        1,
+        vec![
+            PacemakerTimeout::new(0, 1, &signers[0], None),
+            PacemakerTimeout::new(0, 1, &signers[1], None),
+            PacemakerTimeout::new(1, 1, &signers[2], None),
+        ],
+    );
+    assert_eq!(
+        mixed_tc.verify(&verifier),
+        Err(PacemakerTimeoutCertificateVerificationError::EpochMismatch(
+            0, 1
+        ))
+    );
+    let tc = PacemakerTimeoutCertificate::new(
+        1,
+        signers
+            .iter()
+            .map(|signer| PacemakerTimeout::new(1, 1, signer, None))
+            .collect(),
+    );
+    assert_eq!(tc.verify(&verifier), Ok(()));
+}
+
+#[test]
+fn test_secondary_time_service() {
+    // The primary clock is stuck at zero and never runs the scheduled timeouts
+    let (mut pm, _new_round_events_receiver, mut timeout_receiver) =
+        make_unstarted_pacemaker_with_timeout_receiver(
+            PacemakerConfig::default(),
+            Arc::new(ManualTimeService::default()),
+        );
+    block_on(pm.start());
+    assert_eq!(try_next_event(&mut timeout_receiver), None);
+
+    let (mut pm, _new_round_events_receiver, mut timeout_receiver) =
+        make_unstarted_pacemaker_with_timeout_receiver(
+            PacemakerConfig::default(),
+            Arc::new(ManualTimeService::default()),
+        );
+    pm.set_secondary_time_service(Arc::new(SimulatedTimeService::auto_advance_until(
+        Duration::from_secs(10),
+    )));
+    block_on(pm.start());
+    // The secondary time service drives the round timeout instead
+    assert_eq!(try_next_event(&mut timeout_receiver), Some(1));
+    assert!(pm.process_local_timeout(1));
+
+    // The receipt times of the timeouts are read from the secondary time service too
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let secondary_time_service = Arc::new(ManualTimeService::default());
+    *secondary_time_service.now.lock().unwrap() = Duration::from_secs(100);
+    let (mut pm, _new_round_events_receiver) = make_unstarted_pacemaker_with_time_service(
+        PacemakerConfig::default(),
+        Arc::new(ManualTimeService::default()),
+    );
+    pm.set_secondary_time_service(secondary_time_service.clone());
+    block_on(pm.start());
+    for signer in &signers {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+        *secondary_time_service.now.lock().unwrap() += Duration::from_millis(250);
+    }
+    assert_eq!(pm.current_round(), 2);
+    assert_eq!(
+        pm.last_formation_latency(),
+        Some(Duration::from_millis(500))
+    );
+}
+
+#[test]
+fn test_certification_gap() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let make_tc = |round| {
+        PacemakerTimeoutCertificate::new(
+            round,
+            signers
+                .iter()
+                .map(|signer| PacemakerTimeout::new(0, round, signer, None))
+                .collect(),
+        )
+    };
+    let time_service = Arc::new(ManualTimeService::default());
+    *time_service.now.lock().unwrap() = Duration::from_secs(100);
+    let mut config = PacemakerConfig::default();
+    config.certification_gap_alert = Some((2, Duration::from_secs(1)));
+    let (mut pm, _new_round_events_receiver) =
+        make_unstarted_pacemaker_with_time_service(config, time_service.clone());
+    let (alert_sender, mut alert_receiver) = channel::new_test(1_024);
+    pm.observers_mut().certification_gap_alert = Some(alert_sender);
+    block_on(pm.start());
+    assert_eq!(pm.certification_gap(), 1);
+
+    // The gap grows with the rounds timing out, the alert fires once it exceeded the threshold
+    // for the whole period
+    block_on(pm.process_certificates(0, None, Some(&make_tc(1))));
+    assert_eq!(pm.certification_gap(), 2);
+    block_on(pm.process_certificates(0, None, Some(&make_tc(2))));
+    assert_eq!(pm.certification_gap(), 3);
+    *time_service.now.lock().unwrap() += Duration::from_millis(500);
+    block_on(pm.process_certificates(0, None, Some(&make_tc(3))));
+    assert_eq!(pm.certification_gap(), 4);
+    assert_eq!(try_next_event(&mut alert_receiver), None);
+    *time_service.now.lock().unwrap() += Duration::from_millis(600);
+    block_on(pm.process_certificates(0, None, Some(&make_tc(4))));
+    assert_eq!(try_next_event(&mut alert_receiver), Some(5));
+    // Sent once for a sustained excess
+    *time_service.now.lock().unwrap() += Duration::from_secs(2);
+    block_on(pm.process_certificates(0, None, Some(&make_tc(5))));
+    assert_eq!(try_next_event(&mut alert_receiver), None);
+
+    // A quorum certificate closes the gap
+    block_on(pm.process_certificates(6, None, None));
+    assert_eq!(pm.certification_gap(), 1);
+}
+
+/// Elects the given proposers by round (the last proposer is repeated for all the following
+/// rounds)
+struct ScriptedProposerElection {
+    proposers: Vec<Author>,
+}
+
+impl ScriptedProposerElection {
+    fn get_proposer(&self, round: Round) -> Author {
+        self.proposers[(round as usize).min(self.proposers.len() - 1)]
+    }
+}
+
+impl ProposerElection<TestPayload> for ScriptedProposerElection {
+    fn is_valid_proposer(&self, author: Author, round: Round) -> Option<Author> {
+        if self.get_proposer(round) == author {
+            Some(author)
+        } else {
+            None
+        }
+    }
+
+    fn get_valid_proposers(&self, round: Round) -> Vec<Author> {
+        vec![self.get_proposer(round)]
+    }
+
+    fn process_proposal(&self, proposal: Block<TestPayload>) -> Option<Block<TestPayload>> {
+        match proposal.author() {
+            Some(author) if author == self.get_proposer(proposal.round()) => Some(proposal),
+            _ => None,
+        }
+    }
+
+    fn validator_set_root(&self) -> HashValue {
+        HashValue::zero()
+    }
+
+    fn next_change_round(&self, _from_round: Round, _author: Author) -> Option<Round> {
+        None
+    }
+}
+
+#[test]
+fn test_pacemaker_with_election() {
+    let authors: Vec<Author> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]).author())
+        .collect();
+    let proposers = vec![authors[0], authors[2], authors[2], authors[1]];
+    let (mut pm, _new_round_events_receiver) =
+        make_pacemaker_with_election(Box::new(ScriptedProposerElection {
+            proposers: proposers.clone(),
+        }));
+    for round in 1..4 {
+        assert_eq!(pm.current_round(), round);
+        assert_eq!(
+            pm.proposers(pm.current_round()),
+            vec![proposers[round as usize]]
+        );
+        block_on(pm.process_certificates(round, None, None));
+    }
+    assert_eq!(pm.proposers(100), vec![authors[1]]);
+
+    // No proposer is reported without an election
+    let (pm, _new_round_events_receiver) = make_pacemaker();
+    assert!(pm.proposers(1).is_empty());
+}
+
+#[test]
+fn test_max_timeout_authors_per_round() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..20u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let mut config = PacemakerConfig::default();
+    config.max_timeout_authors_per_round = Some(5);
+    // A quorum large enough for the round not to advance
+    config.quorum_spec = Some(QuorumSpec::Count(100));
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    pm.update_quorum_size(100);
+
+    let dropped_count = TimeoutIngestResult::TooManyAuthors.counter().get();
+    for signer in &signers[..5] {
+        expect_ingest_result(
+            &mut pm,
+            PacemakerTimeout::new(0, 2, signer, None),
+            TimeoutIngestResult::Accepted,
+        );
+    }
+    for signer in &signers[5..] {
+        expect_ingest_result(
+            &mut pm,
+            PacemakerTimeout::new(0, 2, signer, None),
+            TimeoutIngestResult::TooManyAuthors,
+        );
+    }
+    assert_eq!(
+        TimeoutIngestResult::TooManyAuthors.counter().get() - dropped_count,
+        15
+    );
+    // The cap applies per round
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 3, &signers[0], None),
+        TimeoutIngestResult::Accepted,
+    );
+}
+
+#[test]
+fn test_install_highest_timeout_certificate() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..4u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let make_tc = |round: Round, signers: &[ValidatorSigner<Ed25519PrivateKey>]| {
+        PacemakerTimeoutCertificate::new(
+            round,
+            signers
+                .iter()
+                .map(|signer| PacemakerTimeout::new(0, round, signer, None))
+                .collect(),
+        )
+    };
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker();
+    pm.set_validator_verifier(Arc::new(ValidatorVerifier::new(
+        signers
+            .iter()
+            .map(|signer| (signer.author(), signer.public_key()))
+            .collect(),
+    )));
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    block_on(pm.process_certificates(2, None, None));
+    block_on(expect_qc(3, &mut new_round_events_receiver));
+
+    // A timeout certificate without a quorum is rejected
+    assert_eq!(
+        pm.install_highest_timeout_certificate(make_tc(12, &signers[..1]))
+            .err(),
+        Some(PacemakerError::InvalidTimeoutCertificate(
+            PacemakerTimeoutCertificateVerificationError::NoQuorum
+        ))
+    );
+    assert_eq!(pm.current_round(), 3);
+
+    block_on(
+        pm.install_highest_timeout_certificate(make_tc(12, &signers[..3]))
+            .unwrap(),
+    );
+    block_on(expect_timeout(13, &mut new_round_events_receiver));
+    assert_eq!(pm.current_round(), 13);
+
+    // The installed certificates must be higher than the highest one
+    for round in &[5, 12] {
+        assert_eq!(
+            pm.install_highest_timeout_certificate(make_tc(*round, &signers[..3]))
+                .err(),
+            Some(PacemakerError::StaleTimeoutCertificate {
+                round: *round,
+                highest_round: 12,
+            })
+        );
+    }
+    assert_eq!(pm.current_round(), 13);
+}
+
+#[test]
+fn test_new_round_event_sequence() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let tc = PacemakerTimeoutCertificate::new(
+        12,
+        signers
+            .iter()
+            .map(|signer| PacemakerTimeout::new(0, 12, signer, None))
+            .collect(),
+    );
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker();
+    block_on(pm.process_certificates(1, None, None));
+    // The rounds jump, the sequence does not
+    block_on(pm.process_certificates(7, None, None));
+    block_on(pm.process_certificates(7, None, Some(&tc)));
+    let events: Vec<NewRoundEvent> = (0..4)
+        .map(|_| try_next_event(&mut new_round_events_receiver).unwrap())
+        .collect();
+    assert_eq!(
+        events.iter().map(|event| event.round).collect::<Vec<_>>(),
+        vec![1, 2, 8, 13]
+    );
+    for (sequence, event) in events.iter().enumerate() {
+        assert_eq!(event.sequence, sequence as u64);
+    }
+}
+
+#[test]
+fn test_storage_failure_policy() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    for storage_failure_policy in &[StorageFailurePolicy::Halt, StorageFailurePolicy::Continue] {
+        let storage = MockStorage::<TestPayload>::start_for_testing().0;
+        storage
+            .shared_storage
+            .fail_liveness_writes
+            .store(true, Ordering::SeqCst);
+        let mut config = PacemakerConfig::default();
+        config.storage_failure_policy = *storage_failure_policy;
+        let (mut pm, _new_round_events_receiver, _timeout_receiver) =
+            make_unstarted_pacemaker_with_storage(
+                config,
+                Arc::new(SimulatedTimeService::new()),
+                storage.persistent_liveness_storage(),
+            );
+        block_on(pm.start());
+
+        // The timeout certificate of round 1 cannot be persisted
+        for signer in &signers {
+            block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+        }
+        match storage_failure_policy {
+            StorageFailurePolicy::Halt => {
+                assert!(pm.is_halted());
+                assert_eq!(pm.halt_reason(), Some(HaltReason::StorageFailure));
+                assert_eq!(pm.current_round(), 1);
+                assert!(!pm.process_local_timeout(1));
+                block_on(pm.process_certificates(3, None, None));
+                assert_eq!(pm.current_round(), 1);
+            }
+            StorageFailurePolicy::Continue => {
+                assert!(!pm.is_halted());
+                assert_eq!(pm.current_round(), 2);
+                assert!(pm.process_local_timeout(2));
+            }
+        }
+    }
+}
+
+/// Records the durations of the scheduled tasks, without running them.
+#[derive(Default)]
+struct RecordingTimeService {
+    scheduled: Mutex<Vec<Duration>>,
+}
+
+impl TimeService for RecordingTimeService {
+    fn run_after(&self, timeout: Duration, _task: Box<dyn ScheduledTask>) {
+        self.scheduled.lock().unwrap().push(timeout);
+    }
+
+    fn get_current_timestamp(&self) -> Duration {
+        Duration::from_millis(0)
+    }
+
+    fn sleep(&self, _t: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        future::ready(()).boxed()
+    }
+}
+
+#[test]
+fn test_proposer_timeout_policy() {
+    let authors: Vec<Author> = (0..2u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]).author())
+        .collect();
+    let mut config = PacemakerConfig::default();
+    config.proposer_timeout_policy = ProposerTimeoutPolicy::Delay(Duration::from_millis(50));
+
+    // The first author proposes every round
+    let mut scheduled_timeouts = vec![];
+    for (local_author, round_duration) in authors.iter().zip(&[52, 2]) {
+        let time_service = Arc::new(RecordingTimeService::default());
+        let (mut pm, mut new_round_events_receiver) =
+            make_unstarted_pacemaker_with_time_service(config.clone(), time_service.clone());
+        pm.set_proposer_election(Box::new(ScriptedProposerElection {
+            proposers: vec![authors[0]],
+        }));
+        pm.set_local_author(*local_author);
+        block_on(pm.start());
+        // The delay is part of the duration of the round
+        let round_duration = Duration::from_millis(*round_duration);
+        let event = try_next_event(&mut new_round_events_receiver).unwrap();
+        assert_eq!(event.timeout, round_duration);
+        assert_eq!(event.proposal_deadline, round_duration);
+        assert_eq!(pm.time_until_timeout(), Some(round_duration));
+        // The retries of the local timeout are not delayed
+        assert!(pm.process_local_timeout(1));
+        scheduled_timeouts.push(time_service.scheduled.lock().unwrap().clone());
+    }
+    assert_eq!(
+        scheduled_timeouts[0],
+        vec![Duration::from_millis(52), Duration::from_millis(2)]
+    );
+    assert_eq!(
+        scheduled_timeouts[1],
+        vec![Duration::from_millis(2), Duration::from_millis(2)]
+    );
+}
+
+/// Time service whose clock advances by the step at every reading.
+struct AdvancingTimeService {
+    now: Mutex<Duration>,
+    step: Duration,
+}
+
+impl TimeService for AdvancingTimeService {
+    fn run_after(&self, _timeout: Duration, _task: Box<dyn ScheduledTask>) {}
+
+    fn get_current_timestamp(&self) -> Duration {
+        let mut now = self.now.lock().unwrap();
+        *now += self.step;
+        *now
+    }
+
+    fn sleep(&self, _t: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        future::ready(()).boxed()
+    }
+}
+
+#[test]
+fn test_slow_verification_threshold() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let author_to_public_keys: HashMap<_, _> = vec![(signer.author(), signer.public_key())]
+        .into_iter()
+        .collect();
+    let verifier = Arc::new(ValidatorVerifier::new(author_to_public_keys));
+
+    // Every reading of the clock is 1s later, the verifications look slow
+    for (threshold, slow) in &[
+        (Duration::from_millis(500), true),
+        (Duration::from_secs(5), false),
+    ] {
+        let mut config = PacemakerConfig::default();
+        config.slow_verification_threshold = Some(*threshold);
+        let (mut pm, _new_round_events_receiver) = make_unstarted_pacemaker_with_time_service(
+            config,
+            Arc::new(AdvancingTimeService {
+                now: Mutex::new(Duration::from_secs(100)),
+                step: Duration::from_secs(1),
+            }),
+        );
+        pm.set_validator_verifier(verifier.clone());
+        block_on(pm.start());
+
+        let count = counters::SLOW_TIMEOUT_VERIFICATION_COUNT.get();
+        expect_ingest_result(
+            &mut pm,
+            PacemakerTimeout::new(0, 1, &signer, None),
+            TimeoutIngestResult::Accepted,
+        );
+        // No other test sets the threshold
+        let expected_count = if *slow { count + 1 } else { count };
+        assert_eq!(
+            counters::SLOW_TIMEOUT_VERIFICATION_COUNT.get(),
+            expected_count
+        );
+    }
+}
+
+#[test]
+fn test_from_snapshot() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..4u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    block_on(pm.process_certificates(4, Some(1), None));
+    for signer in &signers[..3] {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 5, signer, None)));
+    }
+    // Pending the next timeout certificate
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 6, &signers[3], None)));
+    assert!(pm.process_local_timeout(6));
+    assert_eq!(pm.current_round(), 6);
+
+    let (new_round_events_sender, mut new_round_events_receiver) = channel::new_test(1_024);
+    let (timeout_sender, _timeout_receiver) = channel::new_test(1_024);
+    // The timeouts of another epoch are not restored
+    let mut snapshot = pm.snapshot();
+    assert_eq!(snapshot.highest_remote_timeout_round, 6);
+    snapshot
+        .received_timeouts
+        .push(PacemakerTimeout::new(1, 7, &signers[0], None));
+    let mut standby = Pacemaker::from_snapshot(
+        snapshot,
+        MockStorage::<TestPayload>::start_for_testing()
+            .0
+            .persistent_liveness_storage(),
+        Box::new(ExponentialTimeInterval::fixed(Duration::from_millis(2))),
+        Arc::new(SimulatedTimeService::new()),
+        new_round_events_sender,
+        timeout_sender,
+        PacemakerConfig::default(),
+    );
+    assert_eq!(standby.current_round(), pm.current_round());
+    assert_eq!(
+        standby.highest_certified_round(),
+        pm.highest_certified_round()
+    );
+    assert_eq!(standby.highest_certified_round(), 4);
+    assert_eq!(
+        standby.highest_timeout_certificate(),
+        pm.highest_timeout_certificate()
+    );
+    assert_eq!(
+        standby.timeout_certificate_rounds(),
+        pm.timeout_certificate_rounds()
+    );
+    assert!(standby.has_local_timeout(6));
+    assert_eq!(
+        standby.export_pending_timeouts(),
+        pm.export_pending_timeouts()
+    );
+    assert_eq!(standby.snapshot().highest_remote_timeout_round, 6);
+    block_on(standby.start());
+    block_on(expect_qc(6, &mut new_round_events_receiver));
+
+    // The pending timeouts complete into the next timeout certificate on both
+    for pacemaker in &mut [pm, standby] {
+        for signer in &signers[..2] {
+            block_on(pacemaker.process_remote_timeout(PacemakerTimeout::new(0, 6, signer, None)));
+        }
+        assert_eq!(pacemaker.current_round(), 7);
+    }
+}
+
+#[test]
+fn test_proposal_deadline() {
+    let mut config = PacemakerConfig::default();
+    config.proposal_deadline_fraction = Some((2, 3));
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker_with_config(config);
+    pm.set_time_interval(Box::new(ExponentialTimeInterval::fixed(
+        Duration::from_millis(300),
+    )))
+    .unwrap();
+    block_on(pm.process_certificates(1, None, None));
+    let _ = try_next_event(&mut new_round_events_receiver).unwrap();
+    let event = try_next_event(&mut new_round_events_receiver).unwrap();
+    assert_eq!(event.round, 2);
+    assert_eq!(event.timeout, Duration::from_millis(300));
+    // The proposal is due a third of the round duration before the timeout
+    assert_eq!(event.proposal_deadline, Duration::from_millis(200));
+
+    // Due by the end of the round by default
+    let (_pm, mut new_round_events_receiver) = make_pacemaker();
+    let event = try_next_event(&mut new_round_events_receiver).unwrap();
+    assert_eq!(event.proposal_deadline, event.timeout);
+}
+
+#[test]
+fn test_assert_no_event_before() {
+    let mut time_service = SimulatedTimeService::new();
+    let (mut pm, mut new_round_events_receiver, mut timeout_receiver) =
+        make_unstarted_pacemaker_with_timeout_receiver(
+            PacemakerConfig::default(),
+            Arc::new(time_service.clone()),
+        );
+    block_on(pm.start());
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+
+    // The local timeout fires, but the round does not advance without a certificate
+    assert_no_event_before(
+        &mut new_round_events_receiver,
+        &mut time_service,
+        Duration::from_secs(10),
+    );
+    assert_eq!(try_next_event(&mut timeout_receiver), Some(1));
+    block_on(pm.process_certificates(1, None, None));
+    block_on(expect_qc(2, &mut new_round_events_receiver));
+}
+
+#[test]
+#[should_panic(expected = "Unexpected event")]
+fn test_assert_no_event_before_fails_on_event() {
+    let mut time_service = SimulatedTimeService::new();
+    let (mut pm, mut new_round_events_receiver) = make_unstarted_pacemaker_with_time_service(
+        PacemakerConfig::default(),
+        Arc::new(time_service.clone()),
+    );
+    // The event of the first round is not consumed
+    block_on(pm.start());
+    assert_no_event_before(
+        &mut new_round_events_receiver,
+        &mut time_service,
+        Duration::from_millis(1),
+    );
+}
+
+#[test]
+fn test_validator_label() {
+    let mut labeled_pacemakers = vec![];
+    for label in &["alice", "bob"] {
+        let mut config = PacemakerConfig::default();
+        config.validator_label = Some(label.to_string());
+        let (pm, mut new_round_events_receiver) = make_pacemaker_with_config(config);
+        block_on(expect_qc(1, &mut new_round_events_receiver));
+        labeled_pacemakers.push(pm);
+    }
+    assert_eq!(labeled_pacemakers[0].validator_label(), Some("alice"));
+    assert_eq!(labeled_pacemakers[0].log_prefix(), "[alice] ");
+    assert_eq!(labeled_pacemakers[1].validator_label(), Some("bob"));
+    assert_eq!(labeled_pacemakers[1].log_prefix(), "[bob] ");
+
+    // The log messages of an unlabeled pacemaker are not prefixed
+    let (pm, _) = make_pacemaker();
+    assert_eq!(pm.validator_label(), None);
+    assert_eq!(pm.log_prefix(), "");
+}
+
+#[test]
+fn test_heartbeats() {
+    let mut time_service = SimulatedTimeService::new();
+    let mut config = PacemakerConfig::default();
+    config.heartbeat_interval = Some(Duration::from_millis(300));
+    let (mut pm, mut new_round_events_receiver, mut timeout_receiver) =
+        make_unstarted_pacemaker_with_timeout_receiver(config, Arc::new(time_service.clone()));
+    pm.set_time_interval(Box::new(ExponentialTimeInterval::fixed(
+        Duration::from_millis(1000),
+    )))
+    .unwrap();
+    let (heartbeat_sender, mut heartbeat_receiver) = channel::new_test(1_024);
+    pm.observers_mut().heartbeat = Some(heartbeat_sender);
+    block_on(pm.start());
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+
+    // A heartbeat every 300ms while the round of 1s is active
+    time_service.update_auto_advance_limit(Duration::from_millis(999));
+    assert_eq!(try_next_event(&mut timeout_receiver), None);
+    for elapsed in &[300, 600, 900] {
+        assert_eq!(
+            try_next_event(&mut heartbeat_receiver),
+            Some(PacemakerHeartbeat {
+                round: 1,
+                elapsed: Duration::from_millis(*elapsed),
+            })
+        );
+    }
+    assert_eq!(try_next_event(&mut heartbeat_receiver), None);
+
+    // No more heartbeat once the round times out
+    time_service.update_auto_advance_limit(Duration::from_millis(1));
+    assert_eq!(try_next_event(&mut timeout_receiver), Some(1));
+    assert_eq!(try_next_event(&mut heartbeat_receiver), None);
+}
+
+#[test]
+fn test_catch_up_burst() {
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker();
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+
+    // The QCs of the burst enter the rounds 2..100 without emitting their events
+    pm.begin_catch_up();
+    for qc_round in 1..100 {
+        block_on(pm.process_certificates(qc_round, None, None));
+    }
+    assert_eq!(pm.current_round(), 100);
+    assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+
+    // Only the event of the last round is emitted
+    block_on(pm.end_catch_up());
+    let event = try_next_event(&mut new_round_events_receiver).unwrap();
+    assert_eq!(event.round, 100);
+    assert_eq!(event.reason, NewRoundReason::QCReady);
+    assert_eq!(event.skipped, 98);
+    assert_eq!(event.sequence, 1);
+    assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+
+    // An empty burst emits nothing, and the events are emitted again after the burst
+    pm.begin_catch_up();
+    block_on(pm.end_catch_up());
+    assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+    block_on(pm.process_certificates(100, None, None));
+    let event = try_next_event(&mut new_round_events_receiver).unwrap();
+    assert_eq!(event.round, 101);
+    assert_eq!(event.skipped, 0);
+}
+
+#[test]
+fn test_last_qc_advance_time() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker();
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    // Starting the first round is not an advance
+    assert_eq!(pm.last_qc_advance_time(), None);
+
+    block_on(pm.process_certificates(1, None, None));
+    block_on(expect_qc(2, &mut new_round_events_receiver));
+    let qc_advance_time = pm.last_qc_advance_time().unwrap();
+
+    // The rounds advanced by the timeout certificates leave the time unchanged
+    for round in &[2, 5] {
+        for signer in &signers {
+            block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, *round, signer, None)));
+        }
+        block_on(expect_timeout(*round + 1, &mut new_round_events_receiver));
+    }
+    assert_eq!(pm.current_round(), 6);
+    assert_eq!(pm.last_qc_advance_time(), Some(qc_advance_time));
+}
+
+#[test]
+fn test_past_timeout_rounds() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    for (past_timeout_rounds, expected) in &[
+        (0, TimeoutIngestResult::StaleRound),
+        (1, TimeoutIngestResult::Accepted),
+    ] {
+        let mut config = PacemakerConfig::default();
+        config.past_timeout_rounds = *past_timeout_rounds;
+        // The past rounds are not mistaken for future ones by the bound of the future rounds
+        config.max_future_timeout_rounds = Some(5);
+        let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+        block_on(pm.process_certificates(1, None, None));
+        assert_eq!(pm.current_round(), 2);
+        expect_ingest_result(
+            &mut pm,
+            PacemakerTimeout::new(0, 1, &signer, None),
+            *expected,
+        );
+    }
+}
+
+/// Extractor of a consensus variant whose timeouts certify the block voted by their author: the
+/// quorum certificate is made of the vote attached to the timeout.
+#[derive(Debug)]
+struct VoteQcExtractor;
+
+impl TimeoutQcExtractor for VoteQcExtractor {
+    fn extract_qc(
+        &self,
+        pacemaker_timeout: &PacemakerTimeout,
+        _attached_qc: Option<&QuorumCert>,
+    ) -> Option<QuorumCert> {
+        let vote = pacemaker_timeout.vote_msg()?;
+        let mut signatures = HashMap::new();
+        signatures.insert(vote.author(), vote.signature().clone());
+        Some(QuorumCert::new(
+            vote.proposed_block_id(),
+            vote.executed_state(),
+            vote.round(),
+            LedgerInfoWithSignatures::new(vote.ledger_info().clone(), signatures),
+            vote.parent_block_id(),
+            vote.parent_block_round(),
+            vote.grandparent_block_id(),
+            vote.grandparent_block_round(),
+        ))
+    }
+}
+
+#[test]
+fn test_timeout_qc_extractor() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let vote = VoteMsg::new(
+        HashValue::zero(),
+        ExecutedState::state_for_genesis(),
+        5,
+        HashValue::zero(),
+        4,
+        HashValue::zero(),
+        3,
+        signer.author(),
+        placeholder_ledger_info(),
+        &signer,
+    );
+    let timeout = PacemakerTimeout::new(0, 1, &signer, Some(vote));
+
+    // The default extractor ignores the vote
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    block_on(pm.process_remote_timeout(timeout.clone()));
+    assert_eq!(pm.current_round(), 1);
+
+    let mut config = PacemakerConfig::default();
+    config.timeout_qc_extractor = Some(Arc::new(VoteQcExtractor));
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker_with_config(config);
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    assert_eq!(
+        block_on(pm.process_remote_timeout(timeout)),
+        TimeoutIngestResult::StaleRound
+    );
+    block_on(expect_qc(6, &mut new_round_events_receiver));
+    assert_eq!(pm.current_round(), 6);
+}
+
+#[test]
+fn test_manual_advance() {
+    let mut config = PacemakerConfig::default();
+    config.manual_advance = true;
+    let (mut pm, mut new_round_events_receiver, mut timeout_receiver) =
+        make_unstarted_pacemaker_with_timeout_receiver(
+            config,
+            Arc::new(SimulatedTimeService::auto_advance_until(
+                Duration::from_secs(100),
+            )),
+        );
+    block_on(pm.start());
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    // The timers would have fired long ago
+    assert_eq!(try_next_event(&mut timeout_receiver), None);
+
+    // The quorum certificates still advance the round, without scheduling any timeout
+    block_on(pm.process_certificates(1, None, None));
+    block_on(expect_qc(2, &mut new_round_events_receiver));
+    assert_eq!(try_next_event(&mut timeout_receiver), None);
+
+    block_on(pm.advance_on_timeout(2));
+    assert_eq!(try_next_event(&mut timeout_receiver), Some(2));
+    assert!(pm.process_local_timeout(2));
+    assert_eq!(try_next_event(&mut timeout_receiver), None);
+}
+
+#[test]
+fn test_external_timeout_certificate_reason() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker();
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    let is_external = |event: NewRoundEvent| match event.reason {
+        NewRoundReason::Timeout { external, .. } => external,
+        NewRoundReason::QCReady => panic!("Expected a timeout, got {}", event),
+    };
+
+    // The timeout certificate gathered locally from the timeouts
+    for signer in &signers {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+    }
+    let event = try_next_event(&mut new_round_events_receiver).unwrap();
+    assert_eq!(event.round, 2);
+    assert!(!is_external(event));
+
+    // The timeout certificate handed by a peer
+    let tc = PacemakerTimeoutCertificate::new(
+        5,
+        signers
+            .iter()
+            .map(|signer| PacemakerTimeout::new(0, 5, signer, None))
+            .collect(),
+    );
+    block_on(pm.install_highest_timeout_certificate(tc).unwrap());
+    let event = try_next_event(&mut new_round_events_receiver).unwrap();
+    assert_eq!(event.round, 6);
+    assert!(is_external(event));
+}
+
+#[test]
+fn test_timeouts_needed_for_quorum() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..4u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    assert_eq!(pm.timeouts_needed_for_quorum(1), 3);
+
+    // The timeout of a higher round counts toward the quorum of the lower rounds
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, &signers[0], None)));
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 2, &signers[1], None)));
+    assert_eq!(pm.timeouts_needed_for_quorum(1), 1);
+    assert_eq!(pm.timeouts_needed_for_quorum(2), 2);
+
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, &signers[2], None)));
+    assert_eq!(pm.timeouts_needed_for_quorum(1), 0);
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 2, &signers[3], None)));
+    assert_eq!(pm.timeouts_needed_for_quorum(1), 0);
+}
+
+#[test]
+fn test_late_qc_after_timeout_advance() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker();
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    block_on(pm.process_certificates(1, None, None));
+    block_on(expect_qc(2, &mut new_round_events_receiver));
+
+    // The round times out past round 3
+    for signer in &signers {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 3, signer, None)));
+    }
+    block_on(expect_timeout(4, &mut new_round_events_receiver));
+    assert_eq!(pm.highest_certified_round(), 1);
+
+    // The late QC of round 3 is recorded, without a new round event
+    block_on(pm.process_certificates(3, None, None));
+    assert_eq!(pm.highest_certified_round(), 3);
+    assert_eq!(pm.current_round(), 4);
+    assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+}
+
+/// Records the spans opened (true) and closed (false) by the pacemaker
+#[derive(Default)]
+struct RecordingTracer {
+    spans: Mutex<Vec<(bool, PacemakerSpan)>>,
+}
+
+impl PacemakerTracer for RecordingTracer {
+    fn open_span(&self, span: PacemakerSpan) {
+        self.spans.lock().unwrap().push((true, span));
+    }
+
+    fn close_span(&self, span: PacemakerSpan) {
+        self.spans.lock().unwrap().push((false, span));
+    }
+}
+
+#[test]
+fn test_tracer_spans() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let tracer = Arc::new(RecordingTracer::default());
+    let (mut pm, mut new_round_events_receiver) =
+        make_unstarted_pacemaker(PacemakerConfig::default());
+    pm.observers_mut().tracer = Some(tracer.clone());
+    block_on(pm.start());
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+
+    // Round 1 ends in a timeout
+    assert!(pm.process_local_timeout(1));
+    for signer in &signers {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+    }
+    block_on(expect_timeout(2, &mut new_round_events_receiver));
+    assert_eq!(
+        *tracer.spans.lock().unwrap(),
+        vec![
+            (true, PacemakerSpan::Round(1)),
+            (true, PacemakerSpan::Timeout(1)),
+            (true, PacemakerSpan::TimeoutCertificateFormation(1)),
+            (false, PacemakerSpan::TimeoutCertificateFormation(1)),
+            (false, PacemakerSpan::Timeout(1)),
+            (false, PacemakerSpan::Round(1)),
+            (true, PacemakerSpan::Round(2)),
+        ]
+    );
+}
+
+#[test]
+fn test_oscillation_alert() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let make_tc = |round: Round| {
+        PacemakerTimeoutCertificate::new(
+            round,
+            vec![PacemakerTimeout::new(0, round, &signer, None)],
+        )
+    };
+    let mut config = PacemakerConfig::default();
+    config.oscillation_alert = Some((10, 4));
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    let (oscillation_alert_sender, mut oscillation_alert_receiver) = channel::new_test(1_024);
+    pm.observers_mut().oscillation_alert = Some(oscillation_alert_sender);
+
+    // The rounds alternately advance on a QC and on a TC: the n-th advance is the (n-1)-th
+    // alternation
+    for round in 1..8 {
+        if round % 2 == 1 {
+            block_on(pm.process_certificates(round, None, None));
+        } else {
+            block_on(pm.process_certificates(round - 1, None, Some(&make_tc(round))));
+        }
+        assert_eq!(pm.current_round(), round + 1);
+        let expected = if round == 6 { Some(5) } else { None };
+        assert_eq!(try_next_event(&mut oscillation_alert_receiver), expected);
+    }
+}
+
+#[test]
+fn test_halt_signal() {
+    let mut config = PacemakerConfig::default();
+    config.quorum_spec = Some(QuorumSpec::Fraction { num: 2, den: 3 });
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    let (halt_sender, mut halt_receiver) = oneshot::channel();
+    pm.observers_mut().halt = Some(halt_sender);
+    assert_eq!(halt_receiver.try_recv(), Ok(None));
+
+    pm.update_quorum_size(0);
+    assert!(pm.is_halted());
+    assert_eq!(
+        halt_receiver.try_recv(),
+        Ok(Some(HaltReason::EmptyValidatorSet))
+    );
+
+    // Halting again neither fires the signal again nor changes the reason
+    pm.update_quorum_size(0);
+    assert_eq!(pm.halt_reason(), Some(HaltReason::EmptyValidatorSet));
+    assert!(halt_receiver.try_recv().is_err());
+    assert!(!pm.process_local_timeout(1));
+}
+
+#[test]
+fn test_startup_grace_period() {
+    let time_service = Arc::new(ManualTimeService::default());
+    let mut config = PacemakerConfig::default();
+    config.startup_grace_period = Duration::from_secs(1);
+    let (mut pm, mut new_round_events_receiver) =
+        make_unstarted_pacemaker_with_time_service(config, time_service.clone());
+    block_on(pm.start());
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+
+    // The remote timeouts are recorded during the grace period, the local ones are not broadcast
+    for signer in &signers[..2] {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+    }
+    assert_eq!(pm.timeouts_needed_for_quorum(1), 1);
+    assert!(!pm.process_local_timeout(1));
+    *time_service.now.lock().unwrap() = Duration::from_millis(999);
+    assert!(!pm.process_local_timeout(1));
+
+    // The recorded timeouts form the timeout certificate as usual
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, &signers[2], None)));
+    assert_eq!(pm.current_round(), 2);
+    assert!(!pm.process_local_timeout(2));
+
+    // The local timeouts are broadcast once the grace period elapsed
+    *time_service.now.lock().unwrap() = Duration::from_secs(1);
+    assert!(pm.process_local_timeout(2));
+}
+
+/// Returns a quorum certificate of the given round whose ledger info has the given timestamp.
+fn make_qc_with_timestamp(round: Round, timestamp: Duration) -> QuorumCert {
+    let ledger_info = LedgerInfo::new(
+        0,
+        HashValue::zero(),
+        HashValue::zero(),
+        HashValue::zero(),
+        0,
+        timestamp.as_micros() as u64,
+    );
+    QuorumCert::new(
+        HashValue::zero(),
+        ExecutedState::state_for_genesis(),
+        round,
+        LedgerInfoWithSignatures::new(ledger_info, HashMap::new()),
+        HashValue::zero(),
+        round - 1,
+        HashValue::zero(),
+        round - 2,
+    )
+}
+
+#[test]
+fn test_max_qc_timestamp_skew() {
+    let time_service = Arc::new(ManualTimeService::default());
+    *time_service.now.lock().unwrap() = Duration::from_secs(100_000);
+    let mut config = PacemakerConfig::default();
+    config.max_qc_timestamp_skew = Some(Duration::from_secs(60));
+    let (mut pm, _new_round_events_receiver) =
+        make_unstarted_pacemaker_with_time_service(config, time_service.clone());
+    block_on(pm.start());
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+
+    // A quorum certificate timestamped an hour ahead drops the timeout
+    let count = TimeoutIngestResult::ImplausibleQcTimestamp.counter().get();
+    let future_qc = make_qc_with_timestamp(5, Duration::from_secs(100_000 + 3_600));
+    let result = block_on(
+        pm.process_remote_timeout_with_qc(PacemakerTimeout::new(0, 1, &signer, None), &future_qc),
+    );
+    assert_eq!(result, TimeoutIngestResult::ImplausibleQcTimestamp);
+    assert!(TimeoutIngestResult::ImplausibleQcTimestamp.counter().get() > count);
+    assert_eq!(pm.current_round(), 1);
+    assert_eq!(pm.timeouts_needed_for_quorum(1), 3);
+
+    // Within the skew, the quorum certificate and the timeout are processed
+    let skewed_qc = make_qc_with_timestamp(5, Duration::from_secs(100_000 + 30));
+    let result = block_on(
+        pm.process_remote_timeout_with_qc(PacemakerTimeout::new(0, 6, &signer, None), &skewed_qc),
+    );
+    assert_eq!(result, TimeoutIngestResult::Accepted);
+    assert_eq!(pm.current_round(), 6);
+}
+
+#[test]
+fn test_reconfigure() {
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker();
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    let (epoch_change_sender, mut epoch_change_receiver) = channel::new_test(1_024);
+    pm.observers_mut().epoch_change = Some(epoch_change_sender);
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..7u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let author_to_public_keys: HashMap<_, _> = signers
+        .iter()
+        .map(|signer| (signer.author(), signer.public_key()))
+        .collect();
+    let make_params = |time_interval: ExponentialTimeInterval| ReconfigParams::<TestPayload> {
+        epoch: 1,
+        validator_verifier: Arc::new(ValidatorVerifier::new(author_to_public_keys.clone())),
+        time_interval: Some(Box::new(time_interval)),
+        proposer_election: Some(Box::new(RotatingProposer::new(
+            vec![signers[6].author()],
+            1,
+        ))),
+    };
+
+    // Nothing is applied if the time interval is invalid
+    assert_eq!(
+        pm.reconfigure(make_params(ExponentialTimeInterval::fixed(
+            Duration::from_millis(0)
+        )))
+        .err(),
+        Some(PacemakerError::ZeroRoundDuration { round_index: 0 })
+    );
+    assert_eq!(pm.epoch(), 0);
+    assert_eq!(pm.quorum_size(), 3);
+    assert_eq!(try_next_event(&mut epoch_change_receiver), None);
+
+    block_on(
+        pm.reconfigure(make_params(ExponentialTimeInterval::fixed(
+            Duration::from_millis(500),
+        )))
+        .unwrap(),
+    );
+    let snapshot = pm.snapshot();
+    assert_eq!(snapshot.epoch, 1);
+    assert_eq!(snapshot.timeout_quorum_size, 5);
+    assert_eq!(snapshot.current_round, 1);
+    assert_eq!(pm.proposers(2), vec![signers[6].author()]);
+    assert_eq!(
+        pm.projected_schedule(2),
+        vec![
+            (1, Duration::from_millis(2)),
+            (2, Duration::from_millis(500))
+        ]
+    );
+    // A single epoch change, and no new round event
+    assert_eq!(
+        try_next_event(&mut epoch_change_receiver),
+        Some(EpochChange {
+            epoch: 1,
+            validator_set_size: 7,
+            timeout_quorum_size: 5,
+        })
+    );
+    assert_eq!(try_next_event(&mut epoch_change_receiver), None);
+    assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+
+    // A duplicate reconfiguration is rejected without applying anything
+    assert_eq!(
+        pm.reconfigure(make_params(ExponentialTimeInterval::fixed(
+            Duration::from_millis(500),
+        )))
+        .err(),
+        Some(PacemakerError::StaleEpoch {
+            epoch: 1,
+            current_epoch: 1
+        })
+    );
+    assert_eq!(pm.epoch(), 1);
+    assert_eq!(try_next_event(&mut epoch_change_receiver), None);
+}
+
+fn make_pacemaker_with_max_stall_interval() -> (Pacemaker, Arc<ManualTimeService>) {
+    let time_service = Arc::new(ManualTimeService::default());
+    let mut config = PacemakerConfig::default();
+    config.max_stall_interval = Some(Duration::from_secs(1));
+    let (mut pm, _new_round_events_receiver) =
+        make_unstarted_pacemaker_with_time_service(config, time_service.clone());
+    block_on(pm.start());
+    (pm, time_service)
+}
+
+#[test]
+fn test_max_stall_interval_healthy() {
+    let (mut pm, time_service) = make_pacemaker_with_max_stall_interval();
+    // A quorum certificate every 600ms keeps the pacemaker within the interval
+    for qc_round in 1..20 {
+        *time_service.now.lock().unwrap() += Duration::from_millis(600);
+        block_on(pm.process_certificates(qc_round, None, None));
+    }
+    assert_eq!(pm.current_round(), 20);
+    *time_service.now.lock().unwrap() += Duration::from_secs(1);
+    assert!(pm.process_local_timeout(20));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Pacemaker stalled at round 2")]
+fn test_max_stall_interval_stalled() {
+    let (mut pm, time_service) = make_pacemaker_with_max_stall_interval();
+    block_on(pm.process_certificates(1, None, None));
+    // The rounds time out, but nothing advances the round anymore
+    for _ in 0..3 {
+        *time_service.now.lock().unwrap() += Duration::from_millis(600);
+        pm.process_local_timeout(2);
+    }
+}
+
+#[test]
+fn test_retransmit_count() {
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    assert_eq!(pm.retransmit_count(1), 0);
+    // The first broadcast of the local timeout is not a retransmission
+    assert!(pm.process_local_timeout(1));
+    assert_eq!(pm.retransmit_count(1), 0);
+    for _ in 0..2 {
+        assert!(pm.process_local_timeout(1));
+    }
+    assert_eq!(pm.retransmit_count(1), 2);
+    // The local timeouts of the other rounds are not broadcast
+    assert!(!pm.process_local_timeout(3));
+    assert_eq!(pm.retransmit_count(1), 2);
+
+    block_on(pm.process_certificates(1, None, None));
+    assert_eq!(pm.current_round(), 2);
+    assert_eq!(pm.retransmit_count(1), 0);
+    assert_eq!(pm.retransmit_count(2), 0);
+}
+
+#[test]
+fn test_self_echo_timeout() {
+    let local_signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let other_signer = ValidatorSigner::<Ed25519PrivateKey>::random([1; 32]);
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    pm.set_local_author(local_signer.author());
+    block_on(pm.process_certificates(3, None, None));
+    assert_eq!(pm.current_round(), 4);
+
+    // The own timeout of round 3 echoed back is told apart from the stale timeouts of the others
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 3, &local_signer, None),
+        TimeoutIngestResult::SelfEcho,
+    );
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 3, &other_signer, None),
+        TimeoutIngestResult::StaleRound,
+    );
+    assert_eq!(pm.timeouts_needed_for_quorum(3), 3);
+
+    // The own timeouts of the current round are processed as usual
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 4, &local_signer, None),
+        TimeoutIngestResult::Accepted,
+    );
+}
+
+#[test]
+fn test_max_qc_age() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let tc = PacemakerTimeoutCertificate::new(
+        9,
+        signers
+            .iter()
+            .map(|signer| PacemakerTimeout::new(0, 9, signer, None))
+            .collect(),
+    );
+    let mut config = PacemakerConfig::default();
+    config.max_qc_age = Some(2);
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    block_on(pm.process_certificates(0, None, Some(&tc)));
+    assert_eq!(pm.current_round(), 10);
+
+    // Far below the current round, the quorum certificate is dropped
+    let count = counters::OLD_QC_DROPPED_COUNT.get();
+    block_on(pm.process_certificates(5, None, None));
+    assert!(counters::OLD_QC_DROPPED_COUNT.get() > count);
+    assert_eq!(pm.snapshot().highest_qc_round, 0);
+
+    // Within the window, it is only recorded
+    block_on(pm.process_certificates(9, None, None));
+    assert_eq!(pm.snapshot().highest_qc_round, 9);
+    assert_eq!(pm.current_round(), 10);
+
+    // From the current round on, it advances the round
+    block_on(pm.process_certificates(10, None, None));
+    assert_eq!(pm.current_round(), 11);
+}
+
+#[test]
+fn test_await_timeout_certificate() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    let mut timeout_certificate = pm.await_timeout_certificate(2);
+    for signer in &signers[..2] {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 2, signer, None)));
+    }
+    timeout_certificate = match block_on(future::select(timeout_certificate, future::ready(()))) {
+        Either::Left(_) => panic!("The timeout certificate of round 2 is not formed yet"),
+        Either::Right((_, timeout_certificate)) => timeout_certificate,
+    };
+
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 2, &signers[2], None)));
+    let tc = block_on(timeout_certificate);
+    assert_eq!(tc.round(), 2);
+    assert_eq!(tc.timeouts().len(), 3);
+
+    // The certificates already formed resolve the lower rounds immediately
+    assert_eq!(block_on(pm.await_timeout_certificate(1)).round(), 2);
+}
+
+#[test]
+fn test_post_recovery_grace() {
+    let base = Duration::from_millis(2);
+    let grace = Duration::from_millis(500);
+    let make_config = || {
+        let mut config = PacemakerConfig::default();
+        config.post_recovery_grace = grace;
+        config
+    };
+    let (new_round_events_sender, mut new_round_events_receiver) = channel::new_test(1_024);
+    let (timeout_sender, _timeout_receiver) = channel::new_test(1_024);
+    // Recovered with the quorum certificate of round 7
+    let mut pm = PacemakerBuilder::new(
+        MockStorage::<TestPayload>::start_for_testing()
+            .0
+            .persistent_liveness_storage(),
+        Box::new(ExponentialTimeInterval::fixed(base)),
+        Arc::new(SimulatedTimeService::new()),
+        new_round_events_sender,
+        timeout_sender,
+        3,
+    )
+    .highest_qc_round(7)
+    .config(make_config())
+    .build();
+    block_on(pm.start());
+    block_on(async {
+        let first_round = new_round_events_receiver.next().await.unwrap();
+        assert_eq!(first_round.round, 8);
+        assert_eq!(first_round.timeout, base + grace);
+        pm.process_certificates(8, None, None).await;
+        let event = new_round_events_receiver.next().await.unwrap();
+        assert_eq!(event.round, 9);
+        assert_eq!(event.timeout, base);
+    });
+
+    // A cold start has no grace
+    let (_pm, mut new_round_events_receiver) = make_pacemaker_with_config(make_config());
+    let first_round = block_on(new_round_events_receiver.next()).unwrap();
+    assert_eq!(first_round.round, 1);
+    assert_eq!(first_round.timeout, base);
+}
+
+#[test]
+fn test_simulator_link_delay() {
+    // Returns the time the timeout certificate of round 1 forms at on each of the 4 nodes
+    let tc_formation_times = |delayed_links: &[(usize, usize)]| {
+        let mut simulator = MultiPacemakerSimulator::new(4, Duration::from_millis(1000), 3);
+        for (from, to) in delayed_links {
+            simulator.set_link_delay(*from, *to, Duration::from_millis(50));
+        }
+        simulator.run_until(Duration::from_millis(1500));
+        (0..4)
+            .map(|node| {
+                assert_eq!(simulator.pacemaker(node).current_round(), 2);
+                simulator.formed_timeout_certificates(node)[0]
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let on_time = (1, Duration::from_millis(1000));
+    assert_eq!(tc_formation_times(&[]), vec![on_time; 4]);
+    // The timeouts of the other nodes reach node 0 50ms late, and so does its certificate
+    assert_eq!(
+        tc_formation_times(&[(1, 0), (2, 0), (3, 0)]),
+        vec![(1, Duration::from_millis(1050)), on_time, on_time, on_time]
+    );
+}
+
+#[test]
+fn test_suppress_initial_event() {
+    for suppress_initial_event in &[false, true] {
+        let mut config = PacemakerConfig::default();
+        config.suppress_initial_event = *suppress_initial_event;
+        let (mut pm, mut new_round_events_receiver, mut timeout_receiver) =
+            make_unstarted_pacemaker_with_timeout_receiver(
+                config,
+                Arc::new(SimulatedTimeService::auto_advance_until(
+                    Duration::from_millis(2),
+                )),
+            );
+        block_on(pm.start());
+        if *suppress_initial_event {
+            assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+        } else {
+            block_on(expect_qc(1, &mut new_round_events_receiver));
+        }
+        // The first round times out either way
+        assert_eq!(try_next_event(&mut timeout_receiver), Some(1));
+        assert!(pm.process_local_timeout(1));
+        assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+
+        block_on(pm.process_certificates(1, None, None));
+        block_on(expect_qc(2, &mut new_round_events_receiver));
+    }
+}
+
+#[test]
+fn test_expire_current_round_timer() {
+    // The simulated clock never advances
+    let time_service = SimulatedTimeService::new();
+    let (mut pm, mut new_round_events_receiver, mut timeout_receiver) =
+        make_unstarted_pacemaker_with_timeout_receiver(
+            PacemakerConfig::default(),
+            Arc::new(time_service.clone()),
+        );
+    block_on(pm.start());
+    block_on(pm.process_certificates(4, None, None));
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    block_on(expect_qc(5, &mut new_round_events_receiver));
+    assert_eq!(try_next_event(&mut timeout_receiver), None);
+
+    block_on(pm.expire_current_round_timer());
+    assert_eq!(try_next_event(&mut timeout_receiver), Some(5));
+    assert!(pm.process_local_timeout(5));
+    assert_eq!(try_next_event(&mut timeout_receiver), None);
+    assert_eq!(
+        time_service.get_current_timestamp(),
+        Duration::from_millis(0)
+    );
+}
+
+#[test]
+fn test_pending_timeout_power() {
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> =
+        (0..4u8).map(|i| ValidatorSigner::random([i; 32])).collect();
+    let voting_powers: HashMap<Author, u64> = signers
+        .iter()
+        .zip([40, 30, 20, 10].iter())
+        .map(|(signer, power)| (signer.author(), *power))
+        .collect();
+    pm.set_voting_power_quorum(voting_powers, 67);
+    assert_eq!(pm.pending_timeout_power(1), 0);
+
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, &signers[0], None)));
+    assert_eq!(pm.pending_timeout_power(1), 40);
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, &signers[1], None)));
+    // 70% of the voting power timed out in round 1, none in round 2
+    assert_eq!(pm.pending_timeout_power(1), 70);
+    assert_eq!(pm.pending_timeout_power(2), 0);
+}
+
+#[test]
+fn test_try_build_verifies_timeout_certificates() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> =
+        (0..4u8).map(|i| ValidatorSigner::random([i; 32])).collect();
+    let tc = PacemakerTimeoutCertificate::new(
+        5,
+        signers[..3]
+            .iter()
+            .map(|signer| PacemakerTimeout::new(0, 5, signer, None))
+            .collect(),
+    );
+    let try_build = |verifier: ValidatorVerifier<Ed25519PublicKey>| {
+        let (new_round_events_sender, _) = channel::new_test(1_024);
+        let (timeout_sender, _) = channel::new_test(1_024);
+        PacemakerBuilder::new(
+            MockStorage::<TestPayload>::start_for_testing()
+                .0
+                .persistent_liveness_storage(),
+            Box::new(ExponentialTimeInterval::fixed(Duration::from_millis(2))),
+            Arc::new(SimulatedTimeService::new()),
+            new_round_events_sender,
+            timeout_sender,
+            3,
+        )
+        .highest_timeout_certificates(HighestTimeoutCertificates::new(Some(tc.clone()), None))
+        .validator_verifier(Arc::new(verifier))
+        .try_build()
+    };
+
+    let pm = try_build(ValidatorVerifier::new(
+        signers
+            .iter()
+            .map(|signer| (signer.author(), signer.public_key()))
+            .collect(),
+    ))
+    .unwrap();
+    assert_eq!(pm.current_round(), 6);
+
+    // The storage holds a signature of the first author that its public key doesn't verify
+    let mut public_keys: HashMap<Author, Ed25519PublicKey> = signers
+        .iter()
+        .map(|signer| (signer.author(), signer.public_key()))
+        .collect();
+    public_keys.insert(signers[0].author(), signers[3].public_key());
+    assert_eq!(
+        try_build(ValidatorVerifier::new(public_keys)).err(),
+        Some(PacemakerError::InvalidTimeoutCertificate(
+            PacemakerTimeoutCertificateVerificationError::SigVerifyError(
+                signers[0].author(),
+                VerifyError::InvalidSignature
+            )
+        ))
+    );
+}
+
+#[test]
+fn test_round_duration_change() {
+    let mut config = PacemakerConfig::default();
+    config.round_duration_change_threshold = Some(20);
+    let (mut pm, mut new_round_events_receiver) = make_unstarted_pacemaker(config);
+    let (change_sender, mut change_receiver) = channel::new_test(1_024);
+    pm.observers_mut().round_duration_change = Some(change_sender);
+    // The rounds 1 to 3 last 100ms, then the duration doubles every round
+    pm.set_time_interval(Box::new(
+        ExponentialTimeInterval::new_with_backoff_start_round(
+            Duration::from_millis(100),
+            2.0,
+            5,
+            2,
+        ),
+    ))
+    .unwrap();
+    block_on(pm.start());
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    for round in 1..3 {
+        block_on(pm.process_certificates(round, None, None));
+        block_on(expect_qc(round + 1, &mut new_round_events_receiver));
+    }
+    assert_eq!(try_next_event(&mut change_receiver), None);
+
+    block_on(pm.process_certificates(3, None, None));
+    block_on(expect_qc(4, &mut new_round_events_receiver));
+    assert_eq!(
+        try_next_event(&mut change_receiver),
+        Some(RoundDurationChange {
+            round: 4,
+            previous_duration: Duration::from_millis(100),
+            duration: Duration::from_millis(200),
+        })
+    );
+
+    // A change within the threshold is not notified
+    pm.set_time_interval(Box::new(ExponentialTimeInterval::fixed(
+        Duration::from_millis(210),
+    )))
+    .unwrap();
+    block_on(pm.process_certificates(4, None, None));
+    block_on(expect_qc(5, &mut new_round_events_receiver));
+    assert_eq!(try_next_event(&mut change_receiver), None);
+}
+
+#[test]
+fn test_input_budget() {
+    let mut config = PacemakerConfig::default();
+    config.input_budget = Some(5);
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker_with_config(config);
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> =
+        (0..4u8).map(|i| ValidatorSigner::random([i; 32])).collect();
+
+    // A local timeout, 3 remote timeouts and a quorum certificate
+    assert!(pm.process_local_timeout(1));
+    for signer in &signers[..3] {
+        expect_ingest_result(
+            &mut pm,
+            PacemakerTimeout::new(0, 1, signer, None),
+            TimeoutIngestResult::Accepted,
+        );
+    }
+    block_on(expect_timeout(2, &mut new_round_events_receiver));
+    assert!(pm.final_snapshot().is_none());
+    block_on(pm.process_certificates(2, None, None));
+    block_on(expect_qc(3, &mut new_round_events_receiver));
+
+    // The budget is exhausted: every further input is rejected
+    let snapshot = pm.final_snapshot().unwrap();
+    assert_eq!(snapshot.processed_inputs, 5);
+    assert_eq!(snapshot.current_round, 3);
+    block_on(pm.process_certificates(5, None, None));
+    assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+    expect_ingest_result(
+        &mut pm,
+        PacemakerTimeout::new(0, 3, &signers[3], None),
+        TimeoutIngestResult::BudgetExhausted,
+    );
+    assert!(!pm.process_local_timeout(3));
+    let snapshot = pm.final_snapshot().unwrap();
+    assert_eq!(snapshot.processed_inputs, 5);
+    assert_eq!(snapshot.current_round, 3);
+    assert_eq!(snapshot.highest_qc_round, 2);
+}
+
+#[test]
+fn test_proposer_turn_notification() {
+    let authors: Vec<Author> = (0..4u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]).author())
+        .collect();
+    let mut time_service = SimulatedTimeService::new();
+    let mut config = PacemakerConfig::default();
+    config.proposer_turn_lead_time = Some(Duration::from_millis(200));
+    let (mut pm, mut new_round_events_receiver) =
+        make_unstarted_pacemaker_with_time_service(config, Arc::new(time_service.clone()));
+    pm.set_time_interval(Box::new(ExponentialTimeInterval::fixed(
+        Duration::from_millis(1000),
+    )))
+    .unwrap();
+    // The local node leads the rounds 4, 8, ...
+    let proposer_election: Box<dyn ProposerElection<TestPayload> + Send + Sync> =
+        Box::new(RotatingProposer::new(authors.clone(), 1));
+    pm.set_proposer_election(proposer_election);
+    pm.set_local_author(authors[0]);
+    let (turn_sender, mut turn_receiver) = channel::new_test(1_024);
+    pm.observers_mut().proposer_turn = Some(turn_sender);
+    block_on(pm.start());
+    block_on(expect_qc(1, &mut new_round_events_receiver));
+    block_on(pm.process_certificates(2, None, None));
+    block_on(expect_qc(3, &mut new_round_events_receiver));
+
+    // Round 4 starts at the latest once round 3 times out, after 1s
+    time_service.update_auto_advance_limit(Duration::from_millis(799));
+    assert_eq!(try_next_event(&mut turn_receiver), None);
+    time_service.update_auto_advance_limit(Duration::from_millis(1));
+    assert_eq!(try_next_event(&mut turn_receiver), Some(4));
+    assert_eq!(try_next_event(&mut turn_receiver), None);
+}
+
+#[test]
+fn test_conflicting_timeout_certificate() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> =
+        (0..3u8).map(|i| ValidatorSigner::random([i; 32])).collect();
+    let tc = PacemakerTimeoutCertificate::new(
+        5,
+        signers
+            .iter()
+            .map(|signer| PacemakerTimeout::new(0, 5, signer, None))
+            .collect(),
+    );
+    for policy in &[ConflictingTcPolicy::Process, ConflictingTcPolicy::Drop] {
+        let mut config = PacemakerConfig::default();
+        config.conflicting_tc_policy = *policy;
+        let (mut pm, mut new_round_events_receiver) = make_unstarted_pacemaker(config);
+        let (alert_sender, mut alert_receiver) = channel::new_test(1_024);
+        pm.observers_mut().conflicting_tc_alert = Some(alert_sender);
+        block_on(pm.start());
+        block_on(expect_qc(1, &mut new_round_events_receiver));
+        block_on(pm.process_certificates(5, None, None));
+        block_on(expect_qc(6, &mut new_round_events_receiver));
+        assert_eq!(try_next_event(&mut alert_receiver), None);
+
+        // Round 5 is certified, yet a timeout certificate claims it timed out
+        block_on(pm.process_certificates(5, None, Some(&tc)));
+        assert_eq!(try_next_event(&mut alert_receiver), Some(5));
+        assert_eq!(try_next_event(&mut new_round_events_receiver), None);
+        assert_eq!(pm.highest_certified_round(), 5);
+        assert_eq!(pm.current_round(), 6);
+        assert_eq!(
+            pm.highest_timeout_certificate().is_some(),
+            *policy == ConflictingTcPolicy::Process
+        );
+    }
+}
+
+#[test]
+fn test_storage_retry() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let highest_tc_round = |storage: &MockStorage<TestPayload>| {
+        storage
+            .shared_storage
+            .highest_timeout_certificates
+            .lock()
+            .unwrap()
+            .highest_timeout_certificate()
+            .map(PacemakerTimeoutCertificate::round)
+    };
+    for max_retries in &[3, 1] {
+        let storage = MockStorage::<TestPayload>::start_for_testing().0;
+        *storage
+            .shared_storage
+            .fail_next_liveness_writes
+            .lock()
+            .unwrap() = 2;
+        let mut config = PacemakerConfig::default();
+        config.storage_retry = RetryPolicy {
+            max_retries: *max_retries,
+            backoff: Duration::from_millis(100),
+        };
+        let mut time_service = SimulatedTimeService::new();
+        let (mut pm, _new_round_events_receiver, _timeout_receiver) =
+            make_unstarted_pacemaker_with_storage(
+                config,
+                Arc::new(time_service.clone()),
+                storage.persistent_liveness_storage(),
+            );
+        block_on(pm.start());
+
+        // The write of the timeout certificate of round 1 fails, the round advances without
+        // waiting for the retries
+        for signer in &signers {
+            block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+        }
+        assert_eq!(pm.current_round(), 2);
+        assert_eq!(highest_tc_round(&storage), None);
+
+        // The first retry fails again after the backoff, the second one after twice the backoff
+        // succeeds if allowed
+        time_service.update_auto_advance_limit(Duration::from_millis(100));
+        assert_eq!(highest_tc_round(&storage), None);
+        time_service.update_auto_advance_limit(Duration::from_millis(200));
+        if *max_retries == 3 {
+            assert_eq!(highest_tc_round(&storage), Some(1));
+            assert!(pm.process_local_timeout(2));
+            assert!(!pm.is_halted());
+        } else {
+            // The retries are exhausted, the failure policy applies on the next processing
+            assert_eq!(highest_tc_round(&storage), None);
+            assert!(!pm.process_local_timeout(2));
+            assert_eq!(pm.halt_reason(), Some(HaltReason::StorageFailure));
+        }
+    }
+}
+
+#[test]
+fn test_fast_clock_alert() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let mut config = PacemakerConfig::default();
+    config.fast_clock_alert = Some((3, 3));
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    pm.set_local_author(signers[0].author());
+    let (fast_clock_alert_sender, mut fast_clock_alert_receiver) = channel::new_test(1_024);
+    pm.observers_mut().fast_clock_alert = Some(fast_clock_alert_sender);
+
+    // A peer times out round 1 before the local node does
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, &signers[1], None)));
+    assert!(pm.process_local_timeout(1));
+    for signer in &[&signers[0], &signers[2]] {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+    }
+    assert_eq!(pm.current_round(), 2);
+    assert_eq!(try_next_event(&mut fast_clock_alert_receiver), None);
+
+    // From then on, the local node always times out first: the own timeout echoed back does not
+    // count as a remote one
+    for round in 2..5 {
+        assert!(pm.process_local_timeout(round));
+        for signer in &signers {
+            block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, round, signer, None)));
+        }
+        assert_eq!(pm.current_round(), round + 1);
+        let expected = if round == 4 { Some(3) } else { None };
+        assert_eq!(try_next_event(&mut fast_clock_alert_receiver), expected);
+    }
+    // The alert is not repeated while the local timeouts keep firing first
+    assert!(pm.process_local_timeout(5));
+    assert_eq!(try_next_event(&mut fast_clock_alert_receiver), None);
+}
+
+/// Executor holding the spawned tasks until they are run explicitly
+#[derive(Default)]
+struct MockEventExecutor {
+    tasks: Mutex<Vec<Pin<Box<dyn Future<Output = ()> + Send>>>>,
+}
+
+impl MockEventExecutor {
+    fn run_all(&self) -> usize {
+        let tasks: Vec<_> = self.tasks.lock().unwrap().drain(..).collect();
+        let spawned = tasks.len();
+        for task in tasks {
+            block_on(task);
+        }
+        spawned
+    }
+}
+
+impl fmt::Debug for MockEventExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MockEventExecutor")
+    }
+}
+
+impl EventExecutor for MockEventExecutor {
+    fn spawn(&self, task: Pin<Box<dyn Future<Output = ()> + Send>>) {
+        self.tasks.lock().unwrap().push(task);
+    }
+}
+
+#[test]
+fn test_event_executor() {
+    let event_executor = Arc::new(MockEventExecutor::default());
+    let mut config = PacemakerConfig::default();
+    config.event_executor = Some(event_executor.clone());
+    let (mut pm, mut new_round_events_receiver) = make_unstarted_pacemaker(config);
+    block_on(pm.start());
+    assert_eq!(event_executor.run_all(), 1);
+    assert_eq!(
+        try_next_event(&mut new_round_events_receiver).map(|event| event.round),
+        Some(1)
+    );
+
+    // The event of the new round is only delivered once the executor runs its send
+    block_on(pm.process_certificates(1, None, None));
+    assert_eq!(pm.current_round(), 2);
+    assert!(try_next_event(&mut new_round_events_receiver).is_none());
+    assert_eq!(event_executor.run_all(), 1);
+    assert_eq!(
+        try_next_event(&mut new_round_events_receiver).map(|event| event.round),
+        Some(2)
+    );
+}
+
+#[test]
+fn test_timeout_certificate_accessor() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..4u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    block_on(pm.process_certificates(1, None, None));
+    assert_eq!(pm.current_round(), 2);
+
+    // A quorum of timeouts for round 2 forms its certificate, without the fourth signer
+    for signer in &signers[..3] {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 2, signer, None)));
+    }
+    let tc = pm.timeout_certificate(2).unwrap();
+    assert_eq!(tc.round(), 2);
+    let mut tc_signers: Vec<Author> = tc.timeouts().iter().map(PacemakerTimeout::author).collect();
+    tc_signers.sort();
+    let mut expected_signers: Vec<Author> =
+        signers[..3].iter().map(ValidatorSigner::author).collect();
+    expected_signers.sort();
+    assert_eq!(tc_signers, expected_signers);
+    assert!(pm.timeout_certificate(1).is_none());
+    assert!(pm.timeout_certificate(3).is_none());
+}
+
+#[test]
+fn test_simulator_crash_and_recover() {
+    let mut simulator = MultiPacemakerSimulator::new(4, Duration::from_millis(1000), 3);
+    let authors: Vec<Author> = (0..4).map(|node| simulator.author(node)).collect();
+    simulator.enable_proposals(
+        Arc::new(RotatingProposer::new(authors.clone(), 1)),
+        Duration::from_millis(100),
+    );
+    // Node 0 is the proposer of round 4 (and of every 4th round)
+    simulator.crash_node(authors[0]);
+
+    // Rounds 1 to 3 are certified every 100ms, round 4 times out and the other nodes move on
+    simulator.run_until(Duration::from_millis(1450));
+    assert_eq!(simulator.pacemaker(0).current_round(), 1);
+    assert!(simulator.proposed_rounds(0).is_empty());
+    for node in 1..4 {
+        assert_eq!(simulator.pacemaker(node).current_round(), 6);
+        assert_eq!(
+            simulator.formed_timeout_certificates(node),
+            &[(4, Duration::from_millis(1300))]
+        );
+    }
+
+    // The recovered node catches up on the next quorum certificate, and proposes its next round
+    simulator.recover_node(authors[0]);
+    simulator.run_until(Duration::from_millis(1950));
+    assert_eq!(
+        simulator.proposed_rounds(0),
+        &[(8, Duration::from_millis(1600))]
+    );
+    for node in 0..4 {
+        assert_eq!(simulator.pacemaker(node).current_round(), 11);
+    }
+}
+
+#[test]
+fn test_state_sync_trigger() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let make_tc = |round: Round| {
+        PacemakerTimeoutCertificate::new(
+            round,
+            vec![PacemakerTimeout::new(0, round, &signer, None)],
+        )
+    };
+    let mut config = PacemakerConfig::default();
+    config.state_sync_trigger_threshold = Some(5);
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    let (state_sync_trigger_sender, mut state_sync_trigger_receiver) = channel::new_test(1_024);
+    pm.observers_mut().state_sync_trigger = Some(state_sync_trigger_sender);
+
+    // 5 consecutive rounds advance on timeout certificates, without any quorum certificate
+    for round in 1..=6 {
+        block_on(pm.process_certificates(0, None, Some(&make_tc(round))));
+        assert_eq!(pm.current_round(), round + 1);
+        let expected = if round == 5 {
+            Some(TriggerStateSync {
+                round: 6,
+                highest_qc_round: 0,
+                timed_out_rounds: 5,
+            })
+        } else {
+            None
+        };
+        assert_eq!(try_next_event(&mut state_sync_trigger_receiver), expected);
+    }
+
+    // A quorum certificate resets the count
+    block_on(pm.process_certificates(7, None, None));
+    for round in 8..12 {
+        block_on(pm.process_certificates(7, None, Some(&make_tc(round))));
+        assert_eq!(pm.current_round(), round + 1);
+    }
+    assert_eq!(try_next_event(&mut state_sync_trigger_receiver), None);
+}
+
+#[test]
+fn test_targeted_retransmission() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..5u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let author_to_public_keys: HashMap<_, _> = signers
+        .iter()
+        .map(|signer| (signer.author(), signer.public_key()))
+        .collect();
+    let broadcaster = Arc::new(MockTimeoutBroadcaster::default());
+    let mut config = PacemakerConfig::default();
+    config.targeted_retransmission = true;
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    pm.set_validator_verifier(Arc::new(ValidatorVerifier::new(author_to_public_keys)));
+    pm.set_local_author(signers[0].author());
+    pm.set_timeout_broadcaster(broadcaster.clone());
+
+    // The first broadcast of the local timeout reaches all the validators
+    let timeout = PacemakerTimeout::new(0, 1, &signers[0], None);
+    assert!(pm.process_local_timeout(1));
+    block_on(pm.broadcast_local_timeout(timeout.clone()));
+    assert_eq!(*broadcaster.timeouts.lock().unwrap(), vec![timeout.clone()]);
+
+    // The timeouts of 2 of the 5 validators are received (the local one echoed back)
+    for signer in &signers[..2] {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+    }
+    let mut missing_authors: Vec<Author> =
+        signers[2..].iter().map(ValidatorSigner::author).collect();
+    missing_authors.sort();
+    assert_eq!(pm.retransmission_targets(1), Some(missing_authors.clone()));
+
+    // The retransmission only targets the remaining 3 validators
+    assert!(pm.process_local_timeout(1));
+    block_on(pm.broadcast_local_timeout(timeout.clone()));
+    assert_eq!(broadcaster.timeouts.lock().unwrap().len(), 1);
+    assert_eq!(
+        *broadcaster.targeted_timeouts.lock().unwrap(),
+        vec![(timeout, missing_authors)]
+    );
+}
+
+#[test]
+fn test_min_round_duration() {
+    // The time interval halves the duration at each round, as a multiplicative decrease would
+    let time_interval = Box::new(ScriptedTimeInterval {
+        durations: [100, 50, 25, 12]
+            .iter()
+            .map(|ms| Duration::from_millis(*ms))
+            .collect(),
+    });
+    let mut config = PacemakerConfig::default();
+    config.min_round_duration = Some(Duration::from_millis(30));
+    let (new_round_events_sender, mut new_round_events_receiver) = channel::new_test(1_024);
+    let (pacemaker_timeout_tx, _pacemaker_timeout_rx) = channel::new_test(1_024);
+    let mut pm = PacemakerBuilder::new(
+        MockStorage::<TestPayload>::start_for_testing()
+            .0
+            .persistent_liveness_storage(),
+        time_interval,
+        Arc::new(SimulatedTimeService::new()),
+        new_round_events_sender,
+        pacemaker_timeout_tx,
+        3,
+    )
+    .config(config)
+    .build();
+    block_on(pm.start());
+    for round in 1..4 {
+        block_on(pm.process_certificates(round, None, None));
+    }
+
+    let timeouts: Vec<Duration> = (0..4)
+        .map(|_| {
+            try_next_event(&mut new_round_events_receiver)
+                .unwrap()
+                .timeout
+        })
+        .collect();
+    let expected: Vec<Duration> = [100, 50, 30, 30]
+        .iter()
+        .map(|ms| Duration::from_millis(*ms))
+        .collect();
+    assert_eq!(timeouts, expected);
+    let remaining = pm.time_until_timeout().unwrap();
+    assert_eq!(remaining, Duration::from_millis(30));
+}
+
+#[test]
+fn test_round_start_timestamp() {
+    let time_service = Arc::new(ManualTimeService::default());
+    *time_service.now.lock().unwrap() = Duration::from_secs(100);
+    let (mut pm, _new_round_events_receiver) = make_unstarted_pacemaker_with_time_service(
+        PacemakerConfig::default(),
+        time_service.clone(),
+    );
+    assert_eq!(pm.round_start_timestamp(1), None);
+    block_on(pm.start());
+
+    // Round n + 1 starts 10 * n ms after round 1, round 5 is skipped by a jump to round 6
+    let mut expected = vec![(1, Duration::from_secs(100))];
+    for (qc_round, elapsed_ms) in &[(1, 10), (2, 20), (3, 30), (5, 40)] {
+        let now = Duration::from_secs(100) + Duration::from_millis(*elapsed_ms);
+        *time_service.now.lock().unwrap() = now;
+        block_on(pm.process_certificates(*qc_round, None, None));
+        expected.push((qc_round + 1, now));
+    }
+    let start_times: Vec<(Round, Duration)> = expected
+        .iter()
+        .map(|(round, _)| (*round, pm.round_start_timestamp(*round).unwrap()))
+        .collect();
+    assert_eq!(start_times, expected);
+    assert!(start_times.windows(2).all(|pair| pair[0].1 < pair[1].1));
+    assert_eq!(pm.round_start_timestamp(5), None);
+    assert_eq!(pm.round_start_timestamp(7), None);
+}
+
+#[test]
+fn test_proposer_mismatch() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..2u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let authors: Vec<Author> = signers.iter().map(ValidatorSigner::author).collect();
+    let qc_for_round = |round: Round| {
+        placeholder_certificate_for_block(
+            signers.iter().collect(),
+            HashValue::zero(),
+            round,
+            HashValue::zero(),
+            round - 1,
+            HashValue::zero(),
+            round.saturating_sub(2),
+        )
+    };
+    for policy in &[
+        ProposerMismatchPolicy::Advance,
+        ProposerMismatchPolicy::IgnoreQc,
+    ] {
+        let mut config = PacemakerConfig::default();
+        config.proposer_mismatch_policy = *policy;
+        let (mut pm, _new_round_events_receiver) = make_unstarted_pacemaker(config);
+        // The second author proposes the odd rounds, the first one the even rounds
+        pm.set_proposer_election::<TestPayload>(Box::new(RotatingProposer::new(
+            authors.clone(),
+            1,
+        )));
+        block_on(pm.start());
+        let (proposer_mismatch_sender, mut proposer_mismatch_receiver) = channel::new_test(1_024);
+        pm.observers_mut().proposer_mismatch = Some(proposer_mismatch_sender);
+        let mismatches_before = counters::PROPOSER_MISMATCH_COUNT.get();
+
+        // The block of round 1 is attributed to the first author
+        block_on(pm.process_proposal_quorum_cert(&qc_for_round(1), authors[0], None, None));
+        assert_eq!(
+            try_next_event(&mut proposer_mismatch_receiver),
+            Some(ProposerMismatch {
+                round: 1,
+                proposer: authors[0],
+                expected_proposers: vec![authors[1]],
+            })
+        );
+        assert!(counters::PROPOSER_MISMATCH_COUNT.get() > mismatches_before);
+        let expected_round = match policy {
+            ProposerMismatchPolicy::Advance => 2,
+            ProposerMismatchPolicy::IgnoreQc => 1,
+        };
+        assert_eq!(pm.current_round(), expected_round);
+
+        // The block of round 2 comes from its elected proposer
+        block_on(pm.process_proposal_quorum_cert(&qc_for_round(2), authors[0], None, None));
+        assert_eq!(try_next_event(&mut proposer_mismatch_receiver), None);
+        assert_eq!(pm.current_round(), 3);
+    }
+}
+
+#[test]
+fn test_round_summary() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let time_service = Arc::new(ManualTimeService::default());
+    *time_service.now.lock().unwrap() = Duration::from_secs(100);
+    let (mut pm, _new_round_events_receiver) = make_unstarted_pacemaker_with_time_service(
+        PacemakerConfig::default(),
+        time_service.clone(),
+    );
+    block_on(pm.start());
+    let (round_summary_sender, mut round_summary_receiver) = channel::new_test(1_024);
+    pm.observers_mut().round_summary = Some(round_summary_sender);
+
+    // Round 1 times out locally, its timeout is retransmitted once before the timeout certificate
+    assert!(pm.process_local_timeout(1));
+    assert!(pm.process_local_timeout(1));
+    *time_service.now.lock().unwrap() = Duration::from_secs(100) + Duration::from_millis(30);
+    for signer in signers.iter().rev() {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+    }
+    assert_eq!(pm.current_round(), 2);
+    let summary: RoundSummary = try_next_event(&mut round_summary_receiver).unwrap();
+    assert_eq!(summary.round, 1);
+    assert_eq!(summary.start, Duration::from_secs(100));
+    assert_eq!(
+        summary.end,
+        Duration::from_secs(100) + Duration::from_millis(30)
+    );
+    match summary.reason {
+        NewRoundReason::Timeout { ref cert, .. } => assert_eq!(cert.round(), 1),
+        NewRoundReason::QCReady => panic!("Round 1 did not time out"),
+    }
+    assert_eq!(summary.retransmit_count, 1);
+    let mut authors: Vec<Author> = signers.iter().map(ValidatorSigner::author).collect();
+    authors.sort();
+    assert_eq!(summary.timeout_authors, authors);
+    assert_eq!(try_next_event(&mut round_summary_receiver), None);
+
+    // Round 2 ends on a quorum certificate, without retransmissions nor timeout authors
+    block_on(pm.process_certificates(2, None, None));
+    let summary = try_next_event(&mut round_summary_receiver).unwrap();
+    assert_eq!(summary.round, 2);
+    assert_eq!(summary.reason, NewRoundReason::QCReady);
+    assert_eq!(summary.retransmit_count, 0);
+    assert!(summary.timeout_authors.is_empty());
+}
+
+#[test]
+fn test_round_detail_sampling() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let storage = MockStorage::<TestPayload>::start_for_testing().0;
+    let mut config = PacemakerConfig::default();
+    config.round_detail_sampling = RoundDetailSampling::TimeoutRoundsOnly;
+    let (mut pm, _new_round_events_receiver, _timeout_receiver) =
+        make_unstarted_pacemaker_with_storage(
+            config,
+            Arc::new(SimulatedTimeService::new()),
+            storage.persistent_liveness_storage(),
+        );
+    block_on(pm.start());
+
+    // Rounds 1 and 3 end on quorum certificates, round 2 on a timeout certificate
+    block_on(pm.process_certificates(1, None, None));
+    for signer in &signers {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 2, signer, None)));
+    }
+    assert_eq!(pm.current_round(), 3);
+    block_on(pm.process_certificates(3, None, None));
+    assert_eq!(pm.current_round(), 4);
+
+    // Only the timed out round has its details persisted, the current round always is
+    let detailed_rounds: Vec<Round> = storage
+        .shared_storage
+        .round_details
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|summary| summary.round)
+        .collect();
+    assert_eq!(detailed_rounds, vec![2]);
+    assert_eq!(
+        *storage.shared_storage.current_round.lock().unwrap(),
+        Some(4)
+    );
+}
+
+#[test]
+fn test_diff_snapshots() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, &signer, None)));
+    let snapshot = pm.snapshot();
+    assert!(diff_snapshots(&snapshot, &snapshot.clone()).is_empty());
+
+    let mut other = snapshot.clone();
+    other.highest_qc_round = 7;
+    let diff = diff_snapshots(&snapshot, &other);
+    assert!(!diff.is_empty());
+    assert_eq!(
+        diff,
+        SnapshotDiff {
+            highest_qc_round: Some((0, 7)),
+            ..SnapshotDiff::default()
+        }
+    );
+}
+
+#[test]
+fn test_multicast_timeout_broadcaster() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..6u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    // The first 3 validators form group 0, the last 3 group 1
+    let author_to_group: HashMap<Author, u64> = signers
+        .iter()
+        .enumerate()
+        .map(|(i, signer)| (signer.author(), i as u64 / 3))
+        .collect();
+    let sorted_group = |group: &[ValidatorSigner<Ed25519PrivateKey>]| {
+        let mut authors: Vec<Author> = group.iter().map(ValidatorSigner::author).collect();
+        authors.sort();
+        authors
+    };
+    let (group_0, group_1) = (sorted_group(&signers[..3]), sorted_group(&signers[3..]));
+    let inner = Arc::new(MockTimeoutBroadcaster::default());
+    let broadcaster = MulticastTimeoutBroadcaster::new(inner.clone(), author_to_group.clone(), 1);
+
+    // The timeout goes to the 2 other members of group 0 and to the relay of group 1 only
+    let timeout = PacemakerTimeout::new(0, 1, &signers[0], None);
+    block_on(broadcaster.broadcast(timeout.clone()));
+    let mut expected_recipients: Vec<Author> = group_0
+        .iter()
+        .filter(|author| **author != signers[0].author())
+        .cloned()
+        .collect();
+    expected_recipients.push(group_1[0]);
+    assert!(inner.timeouts.lock().unwrap().is_empty());
+    assert_eq!(
+        *inner.targeted_timeouts.lock().unwrap(),
+        vec![(timeout.clone(), expected_recipients)]
+    );
+
+    // The relay forwards it to the rest of group 1, which processes it as any remote timeout
+    block_on(broadcaster.relay(timeout.clone(), group_1[0]));
+    assert_eq!(
+        inner.targeted_timeouts.lock().unwrap()[1],
+        (timeout.clone(), group_1[1..].to_vec())
+    );
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    expect_ingest_result(&mut pm, timeout.clone(), TimeoutIngestResult::Accepted);
+
+    // Over the channels, the multicast timeouts are only sent with their recipients
+    let (sender, mut receiver) = channel::new_test(1_024);
+    let (targeted_sender, mut targeted_receiver) = channel::new_test(1_024);
+    let broadcaster = MulticastTimeoutBroadcaster::new(
+        Arc::new(ChannelTimeoutBroadcaster::new(sender, targeted_sender)),
+        author_to_group,
+        1,
+    );
+    block_on(broadcaster.broadcast(timeout.clone()));
+    assert_eq!(try_next_event(&mut receiver), None);
+    assert_eq!(
+        try_next_event(&mut targeted_receiver),
+        Some(inner.targeted_timeouts.lock().unwrap()[0].clone())
+    );
+}
+
+#[test]
+fn test_advance_reason() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    block_on(pm.process_certificates(2, None, None));
+    assert_eq!(pm.current_round(), 3);
+
+    // Round 3 ends on a quorum certificate, round 4 on a timeout certificate
+    block_on(pm.process_certificates(3, None, None));
+    let tc = PacemakerTimeoutCertificate::new(4, vec![PacemakerTimeout::new(0, 4, &signer, None)]);
+    block_on(pm.process_certificates(3, None, Some(&tc)));
+    assert_eq!(pm.current_round(), 5);
+    assert_eq!(pm.advance_reason(3), Some(NewRoundReason::QCReady));
+    assert_eq!(
+        pm.advance_reason(4),
+        Some(NewRoundReason::Timeout {
+            cert: tc,
+            external: true,
+        })
+    );
+    // The current round is not exited yet
+    assert_eq!(pm.advance_reason(5), None);
+}
+
+#[test]
+fn test_timeout_suppressed_until_round() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let make_tc = |round: Round| {
+        PacemakerTimeoutCertificate::new(
+            round,
+            vec![PacemakerTimeout::new(0, round, &signer, None)],
+        )
+    };
+    let mut config = PacemakerConfig::default();
+    config.timeout_suppressed_until_round = Some(5);
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+
+    // The rounds below 5 time out without broadcasting, and only advance on quorum certificates
+    for round in 1..5 {
+        assert_eq!(pm.current_round(), round);
+        assert!(!pm.process_local_timeout(round));
+        block_on(pm.process_certificates(round - 1, None, Some(&make_tc(round))));
+        assert_eq!(pm.current_round(), round);
+        block_on(pm.process_certificates(round, None, None));
+    }
+
+    // From round 5 on, the timeouts are back to normal
+    assert_eq!(pm.current_round(), 5);
+    assert!(pm.process_local_timeout(5));
+    block_on(pm.process_certificates(4, None, Some(&make_tc(5))));
+    assert_eq!(pm.current_round(), 6);
+}
+
+/// Filter dropping every other outgoing timeout, the first one passing
+#[derive(Default)]
+struct AlternatingTimeoutFilter {
+    filtered: Mutex<usize>,
+}
+
+impl fmt::Debug for AlternatingTimeoutFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AlternatingTimeoutFilter")
+    }
+}
+
+impl OutgoingTimeoutFilter for AlternatingTimeoutFilter {
+    fn filter(&self, timeout: PacemakerTimeout) -> Option<PacemakerTimeout> {
+        let mut filtered = self.filtered.lock().unwrap();
+        *filtered += 1;
+        if *filtered % 2 == 1 {
+            Some(timeout)
+        } else {
+            None
+        }
+    }
+}
+
+#[test]
+fn test_outgoing_timeout_filter() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..4u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let broadcaster = Arc::new(MockTimeoutBroadcaster::default());
+    let mut config = PacemakerConfig::default();
+    config.outgoing_timeout_filter = Some(Arc::new(AlternatingTimeoutFilter::default()));
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    pm.set_timeout_broadcaster(broadcaster.clone());
+
+    let timeouts: Vec<PacemakerTimeout> = signers
+        .iter()
+        .map(|signer| PacemakerTimeout::new(0, 1, signer, None))
+        .collect();
+    for timeout in &timeouts {
+        assert!(pm.process_local_timeout(1));
+        block_on(pm.broadcast_local_timeout(timeout.clone()));
+    }
+    // The second and the fourth timeouts are dropped
+    assert_eq!(
+        *broadcaster.timeouts.lock().unwrap(),
+        vec![timeouts[0].clone(), timeouts[2].clone()]
+    );
+}
+
+#[test]
+fn test_quorum_info() {
+    let two_thirds = QuorumSpec::Fraction { num: 2, den: 3 };
+    let mut config = PacemakerConfig::default();
+    config.quorum_spec = Some(two_thirds);
+    config.timeout_certificate_min_signers = 6;
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+
+    // 2/3 of 7 validators is a quorum of 5, raised to the 6 signers
+    pm.update_quorum_size(7);
+    assert_eq!(
+        pm.quorum_info(),
+        QuorumInfo {
+            spec: Some(two_thirds),
+            validator_set_size: Some(7),
+            quorum_size: 5,
+            min_signers: 6,
+            effective_threshold: 6,
+        }
+    );
+}
+
+#[test]
+fn test_auto_epoch_rounds() {
+    let mut config = PacemakerConfig::default();
+    config.auto_epoch_rounds = Some(10);
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    let (epoch_change_sender, mut epoch_change_receiver) = channel::new_test(1_024);
+    pm.observers_mut().epoch_change = Some(epoch_change_sender);
+    pm.update_quorum_size(4);
+
+    for qc_round in 1..9 {
+        block_on(pm.process_certificates(qc_round, None, None));
+    }
+    assert_eq!(pm.current_round(), 9);
+    assert_eq!(pm.epoch(), 0);
+    assert_eq!(try_next_event(&mut epoch_change_receiver), None);
+
+    // Reaching round 10 starts epoch 1 and resets the round to its start
+    block_on(pm.process_certificates(9, None, None));
+    assert_eq!(pm.current_round(), 1);
+    assert_eq!(pm.epoch(), 1);
+    assert_eq!(
+        try_next_event(&mut epoch_change_receiver),
+        Some(EpochChange {
+            epoch: 1,
+            validator_set_size: 4,
+            timeout_quorum_size: 3,
+        })
+    );
+    assert_eq!(try_next_event(&mut epoch_change_receiver), None);
+
+    // The rounds of the new epoch advance from its start, on the certificates and the timeouts
+    // of the new epoch
+    block_on(pm.process_certificates(1, None, None));
+    assert_eq!(pm.current_round(), 2);
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    for signer in &signers {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(1, 2, signer, None)));
+    }
+    assert_eq!(pm.current_round(), 3);
+    assert_eq!(
+        pm.highest_timeout_certificate().map(|tc| tc.round()),
+        Some(2)
+    );
+
+    // Jumping past the boundary starts a single epoch, without the timeout certificate of the
+    // previous one
+    block_on(pm.process_certificates(30, None, None));
+    assert_eq!(pm.current_round(), 1);
+    assert_eq!(pm.epoch(), 2);
+    assert_eq!(pm.highest_timeout_certificate(), None);
+    assert_eq!(
+        try_next_event(&mut epoch_change_receiver).map(|epoch_change| epoch_change.epoch),
+        Some(2)
+    );
+    assert_eq!(try_next_event(&mut epoch_change_receiver), None);
+}
+
+#[test]
+fn test_serve_timeout_certificate() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    for signer in &signers {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 1, signer, None)));
+    }
+
+    // The held certificate is served in its wire encoding, the others are not
+    let bytes = pm.serve_timeout_certificate(1).unwrap().unwrap();
+    assert_eq!(
+        PacemakerTimeoutCertificate::from_proto_bytes(&bytes).unwrap(),
+        pm.timeout_certificate(1).unwrap()
+    );
+    assert_eq!(pm.serve_timeout_certificate(2).unwrap(), None);
+}
+
+#[test]
+fn test_round_duration_sanity_bound() {
+    let one_year = Duration::from_secs(365 * 24 * 3600);
+    let time_interval = Box::new(ScriptedTimeInterval {
+        durations: vec![Duration::from_millis(100), one_year],
+    });
+    let mut config = PacemakerConfig::default();
+    config.round_duration_sanity_bound = Some(Duration::from_secs(3600));
+    let (new_round_events_sender, mut new_round_events_receiver) = channel::new_test(1_024);
+    let (pacemaker_timeout_tx, _pacemaker_timeout_rx) = channel::new_test(1_024);
+    let mut pm = PacemakerBuilder::new(
+        MockStorage::<TestPayload>::start_for_testing()
+            .0
+            .persistent_liveness_storage(),
+        time_interval,
+        Arc::new(SimulatedTimeService::new()),
+        new_round_events_sender,
+        pacemaker_timeout_tx,
+        3,
+    )
+    .config(config)
+    .build();
+    block_on(pm.start());
+    // The counters are global, other tests may increase them concurrently
+    let clamped_before = counters::ROUND_DURATION_CLAMPED_COUNT.get();
+    block_on(pm.process_certificates(1, None, None));
+
+    // The round of 1 year is clamped to the bound of 1 hour
+    let timeouts: Vec<Duration> = (0..2)
+        .map(|_| {
+            try_next_event(&mut new_round_events_receiver)
+                .unwrap()
+                .timeout
+        })
+        .collect();
+    assert_eq!(
+        timeouts,
+        vec![Duration::from_millis(100), Duration::from_secs(3600)]
+    );
+    assert!(counters::ROUND_DURATION_CLAMPED_COUNT.get() > clamped_before);
+}
+
+#[test]
+fn test_max_round_start_skew() {
+    let time_service = Arc::new(ManualTimeService::default());
+    *time_service.now.lock().unwrap() = Duration::from_secs(100);
+    let mut config = PacemakerConfig::default();
+    config.min_round_duration = Some(Duration::from_secs(10));
+    config.max_round_start_skew = Some(Duration::from_secs(2));
+    let (mut pm, mut new_round_events_receiver) =
+        make_unstarted_pacemaker_with_time_service(config, time_service.clone());
+    block_on(pm.start());
+    while try_next_event(&mut new_round_events_receiver).is_some() {}
+
+    // A quorum certificate slightly behind the local time anchors the start of the round it
+    // triggers, and the timeout runs from its timestamp
+    let qc = make_qc_with_timestamp(2, Duration::from_millis(99_500));
+    block_on(pm.process_quorum_cert(&qc, None, None));
+    assert_eq!(pm.current_round(), 3);
+    assert_eq!(
+        pm.round_start_timestamp(3),
+        Some(Duration::from_millis(99_500))
+    );
+    assert_eq!(pm.time_until_timeout(), Some(Duration::from_millis(9_500)));
+    let event = try_next_event(&mut new_round_events_receiver).unwrap();
+    assert_eq!(event.timeout, Duration::from_millis(9_500));
+
+    // A timestamp further behind is brought back to the skew bound
+    let qc = make_qc_with_timestamp(3, Duration::from_secs(90));
+    block_on(pm.process_quorum_cert(&qc, None, None));
+    assert_eq!(pm.round_start_timestamp(4), Some(Duration::from_secs(98)));
+    assert_eq!(pm.time_until_timeout(), Some(Duration::from_secs(8)));
+
+    // A timestamp ahead of the local time is ignored
+    let qc = make_qc_with_timestamp(4, Duration::from_secs(101));
+    block_on(pm.process_quorum_cert(&qc, None, None));
+    assert_eq!(pm.round_start_timestamp(5), Some(Duration::from_secs(100)));
+    assert_eq!(pm.time_until_timeout(), Some(Duration::from_secs(10)));
+
+    // The rounds entered without a quorum certificate start at the local time
+    block_on(pm.process_certificates(5, None, None));
+    assert_eq!(pm.round_start_timestamp(6), Some(Duration::from_secs(100)));
+    assert_eq!(pm.time_until_timeout(), Some(Duration::from_secs(10)));
+}
+
+#[test]
+fn test_export_import_pending_timeouts() {
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 2, &signers[0], None)));
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 3, &signers[1], None)));
+    assert_eq!(pm.timeouts_needed_for_quorum(2), 1);
+    assert_eq!(pm.timeouts_needed_for_quorum(3), 2);
+
+    // The export groups the timeouts by round and leaves the state unchanged
+    let exported = pm.export_pending_timeouts();
+    assert_eq!(exported.len(), 2);
+    assert_eq!(
+        exported.timeouts.keys().cloned().collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+    assert_eq!(exported.timeouts[&3][0].author(), signers[1].author());
+    assert_eq!(pm.export_pending_timeouts(), exported);
+    assert_eq!(pm.timeouts_needed_for_quorum(2), 1);
+
+    // A fresh pacemaker reflects the same quorum progress once imported
+    let (mut standby, _standby_new_round_events_receiver) = make_pacemaker();
+    assert!(standby.export_pending_timeouts().is_empty());
+    block_on(standby.import_pending_timeouts(exported.clone()));
+    assert_eq!(standby.export_pending_timeouts(), exported);
+    assert_eq!(standby.timeouts_needed_for_quorum(2), 1);
+    assert_eq!(standby.timeouts_needed_for_quorum(3), 2);
+    assert_eq!(standby.current_round(), 1);
+
+    // Both form the timeout certificate of round 2 on the same next timeout
+    let timeout = PacemakerTimeout::new(0, 2, &signers[2], None);
+    block_on(pm.process_remote_timeout(timeout.clone()));
+    block_on(standby.process_remote_timeout(timeout));
+    assert_eq!(pm.current_round(), 3);
+    assert_eq!(standby.current_round(), 3);
+}
+
+#[test]
+fn test_process_batch() {
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0u8; 32]);
+    let batch = || {
+        vec![
+            PacemakerInput::LocalTimeout(3),
+            PacemakerInput::Certificates {
+                qc_round: 3,
+                highest_committed_round: None,
+                timeout_certificate: None,
+            },
+        ]
+    };
+    let broadcaster = Arc::new(MockTimeoutBroadcaster::default());
+    let (mut pm, mut new_round_events_receiver) = make_pacemaker();
+    pm.set_timeout_broadcaster(broadcaster.clone());
+    block_on(pm.process_certificates(2, None, None));
+    assert_eq!(pm.current_round(), 3);
+    while try_next_event(&mut new_round_events_receiver).is_some() {}
+
+    // The quorum certificate of the batch wins over the local timeout of its round
+    let broadcast_rounds = block_on(pm.process_batch(batch()));
+    assert!(broadcast_rounds.is_empty());
+    assert_eq!(pm.current_round(), 4);
+    let event = try_next_event(&mut new_round_events_receiver).unwrap();
+    assert_eq!(event.round, 4);
+    assert_eq!(event.reason, NewRoundReason::QCReady);
+    block_on(pm.broadcast_local_timeout(PacemakerTimeout::new(0, 3, &signer, None)));
+    assert!(broadcaster.timeouts.lock().unwrap().is_empty());
+
+    // In arrival order, the local timeout is processed before the quorum certificate
+    let mut config = PacemakerConfig::default();
+    config.batch_ordering = BatchOrdering::Arrival;
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    block_on(pm.process_certificates(2, None, None));
+    assert_eq!(block_on(pm.process_batch(batch())), vec![3]);
+    assert_eq!(pm.current_round(), 4);
+}
+
+#[test]
+fn test_timer_lag() {
+    let time_service = Arc::new(ManualTimeService::default());
+    *time_service.now.lock().unwrap() = Duration::from_secs(100);
+    let mut config = PacemakerConfig::default();
+    config.min_round_duration = Some(Duration::from_secs(10));
+    config.timer_lag_warn_threshold = Some(Duration::from_secs(1));
+    let (mut pm, _new_round_events_receiver) =
+        make_unstarted_pacemaker_with_time_service(config, time_service.clone());
+    block_on(pm.start());
+
+    // The local timeout of the 10s round fires 3s late
+    // The counters are global, other tests may increase them concurrently
+    let samples = counters::TIMER_LAG_MS.get_sample_count();
+    let lag = counters::TIMER_LAG_MS.get_sample_sum();
+    *time_service.now.lock().unwrap() = Duration::from_secs(113);
+    assert!(pm.process_local_timeout(1));
+    assert!(counters::TIMER_LAG_MS.get_sample_count() > samples);
+    assert!(counters::TIMER_LAG_MS.get_sample_sum() - lag >= 3_000.0);
+
+    // The local timeout of a round the pacemaker is not in is not measured
+    let samples = counters::TIMER_LAG_MS.get_sample_count();
+    assert!(!pm.process_local_timeout(7));
+    assert_eq!(counters::TIMER_LAG_MS.get_sample_count(), samples);
+}
+
+#[test]
+fn test_proposer_election_factory() {
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..4u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let heavy_author = signers[2].author();
+    // A rotating election in epoch 1, an election of the single weighted author afterwards
+    pm.set_proposer_election_factory(
+        move |epoch, validator_verifier| -> Box<dyn ProposerElection<TestPayload> + Send + Sync> {
+            let authors = validator_verifier.get_ordered_account_addresses();
+            if epoch == 1 {
+                return Box::new(RotatingProposer::new(authors, 1));
+            }
+            let weights = authors
+                .into_iter()
+                .map(|author| (author, if author == heavy_author { 1 } else { 0 }))
+                .collect();
+            Box::new(WeightedProposer::new(weights, false))
+        },
+    );
+    assert!(pm.proposers(2).is_empty());
+    let author_to_public_keys: HashMap<_, _> = signers
+        .iter()
+        .map(|signer| (signer.author(), signer.public_key()))
+        .collect();
+    let make_params = |epoch| ReconfigParams::<TestPayload> {
+        epoch,
+        validator_verifier: Arc::new(ValidatorVerifier::new(author_to_public_keys.clone())),
+        time_interval: None,
+        proposer_election: None,
+    };
+
+    block_on(pm.reconfigure(make_params(1)).unwrap());
+    let rotating: Box<dyn ProposerElection<TestPayload>> = Box::new(RotatingProposer::new(
+        ValidatorVerifier::new(author_to_public_keys.clone()).get_ordered_account_addresses(),
+        1,
+    ));
+    for round in 2..6 {
+        assert_eq!(pm.proposers(round), rotating.get_valid_proposers(round));
+    }
+
+    block_on(pm.reconfigure(make_params(2)).unwrap());
+    for round in 2..6 {
+        assert_eq!(pm.proposers(round), vec![heavy_author]);
+    }
+}
+
+#[test]
+fn test_metrics_snapshot() {
+    let (mut pm, _new_round_events_receiver) = make_pacemaker();
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let time_out_round = |pm: &mut Pacemaker, round| {
+        for signer in &signers {
+            block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, round, signer, None)));
+        }
+        assert_eq!(pm.current_round(), round + 1);
+    };
+    assert_eq!(pm.metrics_snapshot(), PacemakerMetrics::default());
+
+    time_out_round(&mut pm, 1);
+    time_out_round(&mut pm, 2);
+    assert_eq!(pm.metrics_snapshot().timeout_advances, 2);
+    assert_eq!(pm.metrics_snapshot().qc_advances, 0);
+
+    // Only the events after the reset are counted
+    pm.reset_metrics();
+    assert_eq!(pm.metrics_snapshot(), PacemakerMetrics::default());
+    time_out_round(&mut pm, 3);
+    block_on(pm.process_certificates(4, None, None));
+    assert_eq!(
+        pm.metrics_snapshot(),
+        PacemakerMetrics {
+            qc_advances: 1,
+            timeout_advances: 1,
+            local_timeouts: 0,
+            conflicting_timeouts: 0,
+        }
+    );
+}
+
+#[test]
+fn test_quorum_growth_keeps_highest_timeout_certificate() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..7u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let mut config = PacemakerConfig::default();
+    config.quorum_spec = Some(QuorumSpec::Fraction { num: 2, den: 3 });
+    let (mut pm, _new_round_events_receiver) = make_pacemaker_with_config(config);
+    pm.update_quorum_size(4);
+    assert_eq!(pm.quorum_size(), 3);
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 2, &signers[3], None)));
+    for signer in &signers[..3] {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 5, signer, None)));
+    }
+    assert_eq!(pm.current_round(), 6);
+
+    // With the larger quorum, the received timeouts only form a certificate of round 2, below
+    // the held one of round 5
+    pm.update_quorum_size(7);
+    assert_eq!(pm.quorum_size(), 5);
+    block_on(pm.process_remote_timeout(PacemakerTimeout::new(0, 6, &signers[4], None)));
+    assert_eq!(pm.current_round(), 6);
+    assert_eq!(pm.timeout_certificate(5).map(|tc| tc.round()), Some(5));
+
+    // After a reconfiguration, a quorum of the new epoch advances the round again
+    let author_to_public_keys: HashMap<_, _> = signers
+        .iter()
+        .map(|signer| (signer.author(), signer.public_key()))
+        .collect();
+    block_on(
+        pm.reconfigure(ReconfigParams::<TestPayload> {
+            epoch: 1,
+            validator_verifier: Arc::new(ValidatorVerifier::new(author_to_public_keys)),
+            time_interval: None,
+            proposer_election: None,
+        })
+        .unwrap(),
+    );
+    for signer in &signers[..5] {
+        block_on(pm.process_remote_timeout(PacemakerTimeout::new(1, 6, signer, None)));
+    }
+    assert_eq!(pm.current_round(), 7);
+}
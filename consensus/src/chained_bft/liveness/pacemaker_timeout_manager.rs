@@ -1,14 +1,37 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::chained_bft::{
-    common::Author,
-    consensus_types::timeout_msg::{PacemakerTimeout, PacemakerTimeoutCertificate},
-    persistent_storage::PersistentLivenessStorage,
+use crate::{
+    chained_bft::{
+        common::{Author, Round},
+        consensus_types::timeout_msg::{
+            PacemakerTimeout, PacemakerTimeoutCertificate,
+            PacemakerTimeoutCertificateVerificationError,
+        },
+        liveness::{
+            pacemaker_config::{RetryPolicy, TimeoutCertificateTieBreak},
+            pacemaker_observers::RoundSummary,
+        },
+        persistent_storage::PersistentLivenessStorage,
+    },
+    counters,
+    util::time_service::{ScheduledTask, TimeService},
 };
+use crypto::ed25519::*;
+use failure::Result;
+use futures::{future, Future, FutureExt};
 use logger::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use types::validator_verifier::ValidatorVerifier;
 
 #[cfg(test)]
 #[path = "pacemaker_timeout_manager_test.rs"]
@@ -57,6 +80,141 @@ impl HighestTimeoutCertificates {
             self.highest_local_timeout_certificate.as_ref()
         }
     }
+
+    /// Verifies both the locally gathered and the received timeout certificates (e.g., before
+    /// trusting the certificates recovered from the storage)
+    pub fn verify(
+        &self,
+        validator: &ValidatorVerifier<Ed25519PublicKey>,
+    ) -> Result<(), PacemakerTimeoutCertificateVerificationError> {
+        for tc in self
+            .highest_local_timeout_certificate
+            .iter()
+            .chain(self.highest_received_timeout_certificate.iter())
+        {
+            tc.verify(validator)?;
+        }
+        Ok(())
+    }
+
+    /// Return whether the highest timeout certificate (see `highest_timeout_certificate`) is
+    /// received from another replica rather than gathered locally
+    pub fn is_highest_received(&self) -> bool {
+        match (
+            &self.highest_local_timeout_certificate,
+            &self.highest_received_timeout_certificate,
+        ) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(local), Some(received)) => local.round() <= received.round(),
+        }
+    }
+}
+
+/// Proof that an author signed conflicting timeouts for the same round: the votes attached to its
+/// timeouts in two timeout certificates are for different blocks of the same round, which an
+/// honest author never does.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConflictProof {
+    pub author: Author,
+    pub round: Round,
+    pub first_timeout: PacemakerTimeout,
+    pub second_timeout: PacemakerTimeout,
+}
+
+/// Compares two timeout certificates of the same round and returns the proof of the first author
+/// found with conflicting timeouts in them.  The certificates are expected to be verified already.
+/// Only the ids of the blocks voted for by the timeouts are compared: the timeouts without a vote,
+/// or whose votes are for different rounds, never conflict, even if their other fields differ.
+pub fn detect_conflicting_tc(
+    tc_a: &PacemakerTimeoutCertificate,
+    tc_b: &PacemakerTimeoutCertificate,
+) -> Option<ConflictProof> {
+    if tc_a.round() != tc_b.round() {
+        return None;
+    }
+    for timeout_a in tc_a.timeouts() {
+        for timeout_b in tc_b.timeouts() {
+            if timeout_a.author() != timeout_b.author() || timeout_a.round() != timeout_b.round() {
+                continue;
+            }
+            if let (Some(vote_a), Some(vote_b)) = (timeout_a.vote_msg(), timeout_b.vote_msg()) {
+                if vote_a.round() == vote_b.round()
+                    && vote_a.proposed_block_id() != vote_b.proposed_block_id()
+                {
+                    return Some(ConflictProof {
+                        author: timeout_a.author(),
+                        round: timeout_a.round(),
+                        first_timeout: timeout_a.clone(),
+                        second_timeout: timeout_b.clone(),
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+/// State of the writes of the timeout certificates, shared with their scheduled retries.
+#[derive(Default)]
+struct WriteState {
+    // Incremented on every write, a retry is only attempted if no newer write happened
+    generation: AtomicU64,
+    // Whether a write failed without a retry left since the last take_write_failure
+    failed: AtomicBool,
+}
+
+/// Retry of a failed write of the highest timeout certificates, which schedules the next retry
+/// on the time service if it fails again.
+struct RetryWriteTask {
+    storage: Arc<dyn PersistentLivenessStorage>,
+    highest_timeout_certificates: HighestTimeoutCertificates,
+    write_state: Arc<WriteState>,
+    generation: u64,
+    retry: u32,
+    storage_retry: RetryPolicy,
+    time_service: Arc<dyn TimeService>,
+}
+
+impl ScheduledTask for RetryWriteTask {
+    fn run(&mut self) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        if self.write_state.generation.load(Ordering::SeqCst) != self.generation {
+            return future::ready(()).boxed();
+        }
+        let e = match self
+            .storage
+            .save_highest_timeout_cert(self.highest_timeout_certificates.clone())
+        {
+            Ok(()) => return future::ready(()).boxed(),
+            Err(e) => e,
+        };
+        if self.retry >= self.storage_retry.max_retries {
+            error!(
+                "Failed to persist highest timeout certificates due to {}, retries exhausted",
+                e
+            );
+            self.write_state.failed.store(true, Ordering::SeqCst);
+            return future::ready(()).boxed();
+        }
+        let retry = self.retry + 1;
+        warn!(
+            "Failed to persist highest timeout certificates due to {}, retry {} of {} scheduled",
+            e, retry, self.storage_retry.max_retries
+        );
+        self.time_service.run_after(
+            self.storage_retry.delay_before(retry),
+            Box::new(RetryWriteTask {
+                storage: self.storage.clone(),
+                highest_timeout_certificates: self.highest_timeout_certificates.clone(),
+                write_state: self.write_state.clone(),
+                generation: self.generation,
+                retry,
+                storage_retry: self.storage_retry,
+                time_service: self.time_service.clone(),
+            }),
+        );
+        future::ready(()).boxed()
+    }
 }
 
 /// Manages the PacemakerTimeout structs received from replicas.
@@ -66,12 +224,35 @@ impl HighestTimeoutCertificates {
 pub struct PacemakerTimeoutManager {
     // The minimum quorum to generate a timeout certificate
     timeout_certificate_quorum_size: usize,
+    // Voting powers of the authors and voting power of a quorum, which replaces the quorum size
+    // if set, see set_voting_power_quorum
+    voting_power_quorum: Option<(HashMap<Author, u64>, u64)>,
+    // Minimum number of distinct signers of a timeout certificate, on top of the quorum
+    min_signers: usize,
+    // Maximum number of distinct rounds of author_to_received_timeouts, see set_max_tracked_rounds
+    max_tracked_rounds: Option<usize>,
+    // Highest round of the evicted timeouts, the timeouts at or below it are ignored
+    evicted_round: Option<Round>,
     // Track the PacemakerTimeoutMsg for highest timeout round received from this node
     author_to_received_timeouts: HashMap<Author, PacemakerTimeout>,
     // Highest timeout certificates
     highest_timeout_certificates: HighestTimeoutCertificates,
     // Used to persistently store the latest known timeout certificate
-    persistent_liveness_storage: Box<dyn PersistentLivenessStorage>,
+    persistent_liveness_storage: Arc<dyn PersistentLivenessStorage>,
+    // Retries of the failed writes of the timeout certificates, see set_storage_retry
+    storage_retry: RetryPolicy,
+    // Choice between the timeout certificates of the same round, see set_tie_break
+    tie_break: TimeoutCertificateTieBreak,
+    // State of the writes of the timeout certificates, shared with their scheduled retries
+    write_state: Arc<WriteState>,
+    // Clock of the receipt times of the timeouts and of the write retries, see set_time_service
+    time_service: Option<Arc<dyn TimeService>>,
+    // Receipt time of the timeouts of author_to_received_timeouts
+    author_to_receipt_times: HashMap<Author, Duration>,
+    // Formation latency of the last generated timeout certificate
+    last_formation_latency: Option<Duration>,
+    // Last conflict found in the received timeout certificates, see take_conflict_proof
+    conflict_proof: Option<ConflictProof>,
 }
 
 impl PacemakerTimeoutManager {
@@ -93,45 +274,267 @@ impl PacemakerTimeoutManager {
         }
         PacemakerTimeoutManager {
             timeout_certificate_quorum_size,
+            voting_power_quorum: None,
+            min_signers: 0,
+            max_tracked_rounds: None,
+            evicted_round: None,
             author_to_received_timeouts,
             highest_timeout_certificates,
-            persistent_liveness_storage,
+            persistent_liveness_storage: Arc::from(persistent_liveness_storage),
+            storage_retry: RetryPolicy::default(),
+            tie_break: TimeoutCertificateTieBreak::default(),
+            write_state: Arc::new(WriteState::default()),
+            time_service: None,
+            author_to_receipt_times: HashMap::new(),
+            last_formation_latency: None,
+            conflict_proof: None,
+        }
+    }
+
+    /// Sets the voting powers of the authors and the voting power of a quorum: the timeout
+    /// certificates are then formed by the timeouts of authors with enough voting power together
+    /// (the authors without a voting power count for nothing), regardless of the quorum size.
+    #[cfg(test)]
+    pub fn set_voting_power_quorum(
+        &mut self,
+        voting_powers: HashMap<Author, u64>,
+        quorum_voting_power: u64,
+    ) {
+        self.voting_power_quorum = Some((voting_powers, quorum_voting_power));
+    }
+
+    /// Sets the minimum number of distinct signers of a timeout certificate (e.g., for
+    /// auditability), which is enforced on top of the quorum: a quorum of fewer signers does not
+    /// form a certificate.
+    pub fn set_min_signers(&mut self, min_signers: usize) {
+        self.min_signers = min_signers;
+    }
+
+    /// Sets the maximum number of distinct rounds the received timeouts accumulate for: when a
+    /// timeout exceeds it, the timeouts of the lowest rounds are evicted, and the timeouts at or
+    /// below the evicted rounds are ignored from then on.  No cap if None.
+    pub fn set_max_tracked_rounds(&mut self, max_tracked_rounds: Option<usize>) {
+        assert!(max_tracked_rounds.map_or(true, |max| max > 0));
+        self.max_tracked_rounds = max_tracked_rounds;
+    }
+
+    /// Evicts the received timeouts of the lowest rounds until at most max_tracked_rounds distinct
+    /// rounds are left.
+    fn evict_lowest_rounds(&mut self) {
+        let max_tracked_rounds = match self.max_tracked_rounds {
+            Some(max_tracked_rounds) => max_tracked_rounds,
+            None => return,
+        };
+        let mut rounds: Vec<Round> = self
+            .author_to_received_timeouts
+            .values()
+            .map(PacemakerTimeout::round)
+            .collect();
+        rounds.sort();
+        rounds.dedup();
+        if rounds.len() <= max_tracked_rounds {
+            return;
+        }
+        let evicted_round = rounds[rounds.len() - max_tracked_rounds - 1];
+        let evicted_authors: Vec<Author> = self
+            .author_to_received_timeouts
+            .iter()
+            .filter(|(_, timeout)| timeout.round() <= evicted_round)
+            .map(|(author, _)| *author)
+            .collect();
+        for author in evicted_authors {
+            self.author_to_received_timeouts.remove(&author);
+            self.author_to_receipt_times.remove(&author);
+            counters::EVICTED_TIMEOUT_COUNT.inc();
+        }
+        self.evicted_round = Some(evicted_round);
+    }
+
+    /// Drops the received timeouts (and their receipt times), e.g., on an epoch change: the
+    /// timeouts of the previous epoch must not form a certificate with the ones of the new epoch.
+    pub fn clear_received_timeouts(&mut self) {
+        self.author_to_received_timeouts.clear();
+        self.author_to_receipt_times.clear();
+    }
+
+    /// Drops the timeout certificates along with the received timeouts, and persists the dropped
+    /// certificates, e.g., when the rounds restart with an automatic epoch: the certificates of
+    /// the previous epoch must not advance the rounds of the new one.
+    pub fn reset_timeout_certificates(&mut self) {
+        self.clear_received_timeouts();
+        self.evicted_round = None;
+        self.highest_timeout_certificates = HighestTimeoutCertificates::default();
+        if let Err(e) = self.save_highest_timeout_certificates() {
+            error!(
+                "Failed to persist the reset highest timeout certificates due to {}",
+                e
+            );
+            self.write_state.failed.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Sets the retries of the failed writes of the timeout certificates, which are scheduled on
+    /// the time service (see set_time_service, no retry without it).  A write is only reported as
+    /// failed (see take_write_failure) once its retries are exhausted.
+    pub fn set_storage_retry(&mut self, storage_retry: RetryPolicy) {
+        self.storage_retry = storage_retry;
+    }
+
+    /// Sets the choice between the timeout certificates of the same round with different signers.
+    pub fn set_tie_break(&mut self, tie_break: TimeoutCertificateTieBreak) {
+        self.tie_break = tie_break;
+    }
+
+    /// Returns whether the candidate timeout certificate is preferred over the held certificate
+    /// of the same round as per tie_break.
+    fn is_preferred(
+        &self,
+        candidate: &PacemakerTimeoutCertificate,
+        held: &PacemakerTimeoutCertificate,
+    ) -> bool {
+        if self.tie_break == TimeoutCertificateTieBreak::FewestSigners {
+            return false;
+        }
+        let sorted_authors = |tc: &PacemakerTimeoutCertificate| {
+            let mut authors: Vec<Author> =
+                tc.timeouts().iter().map(PacemakerTimeout::author).collect();
+            authors.sort();
+            authors
+        };
+        let (candidate_authors, held_authors) = (sorted_authors(candidate), sorted_authors(held));
+        candidate_authors.len() > held_authors.len()
+            || (candidate_authors.len() == held_authors.len() && candidate_authors < held_authors)
+    }
+
+    /// Persists the highest timeout certificates.  A failed write is retried on the time service
+    /// as per storage_retry, unless a newer write supersedes it meanwhile.  Returns the error if
+    /// the write failed without a retry left.
+    fn save_highest_timeout_certificates(&self) -> Result<()> {
+        let generation = self.write_state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let e = match self
+            .persistent_liveness_storage
+            .save_highest_timeout_cert(self.highest_timeout_certificates.clone())
+        {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+        let time_service = match &self.time_service {
+            Some(time_service) if self.storage_retry.max_retries > 0 => time_service.clone(),
+            _ => return Err(e),
+        };
+        warn!(
+            "Failed to persist highest timeout certificates due to {}, retry 1 of {} scheduled",
+            e, self.storage_retry.max_retries
+        );
+        time_service.run_after(
+            self.storage_retry.delay_before(1),
+            Box::new(RetryWriteTask {
+                storage: self.persistent_liveness_storage.clone(),
+                highest_timeout_certificates: self.highest_timeout_certificates.clone(),
+                write_state: self.write_state.clone(),
+                generation,
+                retry: 1,
+                storage_retry: self.storage_retry,
+                time_service: time_service.clone(),
+            }),
+        );
+        Ok(())
+    }
+
+    /// Sets the clock of the receipt times of the timeouts, from which the formation latency of
+    /// the timeout certificates is measured, and on which the write retries are scheduled.  No
+    /// latency is measured and no write is retried if not set.
+    pub fn set_time_service(&mut self, time_service: Arc<dyn TimeService>) {
+        self.time_service = Some(time_service);
+    }
+
+    /// Returns the time elapsed between the first received timeout of the last generated timeout
+    /// certificate and the timeout reaching its quorum, if measured.
+    #[cfg(test)]
+    pub fn last_formation_latency(&self) -> Option<Duration> {
+        self.last_formation_latency
+    }
+
+    /// Measures the formation latency of the generated timeout certificate.
+    fn record_formation_latency(&mut self, timeout_certificate: &PacemakerTimeoutCertificate) {
+        let now = match self.time_service.as_ref() {
+            Some(time_service) => time_service.get_current_timestamp(),
+            None => return,
+        };
+        let first_receipt_time = timeout_certificate
+            .timeouts()
+            .iter()
+            .filter_map(|timeout| self.author_to_receipt_times.get(&timeout.author()))
+            .min()
+            .cloned();
+        if let Some(latency) = first_receipt_time.and_then(|time| now.checked_sub(time)) {
+            counters::TIMEOUT_CERTIFICATE_FORMATION_MS.observe(latency.as_millis() as f64);
+            self.last_formation_latency = Some(latency);
         }
     }
 
-    /// Returns the highest round PacemakerTimeoutCertificate from a map of author to
-    /// timeout messages or None if there are not enough timeout messages available.
+    /// Returns the highest round PacemakerTimeoutCertificate from the received timeout messages
+    /// or None if there are not enough timeout messages available.
     /// A PacemakerTimeoutCertificate is made of the N highest timeout messages received where
-    /// N=timeout_quorum_size.  The round of PacemakerTimeoutCertificate is determined as
-    /// the smallest of round of all messages used to generate this certificate.
+    /// N=timeout_quorum_size (or the fewest highest messages reaching the voting power of a
+    /// quorum, see set_voting_power_quorum), and at least min_signers.  The round of
+    /// PacemakerTimeoutCertificate is determined as the smallest of round of all messages used to
+    /// generate this certificate.
     ///
     /// For example, if timeout_certificate_quorum_size=3 and we received unique author timeouts
     /// for rounds (1,2,3,4), then rounds (2,3,4) would form PacemakerTimeoutCertificate with
     /// round=2.
-    fn generate_timeout_certificate(
-        author_to_received_timeouts: &HashMap<Author, PacemakerTimeout>,
-        timeout_certificate_quorum_size: usize,
-    ) -> Option<PacemakerTimeoutCertificate> {
-        if author_to_received_timeouts.values().len() < timeout_certificate_quorum_size {
-            return None;
-        }
-        let mut values: Vec<&PacemakerTimeout> = author_to_received_timeouts.values().collect();
-        values.sort_by(|x, y| y.round().cmp(&x.round()));
-        let slice = &values[..timeout_certificate_quorum_size];
-        Some(PacemakerTimeoutCertificate::new(
-            // expect does not panic here because code above verifies values length
-            slice
-                .last()
-                .expect("Slice for timeout certificate is empty")
-                .round(),
-            slice.iter().map(|x| (*x).clone()).collect(),
-        ))
+    ///
+    /// The certificate is canonical regardless of the arrival order of the timeouts: among the
+    /// timeouts of the same round the lowest authors are chosen first, and the timeouts of the
+    /// certificate are ordered by author.  With `TimeoutCertificateTieBreak::MostSigners`, the
+    /// certificate includes all the timeouts at or above its round instead.
+    fn generate_timeout_certificate(&self) -> Option<PacemakerTimeoutCertificate> {
+        let mut values: Vec<&PacemakerTimeout> =
+            self.author_to_received_timeouts.values().collect();
+        values.sort_by(|x, y| {
+            y.round()
+                .cmp(&x.round())
+                .then_with(|| x.author().cmp(&y.author()))
+        });
+        let mut voting_power = 0;
+        for (index, timeout) in values.iter().enumerate() {
+            let signers = index + 1;
+            let quorum_reached = match &self.voting_power_quorum {
+                Some((voting_powers, quorum_voting_power)) => {
+                    voting_power += voting_powers.get(&timeout.author()).cloned().unwrap_or(0);
+                    voting_power >= *quorum_voting_power
+                }
+                None => signers >= self.timeout_certificate_quorum_size,
+            };
+            if quorum_reached && signers >= self.min_signers {
+                let signers = match self.tie_break {
+                    TimeoutCertificateTieBreak::FewestSigners => signers,
+                    TimeoutCertificateTieBreak::MostSigners => values
+                        .iter()
+                        .take_while(|x| x.round() >= timeout.round())
+                        .count(),
+                };
+                let mut timeouts: Vec<PacemakerTimeout> =
+                    values[..signers].iter().map(|x| (*x).clone()).collect();
+                timeouts.sort_by_key(PacemakerTimeout::author);
+                return Some(PacemakerTimeoutCertificate::new(timeout.round(), timeouts));
+            }
+        }
+        None
     }
 
     /// Updates internal state according to received message from remote pacemaker and returns true
     /// if round derived from highest PacemakerTimeoutCertificate has increased.
     pub fn update_received_timeout(&mut self, pacemaker_timeout: PacemakerTimeout) -> bool {
         let author = pacemaker_timeout.author();
+        if let Some(evicted_round) = self.evicted_round {
+            if pacemaker_timeout.round() <= evicted_round {
+                warn!("Received timeout message for an evicted round, ignoring. Author: {}, evicted round: {}, received: {}",
+                          author.short_str(), evicted_round, pacemaker_timeout.round());
+                return false;
+            }
+        }
         let prev_timeout = self.author_to_received_timeouts.get(&author).cloned();
         if let Some(prev_timeout) = &prev_timeout {
             if prev_timeout.round() >= pacemaker_timeout.round() {
@@ -143,10 +546,15 @@ impl PacemakerTimeoutManager {
 
         self.author_to_received_timeouts
             .insert(author, pacemaker_timeout.clone());
-        let highest_timeout_certificate = Self::generate_timeout_certificate(
-            &self.author_to_received_timeouts,
-            self.timeout_certificate_quorum_size,
-        );
+        if let Some(time_service) = self.time_service.as_ref() {
+            self.author_to_receipt_times
+                .insert(author, time_service.get_current_timestamp());
+        }
+        self.evict_lowest_rounds();
+        if !self.author_to_received_timeouts.contains_key(&author) {
+            return false;
+        }
+        let highest_timeout_certificate = self.generate_timeout_certificate();
         let highest_round = match &highest_timeout_certificate {
             Some(tc) => tc.round(),
             None => return false,
@@ -156,57 +564,71 @@ impl PacemakerTimeoutManager {
             .highest_local_timeout_certificate
             .as_ref()
             .map(PacemakerTimeoutCertificate::round);
-        assert!(
-            highest_round >= prev_highest_round.unwrap_or(0),
-            "Went down on highest timeout quorum round from {:?} to {:?}.
-            Received: {:?}, all: {:?}",
-            prev_highest_round,
-            highest_round,
-            pacemaker_timeout,
-            self.author_to_received_timeouts,
-        );
+        if highest_round < prev_highest_round.unwrap_or(0) {
+            // The quorum grew (e.g., on a reconfiguration) or the received timeouts were dropped
+            // (on an epoch change) since the held certificate was formed: it stays the highest.
+            debug!(
+                "Timeout quorum round {} is below the held one {:?}, keeping the held certificate",
+                highest_round, prev_highest_round
+            );
+            return false;
+        }
+        if highest_round > prev_highest_round.unwrap_or(0) {
+            if let Some(tc) = &highest_timeout_certificate {
+                self.record_formation_latency(tc);
+            }
+        }
         self.highest_timeout_certificates
             .highest_local_timeout_certificate = highest_timeout_certificate;
-        if let Err(e) = self
-            .persistent_liveness_storage
-            .save_highest_timeout_cert(self.highest_timeout_certificates.clone())
-        {
-            warn!(
+        if let Err(e) = self.save_highest_timeout_certificates() {
+            error!(
                 "Failed to persist local highest timeout certificate in round {} due to {}",
                 highest_round, e
             );
+            self.write_state.failed.store(true, Ordering::SeqCst);
         }
         highest_round > prev_highest_round.unwrap_or(0)
     }
 
     /// Attempts to update highest_received_timeout_certificate when receiving a new remote
-    /// timeout certificate.  Returns true if highest_received_timeout_certificate has changed
+    /// timeout certificate, which is higher or preferred over the held certificate of its round
+    /// (see set_tie_break).  Returns true if highest_received_timeout_certificate has changed.
+    /// The certificate is also checked for conflicts with the highest one (see
+    /// take_conflict_proof).
     pub fn update_highest_received_timeout_certificate(
         &mut self,
         timeout_certificate: &PacemakerTimeoutCertificate,
     ) -> bool {
-        if timeout_certificate.round()
-            > self
-                .highest_timeout_certificates
-                .highest_received_timeout_certificate
-                .as_ref()
-                .map_or(0, PacemakerTimeoutCertificate::round)
+        if let Some(conflict_proof) = self
+            .highest_timeout_certificate()
+            .and_then(|highest_tc| detect_conflicting_tc(highest_tc, timeout_certificate))
         {
+            self.conflict_proof = Some(conflict_proof);
+        }
+        let replaces_held = match &self
+            .highest_timeout_certificates
+            .highest_received_timeout_certificate
+        {
+            Some(held) if held.round() == timeout_certificate.round() => {
+                self.is_preferred(timeout_certificate, held)
+            }
+            Some(held) => timeout_certificate.round() > held.round(),
+            None => timeout_certificate.round() > 0,
+        };
+        if replaces_held {
             debug!(
                 "Received remote timeout certificate at round {}",
                 timeout_certificate.round()
             );
             self.highest_timeout_certificates
                 .highest_received_timeout_certificate = Some(timeout_certificate.clone());
-            if let Err(e) = self
-                .persistent_liveness_storage
-                .save_highest_timeout_cert(self.highest_timeout_certificates.clone())
-            {
-                warn!(
+            if let Err(e) = self.save_highest_timeout_certificates() {
+                error!(
                     "Failed to persist received highest timeout certificate in round {} due to {}",
                     timeout_certificate.round(),
                     e
                 );
+                self.write_state.failed.store(true, Ordering::SeqCst);
             }
             return true;
         }
@@ -219,4 +641,138 @@ impl PacemakerTimeoutManager {
         self.highest_timeout_certificates
             .highest_timeout_certificate()
     }
+
+    /// Return whether persisting the timeout certificates failed since the last call, and reset
+    /// the failure
+    pub fn take_write_failure(&mut self) -> bool {
+        self.write_state.failed.swap(false, Ordering::SeqCst)
+    }
+
+    /// Return the proof of the last conflict between a received timeout certificate and the
+    /// highest one (see detect_conflicting_tc) since the last call, and reset it
+    pub fn take_conflict_proof(&mut self) -> Option<ConflictProof> {
+        self.conflict_proof.take()
+    }
+
+    /// Return the highest timeout certificates, locally generated and remotely received
+    pub fn highest_timeout_certificates(&self) -> &HighestTimeoutCertificates {
+        &self.highest_timeout_certificates
+    }
+
+    /// Return the highest timeout received from every author
+    #[cfg(test)]
+    pub fn received_timeouts(&self) -> Vec<PacemakerTimeout> {
+        self.author_to_received_timeouts.values().cloned().collect()
+    }
+
+    /// Return the minimum quorum to generate a timeout certificate
+    pub fn quorum_size(&self) -> usize {
+        self.timeout_certificate_quorum_size
+    }
+
+    /// Change the minimum quorum to generate a timeout certificate, the new quorum applies from
+    /// the next received timeout on.
+    #[cfg(test)]
+    pub fn update_quorum_size(&mut self, timeout_certificate_quorum_size: usize) {
+        assert!(timeout_certificate_quorum_size > 0);
+        self.timeout_certificate_quorum_size = timeout_certificate_quorum_size;
+    }
+
+    /// Return the round of the highest timeout received from the given author, if any
+    pub fn received_timeout_round(&self, author: Author) -> Option<Round> {
+        self.author_to_received_timeouts
+            .get(&author)
+            .map(PacemakerTimeout::round)
+    }
+
+    /// Persist the current round of the pacemaker, which is not critical: failures are only logged
+    pub fn save_current_round(&self, round: Round) {
+        if let Err(e) = self.persistent_liveness_storage.save_current_round(round) {
+            warn!("Failed to persist the current round {} due to {}", round, e);
+        }
+    }
+
+    /// Persist the detailed state of an ended round, which is not critical: failures are only
+    /// logged
+    pub fn save_round_details(&self, summary: RoundSummary) {
+        let round = summary.round;
+        if let Err(e) = self.persistent_liveness_storage.save_round_details(summary) {
+            warn!(
+                "Failed to persist the details of round {} due to {}",
+                round, e
+            );
+        }
+    }
+
+    /// Return the rounds, in increasing order, of the timeout certificates held (locally formed or
+    /// received) and of the timeouts received, which accumulate into the next certificates
+    #[cfg(test)]
+    pub fn timeout_certificate_rounds(&self) -> Vec<Round> {
+        let mut rounds: Vec<Round> = self
+            .highest_timeout_certificates
+            .highest_local_timeout_certificate
+            .iter()
+            .chain(
+                self.highest_timeout_certificates
+                    .highest_received_timeout_certificate
+                    .iter(),
+            )
+            .map(PacemakerTimeoutCertificate::round)
+            .chain(
+                self.author_to_received_timeouts
+                    .values()
+                    .map(PacemakerTimeout::round),
+            )
+            .collect();
+        rounds.sort();
+        rounds.dedup();
+        rounds
+    }
+
+    /// Return the timeout certificate held (locally formed or received) for the given round, if any
+    #[cfg(test)]
+    pub fn timeout_certificate(&self, round: Round) -> Option<&PacemakerTimeoutCertificate> {
+        self.highest_timeout_certificates
+            .highest_local_timeout_certificate
+            .iter()
+            .chain(
+                self.highest_timeout_certificates
+                    .highest_received_timeout_certificate
+                    .iter(),
+            )
+            .find(|tc| tc.round() == round)
+    }
+
+    /// Return the number of authors whose highest received timeout is for the given round
+    pub fn received_timeouts_at_round(&self, round: Round) -> usize {
+        self.author_to_received_timeouts
+            .values()
+            .filter(|timeout| timeout.round() == round)
+            .count()
+    }
+
+    /// Return the total voting power of the authors whose highest received timeout is at or above
+    /// the given round (see set_voting_power_quorum).  Every author counts for 1 if the voting
+    /// powers are not set.
+    #[cfg(test)]
+    pub fn received_timeouts_power(&self, min_round: Round) -> u128 {
+        self.author_to_received_timeouts
+            .values()
+            .filter(|timeout| timeout.round() >= min_round)
+            .map(|timeout| match &self.voting_power_quorum {
+                Some((voting_powers, _)) => {
+                    u128::from(voting_powers.get(&timeout.author()).cloned().unwrap_or(0))
+                }
+                None => 1,
+            })
+            .sum()
+    }
+
+    /// Return the number of authors whose highest received timeout is at or above the given round
+    pub fn received_timeouts_count(&self, min_round: Round) -> usize {
+        self.author_to_received_timeouts
+            .values()
+            .filter(|timeout| timeout.round() >= min_round)
+            .count()
+    }
 }
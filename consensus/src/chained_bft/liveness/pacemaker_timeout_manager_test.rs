@@ -1,12 +1,25 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::chained_bft::{
-    consensus_types::timeout_msg::{PacemakerTimeout, PacemakerTimeoutCertificate},
-    liveness::pacemaker_timeout_manager::{HighestTimeoutCertificates, PacemakerTimeoutManager},
-    persistent_storage::PersistentStorage,
-    test_utils::{MockStorage, TestPayload},
+use crate::{
+    chained_bft::{
+        consensus_types::timeout_msg::{PacemakerTimeout, PacemakerTimeoutCertificate},
+        liveness::{
+            pacemaker_config::TimeoutCertificateTieBreak,
+            pacemaker_timeout_manager::{
+                detect_conflicting_tc, HighestTimeoutCertificates, PacemakerTimeoutManager,
+            },
+        },
+        persistent_storage::PersistentStorage,
+        safety::vote_msg::VoteMsg,
+        test_utils::{placeholder_ledger_info, MockStorage, TestPayload},
+    },
+    state_replication::ExecutedState,
+    util::{mock_time_service::SimulatedTimeService, time_service::TimeService},
 };
+use crypto::{ed25519::*, HashValue};
+use futures::executor::block_on;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use types::validator_signer::ValidatorSigner;
 
 #[test]
@@ -23,13 +36,13 @@ fn test_basic() {
     let validator_signer2 = ValidatorSigner::random([1u8; 32]);
 
     // No timeout certificate generated on adding 2 timeouts from the same author
-    let timeout_signer1_round1 = PacemakerTimeout::new(1, &validator_signer1, None);
+    let timeout_signer1_round1 = PacemakerTimeout::new(0, 1, &validator_signer1, None);
     assert_eq!(
         timeout_manager.update_received_timeout(timeout_signer1_round1),
         false
     );
     assert_eq!(timeout_manager.highest_timeout_certificate(), None);
-    let timeout_signer1_round2 = PacemakerTimeout::new(2, &validator_signer1, None);
+    let timeout_signer1_round2 = PacemakerTimeout::new(0, 2, &validator_signer1, None);
     assert_eq!(
         timeout_manager.update_received_timeout(timeout_signer1_round2),
         false
@@ -37,7 +50,7 @@ fn test_basic() {
     assert_eq!(timeout_manager.highest_timeout_certificate(), None);
 
     // Timeout certificate generated on adding a timeout from signer2
-    let timeout_signer2_round1 = PacemakerTimeout::new(1, &validator_signer2, None);
+    let timeout_signer2_round1 = PacemakerTimeout::new(0, 1, &validator_signer2, None);
     assert_eq!(
         timeout_manager.update_received_timeout(timeout_signer2_round1),
         true
@@ -51,7 +64,7 @@ fn test_basic() {
     );
 
     // Timeout certificate increased when incrementing the round from signer 2
-    let timeout_signer2_round2 = PacemakerTimeout::new(2, &validator_signer2, None);
+    let timeout_signer2_round2 = PacemakerTimeout::new(0, 2, &validator_signer2, None);
     assert_eq!(
         timeout_manager.update_received_timeout(timeout_signer2_round2),
         true
@@ -65,7 +78,7 @@ fn test_basic() {
     );
 
     // No timeout certificate generated since signer 1 is still on round 2
-    let timeout_signer2_round3 = PacemakerTimeout::new(3, &validator_signer2, None);
+    let timeout_signer2_round3 = PacemakerTimeout::new(0, 3, &validator_signer2, None);
     assert_eq!(
         timeout_manager.update_received_timeout(timeout_signer2_round3),
         false
@@ -82,8 +95,8 @@ fn test_basic() {
     let received_timeout_certificate = PacemakerTimeoutCertificate::new(
         10,
         vec![
-            PacemakerTimeout::new(10, &validator_signer1, None),
-            PacemakerTimeout::new(11, &validator_signer2, None),
+            PacemakerTimeout::new(0, 10, &validator_signer1, None),
+            PacemakerTimeout::new(0, 11, &validator_signer2, None),
         ],
     );
     assert_eq!(
@@ -104,8 +117,8 @@ fn test_recovery_from_highest_timeout_certificate() {
     let validator_signer1 = ValidatorSigner::random([0u8; 32]);
     let validator_signer2 = ValidatorSigner::random([1u8; 32]);
 
-    let timeout1 = PacemakerTimeout::new(10, &validator_signer1, None);
-    let timeout2 = PacemakerTimeout::new(11, &validator_signer2, None);
+    let timeout1 = PacemakerTimeout::new(0, 10, &validator_signer1, None);
+    let timeout2 = PacemakerTimeout::new(0, 11, &validator_signer2, None);
     let tc = PacemakerTimeoutCertificate::new(10, vec![timeout1, timeout2]);
 
     let timeout_manager = PacemakerTimeoutManager::new(
@@ -129,3 +142,307 @@ fn test_recovery_from_highest_timeout_certificate() {
         true
     );
 }
+
+fn make_timeout_with_vote(
+    round: u64,
+    signer: &ValidatorSigner<Ed25519PrivateKey>,
+    proposed_block_id: HashValue,
+) -> PacemakerTimeout {
+    let vote = VoteMsg::new(
+        proposed_block_id,
+        ExecutedState::state_for_genesis(),
+        round,
+        HashValue::zero(),
+        0,
+        HashValue::zero(),
+        0,
+        signer.author(),
+        placeholder_ledger_info(),
+        signer,
+    );
+    PacemakerTimeout::new(0, round, signer, Some(vote))
+}
+
+#[test]
+fn test_detect_conflicting_tc() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> =
+        (0..3u8).map(|i| ValidatorSigner::random([i; 32])).collect();
+    let block_a = HashValue::random();
+    let block_b = HashValue::random();
+    // The first signer is part of both certificates, voting for different blocks
+    let tc_a = PacemakerTimeoutCertificate::new(
+        1,
+        vec![
+            make_timeout_with_vote(1, &signers[0], block_a),
+            make_timeout_with_vote(1, &signers[1], block_a),
+        ],
+    );
+    let tc_b = PacemakerTimeoutCertificate::new(
+        1,
+        vec![
+            make_timeout_with_vote(1, &signers[0], block_b),
+            make_timeout_with_vote(1, &signers[2], block_b),
+        ],
+    );
+
+    let proof = detect_conflicting_tc(&tc_a, &tc_b).unwrap();
+    assert_eq!(proof.author, signers[0].author());
+    assert_eq!(proof.round, 1);
+    assert_eq!(proof.first_timeout, tc_a.timeouts()[0]);
+    assert_eq!(proof.second_timeout, tc_b.timeouts()[0]);
+
+    assert_eq!(detect_conflicting_tc(&tc_a, &tc_a), None);
+    let tc_c = PacemakerTimeoutCertificate::new(
+        1,
+        vec![
+            make_timeout_with_vote(1, &signers[1], block_a),
+            make_timeout_with_vote(1, &signers[2], block_a),
+        ],
+    );
+    assert_eq!(detect_conflicting_tc(&tc_a, &tc_c), None);
+
+    // The conflicts with the highest timeout certificate are kept until taken
+    let mut timeout_manager = PacemakerTimeoutManager::new(
+        2,
+        HighestTimeoutCertificates::new(None, None),
+        MockStorage::<TestPayload>::start_for_testing()
+            .0
+            .persistent_liveness_storage(),
+    );
+    assert!(timeout_manager.update_highest_received_timeout_certificate(&tc_a));
+    assert_eq!(timeout_manager.take_conflict_proof(), None);
+    timeout_manager.update_highest_received_timeout_certificate(&tc_b);
+    assert_eq!(timeout_manager.take_conflict_proof(), Some(proof));
+    assert_eq!(timeout_manager.take_conflict_proof(), None);
+}
+
+#[test]
+fn test_formation_latency() {
+    let time_service = SimulatedTimeService::new();
+    let mut timeout_manager = PacemakerTimeoutManager::new(
+        3,
+        HighestTimeoutCertificates::new(None, None),
+        MockStorage::<TestPayload>::start_for_testing()
+            .0
+            .persistent_liveness_storage(),
+    );
+    timeout_manager.set_time_service(Arc::new(time_service.clone()));
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> =
+        (0..3u8).map(|i| ValidatorSigner::random([i; 32])).collect();
+
+    // The timeouts of each round arrive at the given instants, the last one forms the certificate
+    let mut now = 0;
+    for (round, arrivals, expected_latency) in &[(1, [0, 100, 250], 250), (2, [400, 450, 700], 300)]
+    {
+        for (signer, arrival) in signers.iter().zip(arrivals.iter()) {
+            block_on(time_service.sleep(Duration::from_millis(arrival - now)));
+            now = *arrival;
+            timeout_manager.update_received_timeout(PacemakerTimeout::new(0, *round, signer, None));
+        }
+        assert_eq!(
+            timeout_manager
+                .highest_timeout_certificate()
+                .unwrap()
+                .round(),
+            *round
+        );
+        assert_eq!(
+            timeout_manager.last_formation_latency(),
+            Some(Duration::from_millis(*expected_latency))
+        );
+    }
+}
+
+#[test]
+fn test_min_signers() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> =
+        (0..3u8).map(|i| ValidatorSigner::random([i; 32])).collect();
+    // The two first validators together hold the voting power of a quorum
+    let voting_powers: HashMap<_, _> = signers
+        .iter()
+        .zip([5, 5, 1].iter())
+        .map(|(signer, power)| (signer.author(), *power))
+        .collect();
+
+    for min_signers in &[0, 3] {
+        let mut timeout_manager = PacemakerTimeoutManager::new(
+            3,
+            HighestTimeoutCertificates::new(None, None),
+            MockStorage::<TestPayload>::start_for_testing()
+                .0
+                .persistent_liveness_storage(),
+        );
+        timeout_manager.set_voting_power_quorum(voting_powers.clone(), 7);
+        timeout_manager.set_min_signers(*min_signers);
+        timeout_manager.update_received_timeout(PacemakerTimeout::new(0, 1, &signers[0], None));
+        timeout_manager.update_received_timeout(PacemakerTimeout::new(0, 1, &signers[1], None));
+        if *min_signers == 0 {
+            // The heavy validators alone form the certificate
+            let tc = timeout_manager.highest_timeout_certificate().unwrap();
+            assert_eq!(tc.round(), 1);
+            assert_eq!(tc.timeouts().len(), 2);
+            continue;
+        }
+        // The quorum is met, but not the floor of signers
+        assert!(timeout_manager.highest_timeout_certificate().is_none());
+        timeout_manager.update_received_timeout(PacemakerTimeout::new(0, 1, &signers[2], None));
+        let tc = timeout_manager.highest_timeout_certificate().unwrap();
+        assert_eq!(tc.round(), 1);
+        assert_eq!(tc.timeouts().len(), 3);
+    }
+}
+
+#[test]
+fn test_max_tracked_rounds() {
+    let mut timeout_manager = PacemakerTimeoutManager::new(
+        3,
+        HighestTimeoutCertificates::new(None, None),
+        MockStorage::<TestPayload>::start_for_testing()
+            .0
+            .persistent_liveness_storage(),
+    );
+    timeout_manager.set_max_tracked_rounds(Some(2));
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> =
+        (0..6u8).map(|i| ValidatorSigner::random([i; 32])).collect();
+
+    // Every author times out a different round, only the 2 highest rounds are kept
+    for (round, signer) in (1..=5).zip(signers.iter()) {
+        timeout_manager.update_received_timeout(PacemakerTimeout::new(0, round, signer, None));
+    }
+    let mut rounds: Vec<_> = timeout_manager
+        .received_timeouts()
+        .iter()
+        .map(PacemakerTimeout::round)
+        .collect();
+    rounds.sort();
+    assert_eq!(rounds, vec![4, 5]);
+    assert_eq!(
+        timeout_manager.received_timeout_round(signers[0].author()),
+        None
+    );
+
+    // The timeouts of the evicted rounds are ignored, the higher ones accumulate as usual
+    assert!(
+        !timeout_manager.update_received_timeout(PacemakerTimeout::new(0, 3, &signers[5], None))
+    );
+    assert_eq!(
+        timeout_manager.received_timeout_round(signers[5].author()),
+        None
+    );
+    assert!(
+        timeout_manager.update_received_timeout(PacemakerTimeout::new(0, 5, &signers[5], None))
+    );
+    assert_eq!(
+        timeout_manager
+            .highest_timeout_certificate()
+            .unwrap()
+            .round(),
+        4
+    );
+}
+
+#[test]
+fn test_canonical_timeout_certificate() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> =
+        (0..5u8).map(|i| ValidatorSigner::random([i; 32])).collect();
+    let make_tc = |arrival_order: &[usize]| {
+        let mut timeout_manager = PacemakerTimeoutManager::new(
+            3,
+            HighestTimeoutCertificates::new(None, None),
+            MockStorage::<TestPayload>::start_for_testing()
+                .0
+                .persistent_liveness_storage(),
+        );
+        for index in arrival_order {
+            timeout_manager.update_received_timeout(PacemakerTimeout::new(
+                0,
+                1,
+                &signers[*index],
+                None,
+            ));
+        }
+        timeout_manager
+            .highest_timeout_certificate()
+            .unwrap()
+            .clone()
+    };
+
+    let tc = make_tc(&[0, 1, 2, 3, 4]);
+    assert_eq!(tc, make_tc(&[3, 0, 4, 1, 2]));
+    assert_eq!(tc, make_tc(&[4, 3, 2, 1, 0]));
+    // The timeouts of the lowest authors are chosen, ordered by author
+    let authors: Vec<_> = tc.timeouts().iter().map(PacemakerTimeout::author).collect();
+    let mut expected_authors: Vec<_> = signers.iter().map(ValidatorSigner::author).collect();
+    expected_authors.sort();
+    expected_authors.truncate(3);
+    assert_eq!(authors, expected_authors);
+}
+
+#[test]
+fn test_timeout_certificate_tie_break() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> =
+        (0..5u8).map(|i| ValidatorSigner::random([i; 32])).collect();
+    let make_tc = |indices: &[usize]| {
+        PacemakerTimeoutCertificate::new(
+            5,
+            indices
+                .iter()
+                .map(|index| PacemakerTimeout::new(0, 5, &signers[*index], None))
+                .collect(),
+        )
+    };
+    let (smaller_tc, larger_tc) = (make_tc(&[0, 1, 2]), make_tc(&[1, 2, 3, 4]));
+    for tie_break in &[
+        TimeoutCertificateTieBreak::FewestSigners,
+        TimeoutCertificateTieBreak::MostSigners,
+    ] {
+        let storage = MockStorage::<TestPayload>::start_for_testing().0;
+        let mut timeout_manager = PacemakerTimeoutManager::new(
+            3,
+            HighestTimeoutCertificates::new(None, None),
+            storage.persistent_liveness_storage(),
+        );
+        timeout_manager.set_tie_break(*tie_break);
+
+        // Two candidate certificates are received for round 5, the larger one second
+        assert!(timeout_manager.update_highest_received_timeout_certificate(&smaller_tc));
+        let replaced = timeout_manager.update_highest_received_timeout_certificate(&larger_tc);
+        let expected_tc = match tie_break {
+            TimeoutCertificateTieBreak::FewestSigners => &smaller_tc,
+            TimeoutCertificateTieBreak::MostSigners => &larger_tc,
+        };
+        assert_eq!(replaced, expected_tc == &larger_tc);
+        assert_eq!(
+            timeout_manager.highest_timeout_certificate(),
+            Some(expected_tc)
+        );
+        // The chosen certificate is the persisted one
+        assert_eq!(
+            storage
+                .shared_storage
+                .highest_timeout_certificates
+                .lock()
+                .unwrap()
+                .highest_timeout_certificate(),
+            Some(expected_tc)
+        );
+        // The smaller certificate never replaces the larger one
+        assert!(!timeout_manager.update_highest_received_timeout_certificate(&smaller_tc));
+    }
+
+    // The locally formed certificate keeps growing with the timeouts of its round
+    let mut timeout_manager = PacemakerTimeoutManager::new(
+        3,
+        HighestTimeoutCertificates::new(None, None),
+        MockStorage::<TestPayload>::start_for_testing()
+            .0
+            .persistent_liveness_storage(),
+    );
+    timeout_manager.set_tie_break(TimeoutCertificateTieBreak::MostSigners);
+    for signer in &signers[..4] {
+        timeout_manager.update_received_timeout(PacemakerTimeout::new(0, 5, signer, None));
+    }
+    let tc = timeout_manager.highest_timeout_certificate().unwrap();
+    assert_eq!(tc.round(), 5);
+    assert_eq!(tc.timeouts().len(), 4);
+}
@@ -0,0 +1,110 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::{Author, Payload, Round},
+    consensus_types::block::Block,
+    liveness::proposer_election::ProposerElection,
+};
+use crypto::HashValue;
+use std::sync::Arc;
+
+/// Number of rounds scanned ahead for the proposers replacing the excluded ones.
+const EXCLUSION_LOOKAHEAD_ROUNDS: u64 = 100;
+
+/// Source of the liveness scores of the validators (e.g., an external performance oracle).  The
+/// replicas only agree on the proposers if their providers report the same scores, hence the
+/// scores are expected to be derived from data all the replicas share (e.g., committed on chain).
+pub trait LivenessScoreProvider: Send + Sync {
+    /// Returns the current liveness score of the author, the higher the healthier.
+    fn liveness_score(&self, author: Author) -> u64;
+}
+
+/// The performance proposer follows the wrapped election, except for the proposers whose
+/// liveness score is below the threshold: their rounds are proposed by the healthy proposers of
+/// the next rounds instead.  If no healthy proposer is found (e.g., all the validators are below
+/// the threshold), the proposers of the wrapped election are kept.
+pub struct PerformanceProposerElection<T> {
+    proposer_election: Box<dyn ProposerElection<T> + Send + Sync>,
+    score_provider: Arc<dyn LivenessScoreProvider>,
+    // Minimum liveness score of the proposers
+    threshold: u64,
+}
+
+impl<T> PerformanceProposerElection<T> {
+    pub fn new(
+        proposer_election: Box<dyn ProposerElection<T> + Send + Sync>,
+        score_provider: Arc<dyn LivenessScoreProvider>,
+        threshold: u64,
+    ) -> Self {
+        Self {
+            proposer_election,
+            score_provider,
+            threshold,
+        }
+    }
+
+    fn is_healthy(&self, author: Author) -> bool {
+        self.score_provider.liveness_score(author) >= self.threshold
+    }
+
+    /// Returns the healthy proposers of the first round from the given one that has some.  The
+    /// proposers of the wrapped election are kept if none is found.
+    fn get_proposers(&self, round: Round) -> Vec<Author> {
+        for offset in 0..=EXCLUSION_LOOKAHEAD_ROUNDS {
+            let proposers: Vec<Author> = match round.checked_add(offset) {
+                Some(candidate_round) => self
+                    .proposer_election
+                    .get_valid_proposers(candidate_round)
+                    .into_iter()
+                    .filter(|author| self.is_healthy(*author))
+                    .collect(),
+                None => break,
+            };
+            if !proposers.is_empty() {
+                return proposers;
+            }
+        }
+        self.proposer_election.get_valid_proposers(round)
+    }
+}
+
+impl<T: Payload> ProposerElection<T> for PerformanceProposerElection<T> {
+    fn is_valid_proposer(&self, author: Author, round: Round) -> Option<Author> {
+        if self.get_proposers(round).contains(&author) {
+            Some(author)
+        } else {
+            None
+        }
+    }
+
+    fn get_valid_proposers(&self, round: Round) -> Vec<Author> {
+        self.get_proposers(round)
+    }
+
+    fn process_proposal(&self, proposal: Block<T>) -> Option<Block<T>> {
+        let author = proposal.author()?;
+        if self.get_proposers(proposal.round()).contains(&author) {
+            Some(proposal)
+        } else {
+            None
+        }
+    }
+
+    fn validator_set_root(&self) -> HashValue {
+        self.proposer_election.validator_set_root()
+    }
+
+    fn next_change_round(&self, from_round: Round, author: Author) -> Option<Round> {
+        self.proposer_election
+            .next_change_round(from_round, author)?;
+        let mut round = from_round;
+        for _ in 0..=EXCLUSION_LOOKAHEAD_ROUNDS {
+            if self.get_proposers(round) != vec![author] {
+                return Some(round);
+            }
+            round = round.checked_add(1)?;
+        }
+        None
+    }
+}
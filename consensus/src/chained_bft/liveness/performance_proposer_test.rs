@@ -0,0 +1,73 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::{Author, Round},
+    liveness::{
+        performance_proposer_election::{LivenessScoreProvider, PerformanceProposerElection},
+        proposer_election::ProposerElection,
+        rotating_proposer_election::RotatingProposer,
+    },
+};
+use crypto::ed25519::*;
+use std::{collections::HashMap, sync::Arc};
+use types::validator_signer::ValidatorSigner;
+
+/// Scores reported by the oracle, the unknown authors score 0
+struct FixedScores(HashMap<Author, u64>);
+
+impl LivenessScoreProvider for FixedScores {
+    fn liveness_score(&self, author: Author) -> u64 {
+        self.0.get(&author).cloned().unwrap_or(0)
+    }
+}
+
+fn make_election(authors: &[Author], scores: &[u64]) -> PerformanceProposerElection<u32> {
+    let scores = authors
+        .iter()
+        .cloned()
+        .zip(scores.iter().cloned())
+        .collect();
+    PerformanceProposerElection::new(
+        Box::new(RotatingProposer::new(authors.to_vec(), 1)),
+        Arc::new(FixedScores(scores)),
+        50,
+    )
+}
+
+#[test]
+fn test_exclude_low_score_proposer() {
+    let authors: Vec<Author> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]).author())
+        .collect();
+    // The second author leads the rounds 1, 4, 7, ... in the wrapped election
+    let pe = make_election(&authors, &[90, 10, 60]);
+
+    // The rounds of the excluded proposer are proposed by the proposer of the next round
+    assert_eq!(pe.get_valid_proposers(1), vec![authors[2]]);
+    assert_eq!(pe.is_valid_proposer(authors[1], 1), None);
+    assert_eq!(pe.get_valid_proposers(2), vec![authors[2]]);
+    assert_eq!(pe.get_valid_proposers(3), vec![authors[0]]);
+    let history: Vec<(Round, Author)> = pe.proposer_history(1, 31);
+    assert!(history.iter().all(|(_, author)| *author != authors[1]));
+
+    // Another node with the same scores computes the same turns
+    let other_pe = make_election(&authors, &[90, 10, 60]);
+    assert_eq!(other_pe.proposer_history(1, 31), history);
+}
+
+#[test]
+fn test_all_proposers_below_threshold() {
+    let authors: Vec<Author> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]).author())
+        .collect();
+    let pe = make_election(&authors, &[10, 20, 30]);
+    let wrapped_pe: Box<dyn ProposerElection<u32>> =
+        Box::new(RotatingProposer::new(authors.clone(), 1));
+
+    // Everyone is kept
+    assert_eq!(
+        pe.proposer_history(1, 10),
+        wrapped_pe.proposer_history(1, 10)
+    );
+}
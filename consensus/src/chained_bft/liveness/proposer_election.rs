@@ -5,10 +5,33 @@ use crate::chained_bft::{
     common::{Author, Round},
     consensus_types::block::Block,
 };
+use crypto::{
+    hash::{CryptoHasher, ValidatorSetHasher},
+    HashValue,
+};
+use std::ops::Range;
+
+/// Errors of the proposer queries.
+#[derive(Debug, Fail, PartialEq)]
+pub enum ElectionError {
+    /// No proposer is elected for the round (e.g., the validator set of its epoch is empty or the
+    /// election is halted)
+    #[fail(display = "NoProposer for round {}", round)]
+    NoProposer { round: Round },
+    /// The proposer elected for the round is not a member of the validator set
+    #[fail(display = "ProposerNotInValidatorSet {} for round {}", author, round)]
+    ProposerNotInValidatorSet { round: Round, author: Author },
+}
 
 /// ProposerElection incorporates the logic of choosing a leader among multiple candidates.
 /// We are open to a possibility for having multiple proposers per round, the ultimate choice
 /// of a proposal is exposed by the election protocol via the stream of proposals.
+///
+/// The proposer queries (`is_valid_proposer`, `get_valid_proposers`,
+/// `get_valid_proposer_checked`, `proposer_history`, `validate_for_epoch` and
+/// `next_change_round`) are pure: they can be made for any past or future round (e.g., for
+/// display) without affecting the election.  Only `process_proposal` may update the state of the
+/// election.
 pub trait ProposerElection<T> {
     /// If a given author is a valid candidate for being a proposer, generate the info,
     /// otherwise return None.
@@ -19,8 +42,72 @@ pub trait ProposerElection<T> {
     /// used by e.g., voters for choosing the destinations for sending their votes to).
     fn get_valid_proposers(&self, round: Round) -> Vec<Author>;
 
+    /// Return the first valid proposer for a given round, or an error if no proposer is elected
+    /// for the round.
+    fn get_valid_proposer_checked(&self, round: Round) -> Result<Author, ElectionError> {
+        self.get_valid_proposers(round)
+            .first()
+            .cloned()
+            .ok_or(ElectionError::NoProposer { round })
+    }
+
+    /// Return the elected proposer of each round from `start` (included) to `end` (excluded) of
+    /// the current epoch (e.g., for audit or for the validation of backfilled blocks), skipping
+    /// the rounds without a proposer.  The proposers are the ones of the live queries.
+    fn proposer_history(&self, start: Round, end: Round) -> Vec<(Round, Author)> {
+        (start..end)
+            .filter_map(|round| {
+                self.get_valid_proposer_checked(round)
+                    .ok()
+                    .map(|author| (round, author))
+            })
+            .collect()
+    }
+
+    /// Check that a proposer is elected for each of the given rounds, and that all of them are
+    /// members of the given validator set (e.g., for the first rounds of an upcoming epoch, before
+    /// its transition).  The first misconfigured round is returned in the error.
+    fn validate_for_epoch(
+        &self,
+        validator_set: &[Author],
+        rounds: Range<Round>,
+    ) -> Result<(), ElectionError> {
+        for round in rounds {
+            let proposers = self.get_valid_proposers(round);
+            if proposers.is_empty() {
+                return Err(ElectionError::NoProposer { round });
+            }
+            if let Some(author) = proposers
+                .into_iter()
+                .find(|author| !validator_set.contains(author))
+            {
+                return Err(ElectionError::ProposerNotInValidatorSet { round, author });
+            }
+        }
+        Ok(())
+    }
+
     /// Notify proposer election about a new proposal. The function doesn't return any information:
     /// proposer election is going to notify the client about the chosen proposal via a dedicated
     /// channel (to be passed in constructor).
     fn process_proposal(&self, proposal: Block<T>) -> Option<Block<T>>;
+
+    /// Return the root hash of the validator set the election is computed for. Replicas that
+    /// disagree about the validator set may elect different proposers for the same round, comparing
+    /// the roots allows to detect such a mismatch.
+    fn validator_set_root(&self) -> HashValue;
+
+    /// Return the first round starting from `from_round` (included) whose proposer is not the
+    /// given author, or None if the author proposes every round (e.g., a single validator).
+    fn next_change_round(&self, from_round: Round, author: Author) -> Option<Round>;
+}
+
+/// Compute the root hash of an ordered validator set given as pairs of author and weight.
+pub fn compute_validator_set_root(validators: &[(Author, u64)]) -> HashValue {
+    let mut hasher = ValidatorSetHasher::default();
+    for (author, weight) in validators {
+        hasher.write(author.as_ref());
+        hasher.write(&weight.to_le_bytes());
+    }
+    hasher.finish()
 }
@@ -4,8 +4,9 @@
 use crate::chained_bft::{
     common::{Author, Payload, Round},
     consensus_types::block::Block,
-    liveness::proposer_election::ProposerElection,
+    liveness::proposer_election::{compute_validator_set_root, ProposerElection},
 };
+use crypto::HashValue;
 
 /// The rotating proposer maps a round to an author according to a round-robin rotation.
 /// A fixed proposer strategy loses liveness when the fixed proposer is down. Rotating proposers
@@ -16,20 +17,53 @@ pub struct RotatingProposer {
     // Number of contiguous rounds (i.e. round numbers increase by 1) a proposer is active
     // in a row
     contiguous_rounds: u32,
+    // Root hash of the proposers (all with the same weight)
+    validator_set_root: HashValue,
+    // Round the rotation starts from the first proposer at
+    rotation_start_round: Round,
 }
 
 impl RotatingProposer {
-    /// With only one proposer in the vector, it behaves the same as a fixed proposer strategy.
+    /// With only one proposer in the vector, it behaves the same as a fixed proposer strategy:
+    /// in a single validator network the sole validator proposes every round.
     pub fn new(proposers: Vec<Author>, contiguous_rounds: u32) -> Self {
+        assert!(
+            !proposers.is_empty(),
+            "RotatingProposer requires at least one proposer"
+        );
+        assert!(
+            contiguous_rounds > 0,
+            "RotatingProposer requires a positive number of contiguous rounds"
+        );
+        let validators: Vec<(Author, u64)> = proposers.iter().map(|author| (*author, 1)).collect();
         Self {
+            validator_set_root: compute_validator_set_root(&validators),
             proposers,
             contiguous_rounds,
+            rotation_start_round: 0,
+        }
+    }
+
+    /// Returns the election of the next epoch, starting at the given round.  If the validator
+    /// set is unchanged, the rotation continues seamlessly across the epoch boundary, otherwise
+    /// it restarts from the first proposer at the first round of the epoch.
+    #[allow(dead_code)]
+    pub fn next_epoch(&self, proposers: Vec<Author>, epoch_start_round: Round) -> Self {
+        let rotation_start_round = if proposers == self.proposers {
+            self.rotation_start_round
+        } else {
+            epoch_start_round
+        };
+        Self {
+            rotation_start_round,
+            ..Self::new(proposers, self.contiguous_rounds)
         }
     }
 
     fn get_proposer(&self, round: Round) -> Author {
-        self.proposers
-            [((round / u64::from(self.contiguous_rounds)) % self.proposers.len() as u64) as usize]
+        let rotation_round = round.saturating_sub(self.rotation_start_round);
+        self.proposers[((rotation_round / u64::from(self.contiguous_rounds))
+            % self.proposers.len() as u64) as usize]
     }
 }
 
@@ -56,4 +90,24 @@ impl<T: Payload> ProposerElection<T> for RotatingProposer {
             Some(proposal)
         }
     }
+
+    fn validator_set_root(&self) -> HashValue {
+        self.validator_set_root
+    }
+
+    fn next_change_round(&self, from_round: Round, author: Author) -> Option<Round> {
+        if self.proposers.iter().all(|proposer| *proposer == author) {
+            return None;
+        }
+        // The proposer can only change at the first round of a streak of contiguous rounds.
+        let contiguous_rounds = u64::from(self.contiguous_rounds);
+        let mut round = from_round;
+        while self.get_proposer(round) == author {
+            let rotation_round = round.saturating_sub(self.rotation_start_round);
+            round = (rotation_round / contiguous_rounds + 1)
+                .checked_mul(contiguous_rounds)?
+                .checked_add(self.rotation_start_round)?;
+        }
+        Some(round)
+    }
 }
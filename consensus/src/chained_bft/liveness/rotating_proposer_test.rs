@@ -2,10 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::chained_bft::{
+    common::{Author, Round},
     consensus_types::{block::Block, quorum_cert::QuorumCert},
-    liveness::{proposer_election::ProposerElection, rotating_proposer_election::RotatingProposer},
+    liveness::{
+        proposer_election::{ElectionError, ProposerElection},
+        rotating_proposer_election::RotatingProposer,
+    },
 };
-use crypto::ed25519::*;
+use crypto::{ed25519::*, HashValue};
 use std::sync::Arc;
 use types::validator_signer::ValidatorSigner;
 
@@ -126,6 +130,33 @@ fn test_rotating_proposer_with_three_contiguous_rounds() {
     assert_eq!(pe.get_valid_proposers(2), vec![chosen_author]);
 }
 
+#[test]
+fn test_next_change_round_with_three_contiguous_rounds() {
+    let chosen_author = ValidatorSigner::<Ed25519PrivateKey>::random([0u8; 32]).author();
+    let another_author = ValidatorSigner::<Ed25519PrivateKey>::random([1u8; 32]).author();
+    let pe: Arc<dyn ProposerElection<u32>> = Arc::new(RotatingProposer::new(
+        vec![chosen_author, another_author],
+        3,
+    ));
+
+    // The chosen author proposes rounds 0-2 and 6-8, another author proposes rounds 3-5
+    for round in 0..3 {
+        assert_eq!(pe.next_change_round(round, chosen_author), Some(3));
+        assert_eq!(pe.next_change_round(round, another_author), Some(round));
+    }
+    for round in 3..6 {
+        assert_eq!(pe.next_change_round(round, chosen_author), Some(round));
+        assert_eq!(pe.next_change_round(round, another_author), Some(6));
+    }
+    assert_eq!(pe.next_change_round(7, chosen_author), Some(9));
+
+    // A fixed proposer never changes
+    let fixed_pe: Arc<dyn ProposerElection<u32>> =
+        Arc::new(RotatingProposer::new(vec![chosen_author], 3));
+    assert_eq!(fixed_pe.next_change_round(0, chosen_author), None);
+    assert_eq!(fixed_pe.next_change_round(4, another_author), Some(4));
+}
+
 #[test]
 fn test_fixed_proposer() {
     let chosen_validator_signer = ValidatorSigner::<Ed25519PrivateKey>::random([0u8; 32]);
@@ -179,3 +210,103 @@ fn test_fixed_proposer() {
     assert_eq!(pe.is_valid_proposer(another_author, 1), None);
     assert_eq!(pe.get_valid_proposers(1), vec![chosen_author]);
 }
+
+/// Election of an epoch without validators, which never elects a proposer
+struct HaltedElection;
+
+impl ProposerElection<u32> for HaltedElection {
+    fn is_valid_proposer(&self, _author: Author, _round: Round) -> Option<Author> {
+        None
+    }
+
+    fn get_valid_proposers(&self, _round: Round) -> Vec<Author> {
+        vec![]
+    }
+
+    fn process_proposal(&self, _proposal: Block<u32>) -> Option<Block<u32>> {
+        None
+    }
+
+    fn validator_set_root(&self) -> HashValue {
+        HashValue::zero()
+    }
+
+    fn next_change_round(&self, _from_round: Round, _author: Author) -> Option<Round> {
+        None
+    }
+}
+
+#[test]
+fn test_get_valid_proposer_checked() {
+    let chosen_author = ValidatorSigner::<Ed25519PrivateKey>::random([0u8; 32]).author();
+    let another_author = ValidatorSigner::<Ed25519PrivateKey>::random([1u8; 32]).author();
+    let pe: Arc<dyn ProposerElection<u32>> = Arc::new(RotatingProposer::new(
+        vec![chosen_author, another_author],
+        1,
+    ));
+
+    // Querying far away rounds does not affect the proposers of the other rounds
+    assert_eq!(pe.get_valid_proposer_checked(1_000_001), Ok(another_author));
+    assert_eq!(pe.get_valid_proposer_checked(2), Ok(chosen_author));
+    assert_eq!(pe.get_valid_proposer_checked(1), Ok(another_author));
+    assert_eq!(pe.get_valid_proposer_checked(1_000_001), Ok(another_author));
+
+    let halted_pe: Arc<dyn ProposerElection<u32>> = Arc::new(HaltedElection);
+    assert_eq!(
+        halted_pe.get_valid_proposer_checked(3),
+        Err(ElectionError::NoProposer { round: 3 })
+    );
+}
+
+#[test]
+fn test_proposer_history() {
+    let authors: Vec<Author> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]).author())
+        .collect();
+    let pe: Arc<dyn ProposerElection<u32>> = Arc::new(RotatingProposer::new(authors, 2));
+
+    let live: Vec<(Round, Author)> = (1..10)
+        .map(|round| (round, pe.get_valid_proposer_checked(round).unwrap()))
+        .collect();
+    assert_eq!(pe.proposer_history(1, 10), live);
+    // The history is pure: querying it again, or in parts, gives the same proposers
+    let mut parts = pe.proposer_history(5, 10);
+    parts.splice(0..0, pe.proposer_history(1, 5));
+    assert_eq!(parts, live);
+    assert_eq!(pe.proposer_history(1, 10), live);
+
+    let halted_pe: Arc<dyn ProposerElection<u32>> = Arc::new(HaltedElection);
+    assert!(halted_pe.proposer_history(1, 10).is_empty());
+}
+
+#[test]
+fn test_next_epoch_continuity() {
+    let authors: Vec<Author> = (0..4u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]).author())
+        .collect();
+    let proposer = RotatingProposer::new(authors[..3].to_vec(), 2);
+    let pe: &dyn ProposerElection<u32> = &proposer;
+
+    // With the same validator set, the proposers of the new epoch starting at round 9 continue
+    // the rotation of the previous epoch
+    let same_set = proposer.next_epoch(authors[..3].to_vec(), 9);
+    let same_set_pe: &dyn ProposerElection<u32> = &same_set;
+    assert_eq!(
+        same_set_pe.proposer_history(9, 20),
+        pe.proposer_history(9, 20)
+    );
+    assert_eq!(
+        same_set_pe.next_change_round(9, pe.get_valid_proposer_checked(9).unwrap()),
+        pe.next_change_round(9, pe.get_valid_proposer_checked(9).unwrap())
+    );
+
+    // With another validator set, the rotation restarts from its first proposer at round 9
+    let new_set = proposer.next_epoch(authors[1..].to_vec(), 9);
+    let new_set_pe: &dyn ProposerElection<u32> = &new_set;
+    let expected: Vec<(Round, Author)> = (9..15)
+        .map(|round| (round, authors[1 + ((round - 9) / 2 % 3) as usize]))
+        .collect();
+    assert_eq!(new_set_pe.proposer_history(9, 15), expected);
+    assert_eq!(new_set_pe.next_change_round(9, authors[1]), Some(11));
+    assert_eq!(new_set_pe.next_change_round(12, authors[2]), Some(13));
+}
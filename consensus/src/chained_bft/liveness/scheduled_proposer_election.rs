@@ -0,0 +1,98 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::{Author, Payload, Round},
+    consensus_types::block::Block,
+    liveness::proposer_election::{compute_validator_set_root, ProposerElection},
+};
+use crypto::HashValue;
+use failure::prelude::*;
+use std::{fs, path::Path};
+
+/// The scheduled proposer follows a precomputed leader schedule: round r is proposed by the
+/// entry r of the schedule, wrapping around at its end.  This gives full control over the
+/// leadership (e.g., for reproducible testnets or staged rollouts).
+pub struct ScheduledProposerElection {
+    // Proposer of every round index (all honest replicas must agree on this)
+    schedule: Vec<Author>,
+    // Root hash of the schedule entries, in order
+    validator_set_root: HashValue,
+}
+
+impl ScheduledProposerElection {
+    /// The schedule must not be empty.
+    pub fn new(schedule: Vec<Author>) -> Result<Self> {
+        ensure!(
+            !schedule.is_empty(),
+            "ScheduledProposerElection requires a non-empty schedule"
+        );
+        let entries: Vec<(Author, u64)> = schedule.iter().map(|author| (*author, 1)).collect();
+        Ok(Self {
+            validator_set_root: compute_validator_set_root(&entries),
+            schedule,
+        })
+    }
+
+    /// Loads the schedule from a file with one author per line, in the `0x` prefixed hex format
+    /// of its `Display` implementation.  Blank lines are ignored.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let mut schedule = vec![];
+        for line in contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            ensure!(
+                line.starts_with("0x"),
+                "Invalid author {} in the leader schedule",
+                line
+            );
+            schedule.push(Author::from_hex_literal(line)?);
+        }
+        Self::new(schedule)
+    }
+
+    fn get_proposer(&self, round: Round) -> Author {
+        self.schedule[(round % self.schedule.len() as u64) as usize]
+    }
+}
+
+impl<T: Payload> ProposerElection<T> for ScheduledProposerElection {
+    fn is_valid_proposer(&self, author: Author, round: Round) -> Option<Author> {
+        if self.get_proposer(round) == author {
+            Some(author)
+        } else {
+            None
+        }
+    }
+
+    fn get_valid_proposers(&self, round: Round) -> Vec<Author> {
+        vec![self.get_proposer(round)]
+    }
+
+    fn process_proposal(&self, proposal: Block<T>) -> Option<Block<T>> {
+        let round_author = self.get_proposer(proposal.round());
+        if Some(round_author) != proposal.author() {
+            None
+        } else {
+            Some(proposal)
+        }
+    }
+
+    fn validator_set_root(&self) -> HashValue {
+        self.validator_set_root
+    }
+
+    fn next_change_round(&self, from_round: Round, author: Author) -> Option<Round> {
+        if self.schedule.iter().all(|proposer| *proposer == author) {
+            return None;
+        }
+        let mut round = from_round;
+        while self.get_proposer(round) == author {
+            round = round.checked_add(1)?;
+        }
+        Some(round)
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::Author,
+    liveness::{
+        proposer_election::{ElectionError, ProposerElection},
+        scheduled_proposer_election::ScheduledProposerElection,
+    },
+    test_utils::TestPayload,
+};
+use crypto::ed25519::*;
+use std::io::Write;
+use tempfile::NamedTempFile;
+use types::validator_signer::ValidatorSigner;
+
+#[test]
+fn test_scheduled_proposer_from_file() {
+    let authors: Vec<Author> = (0..2u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]).author())
+        .collect();
+    let schedule = vec![authors[0], authors[1], authors[1]];
+    let mut file = NamedTempFile::new().unwrap();
+    for author in &schedule {
+        writeln!(file, "{}", author).unwrap();
+    }
+
+    let pe: &dyn ProposerElection<TestPayload> =
+        &ScheduledProposerElection::from_file(file.path()).unwrap();
+    for round in 0..9 {
+        assert_eq!(
+            pe.get_valid_proposers(round),
+            vec![schedule[round as usize % 3]]
+        );
+        assert_eq!(
+            pe.is_valid_proposer(schedule[round as usize % 3], round),
+            Some(schedule[round as usize % 3])
+        );
+    }
+    assert_eq!(pe.next_change_round(1, authors[1]), Some(3));
+}
+
+#[test]
+fn test_scheduled_proposer_empty_schedule() {
+    assert!(ScheduledProposerElection::new(vec![]).is_err());
+    let file = NamedTempFile::new().unwrap();
+    assert!(ScheduledProposerElection::from_file(file.path()).is_err());
+}
+
+#[test]
+fn test_validate_for_epoch() {
+    let authors: Vec<Author> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]).author())
+        .collect();
+    let validator_set = &authors[..2];
+
+    let pe: &dyn ProposerElection<TestPayload> =
+        &ScheduledProposerElection::new(vec![authors[0], authors[1]]).unwrap();
+    assert_eq!(pe.validate_for_epoch(validator_set, 0..10), Ok(()));
+
+    // The schedule references an author out of the validator set of the epoch
+    let misconfigured_pe: &dyn ProposerElection<TestPayload> =
+        &ScheduledProposerElection::new(vec![authors[0], authors[1], authors[2]]).unwrap();
+    assert_eq!(
+        misconfigured_pe.validate_for_epoch(validator_set, 0..2),
+        Ok(())
+    );
+    assert_eq!(
+        misconfigured_pe.validate_for_epoch(validator_set, 0..10),
+        Err(ElectionError::ProposerNotInValidatorSet {
+            round: 2,
+            author: authors[2],
+        })
+    );
+}
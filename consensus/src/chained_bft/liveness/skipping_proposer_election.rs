@@ -0,0 +1,156 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::{Author, Payload, Round},
+    consensus_types::block::Block,
+    liveness::{pacemaker_observers::RoundTimeoutObserver, proposer_election::ProposerElection},
+    persistent_storage::PersistentLivenessStorage,
+};
+use crypto::HashValue;
+use failure::Result;
+use rmp_serde::{from_slice, to_vec_named};
+use std::{collections::HashMap, sync::Mutex};
+
+/// Number of rounds scanned ahead for the proposers replacing the skipped ones.
+const SKIP_LOOKAHEAD_ROUNDS: u64 = 100;
+
+/// The skipping proposer follows the wrapped election, except for the proposers that led
+/// `failure_threshold` consecutive rounds ending in a timeout: their rounds are proposed by the
+/// proposers of the next rounds instead.  A skipped proposer is reinstated once it proposes again
+/// (for a round it leads in the wrapped election).
+///
+/// The pacemaker reports the rounds ending in a timeout (see
+/// `PacemakerObservers::round_timeout_observer`).
+pub struct SkippingProposerElection<T> {
+    proposer_election: Box<dyn ProposerElection<T> + Send + Sync>,
+    failure_threshold: u64,
+    // Number of consecutive rounds led by the proposers that ended in a timeout
+    failure_counts: Mutex<HashMap<Author, u64>>,
+}
+
+impl<T> SkippingProposerElection<T> {
+    pub fn new(
+        proposer_election: Box<dyn ProposerElection<T> + Send + Sync>,
+        failure_threshold: u64,
+    ) -> Self {
+        assert!(
+            failure_threshold > 0,
+            "SkippingProposerElection requires a positive failure threshold"
+        );
+        Self {
+            proposer_election,
+            failure_threshold,
+            failure_counts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the number of consecutive failed rounds of the proposers, for diagnostics.
+    pub fn failure_counts(&self) -> HashMap<Author, u64> {
+        self.failure_counts.lock().unwrap().clone()
+    }
+
+    /// Persists the failure counts of the proposers to the storage.
+    #[allow(dead_code)]
+    pub fn save_state(&self, storage: &dyn PersistentLivenessStorage) -> Result<()> {
+        let mut failure_counts: Vec<(Author, u64)> = self.failure_counts().into_iter().collect();
+        failure_counts.sort();
+        storage.save_election_state(to_vec_named(&failure_counts)?)
+    }
+
+    /// Replaces the failure counts of the proposers with the ones persisted to the storage, if
+    /// any: the proposers skipped before a restart keep being skipped after it.
+    #[allow(dead_code)]
+    pub fn recover_state(&self, storage: &dyn PersistentLivenessStorage) -> Result<()> {
+        if let Some(bytes) = storage.recover_election_state()? {
+            let failure_counts: Vec<(Author, u64)> = from_slice(&bytes[..])?;
+            *self.failure_counts.lock().unwrap() = failure_counts.into_iter().collect();
+        }
+        Ok(())
+    }
+
+    fn is_skipped(&self, failure_counts: &HashMap<Author, u64>, author: Author) -> bool {
+        failure_counts
+            .get(&author)
+            .map_or(false, |count| *count >= self.failure_threshold)
+    }
+
+    /// Returns the proposers of the first round from the given one whose proposers are not all
+    /// skipped.  The proposers of the wrapped election are kept if every proposer is skipped.
+    fn get_proposers(&self, round: Round) -> Vec<Author> {
+        let failure_counts = self.failure_counts.lock().unwrap();
+        for offset in 0..=SKIP_LOOKAHEAD_ROUNDS {
+            let proposers: Vec<Author> = match round.checked_add(offset) {
+                Some(candidate_round) => self
+                    .proposer_election
+                    .get_valid_proposers(candidate_round)
+                    .into_iter()
+                    .filter(|author| !self.is_skipped(&failure_counts, *author))
+                    .collect(),
+                None => break,
+            };
+            if !proposers.is_empty() {
+                return proposers;
+            }
+        }
+        self.proposer_election.get_valid_proposers(round)
+    }
+}
+
+impl<T: Payload> ProposerElection<T> for SkippingProposerElection<T> {
+    fn is_valid_proposer(&self, author: Author, round: Round) -> Option<Author> {
+        if self.get_proposers(round).contains(&author) {
+            Some(author)
+        } else {
+            None
+        }
+    }
+
+    fn get_valid_proposers(&self, round: Round) -> Vec<Author> {
+        self.get_proposers(round)
+    }
+
+    fn process_proposal(&self, proposal: Block<T>) -> Option<Block<T>> {
+        let author = proposal.author()?;
+        if self
+            .proposer_election
+            .is_valid_proposer(author, proposal.round())
+            .is_some()
+        {
+            // The proposer is alive again, it is reinstated from the next rounds on.
+            self.failure_counts.lock().unwrap().remove(&author);
+        }
+        if self.get_proposers(proposal.round()).contains(&author) {
+            Some(proposal)
+        } else {
+            None
+        }
+    }
+
+    fn validator_set_root(&self) -> HashValue {
+        self.proposer_election.validator_set_root()
+    }
+
+    fn next_change_round(&self, from_round: Round, author: Author) -> Option<Round> {
+        self.proposer_election
+            .next_change_round(from_round, author)?;
+        let mut round = from_round;
+        for _ in 0..=SKIP_LOOKAHEAD_ROUNDS {
+            if self.get_proposers(round) != vec![author] {
+                return Some(round);
+            }
+            round = round.checked_add(1)?;
+        }
+        None
+    }
+}
+
+impl<T: Payload> RoundTimeoutObserver for SkippingProposerElection<T> {
+    fn round_timed_out(&self, round: Round) {
+        let proposers = self.get_proposers(round);
+        let mut failure_counts = self.failure_counts.lock().unwrap();
+        for author in proposers {
+            *failure_counts.entry(author).or_insert(0) += 1;
+        }
+    }
+}
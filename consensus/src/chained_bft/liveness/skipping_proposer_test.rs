@@ -0,0 +1,88 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::Author,
+    consensus_types::{block::Block, quorum_cert::QuorumCert},
+    liveness::{
+        pacemaker_observers::RoundTimeoutObserver, proposer_election::ProposerElection,
+        rotating_proposer_election::RotatingProposer,
+        skipping_proposer_election::SkippingProposerElection,
+    },
+    persistent_storage::PersistentStorage,
+    test_utils::{MockStorage, TestPayload},
+};
+use crypto::ed25519::*;
+use types::validator_signer::ValidatorSigner;
+
+#[test]
+fn test_skip_failing_proposer() {
+    let signers: Vec<ValidatorSigner<Ed25519PrivateKey>> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]))
+        .collect();
+    let authors: Vec<Author> = signers.iter().map(|signer| signer.author()).collect();
+    // The second author leads the rounds 1, 4, 7, 10, ...
+    let pe = SkippingProposerElection::<u32>::new(
+        Box::new(RotatingProposer::new(authors.clone(), 1)),
+        3,
+    );
+
+    for round in &[1, 4] {
+        pe.round_timed_out(*round);
+    }
+    assert_eq!(pe.get_valid_proposers(10), vec![authors[1]]);
+    pe.round_timed_out(7);
+    assert_eq!(pe.failure_counts().get(&authors[1]), Some(&3));
+
+    // The rounds of the failing proposer are proposed by the proposer of the next round
+    assert_eq!(pe.get_valid_proposers(10), vec![authors[2]]);
+    assert_eq!(pe.is_valid_proposer(authors[1], 10), None);
+    assert_eq!(pe.get_valid_proposers(11), vec![authors[2]]);
+    assert_eq!(pe.get_valid_proposers(12), vec![authors[0]]);
+
+    // The proposer is alive again: its proposal is not valid for the round it was skipped at, but
+    // it is reinstated for the next rounds it leads
+    let genesis_block = Block::make_genesis_block();
+    let proposal = Block::make_block(
+        &genesis_block,
+        1,
+        10,
+        1,
+        QuorumCert::certificate_for_genesis(),
+        &signers[1],
+    );
+    assert!(pe.process_proposal(proposal).is_none());
+    assert!(pe.failure_counts().get(&authors[1]).is_none());
+    assert_eq!(pe.get_valid_proposers(13), vec![authors[1]]);
+}
+
+#[test]
+fn test_recover_failure_counts() {
+    let authors: Vec<Author> = (0..3u8)
+        .map(|i| ValidatorSigner::<Ed25519PrivateKey>::random([i; 32]).author())
+        .collect();
+    let make_election = || {
+        SkippingProposerElection::<u32>::new(Box::new(RotatingProposer::new(authors.clone(), 1)), 3)
+    };
+    let storage = MockStorage::<TestPayload>::start_for_testing()
+        .0
+        .persistent_liveness_storage();
+
+    // Nothing persisted yet: the recovered election starts from a clean state
+    let pe = make_election();
+    pe.recover_state(&*storage).unwrap();
+    assert!(pe.failure_counts().is_empty());
+
+    for round in &[1, 4, 7] {
+        pe.round_timed_out(*round);
+    }
+    assert_eq!(pe.get_valid_proposers(10), vec![authors[2]]);
+    pe.save_state(&*storage).unwrap();
+
+    // After a restart, the failing proposer keeps being skipped
+    let recovered_pe = make_election();
+    recovered_pe.recover_state(&*storage).unwrap();
+    assert_eq!(recovered_pe.failure_counts(), pe.failure_counts());
+    assert_eq!(recovered_pe.get_valid_proposers(10), vec![authors[2]]);
+    assert_eq!(recovered_pe.is_valid_proposer(authors[1], 13), None);
+}
@@ -0,0 +1,168 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{common::Author, consensus_types::timeout_msg::PacemakerTimeout};
+use channel;
+use futures::{future, Future, FutureExt, SinkExt};
+use logger::prelude::*;
+use std::{
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+    sync::Arc,
+};
+
+/// Transport of the local timeouts to the other validators, which lets the Pacemaker broadcast
+/// through different transports (e.g., a channel, the network directly or a mock in tests).
+pub trait TimeoutBroadcaster: Send + Sync {
+    /// Broadcasts the timeout, the future is fulfilled once the transport took it over (it does
+    /// not indicate whether the timeout is delivered).
+    fn broadcast(&self, timeout: PacemakerTimeout) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    /// Sends the timeout to the given validators only.  The transports must not fall back to a
+    /// broadcast, which would multiply the traffic of the callers sending a timeout to every group.
+    fn send_to(
+        &self,
+        timeout: PacemakerTimeout,
+        recipients: Vec<Author>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+/// The default TimeoutBroadcaster: the timeouts are sent on a channel, whose receiver forwards
+/// them to the network, and the targeted timeouts on another one along with their recipients.
+#[allow(dead_code)]
+pub struct ChannelTimeoutBroadcaster {
+    sender: channel::Sender<PacemakerTimeout>,
+    targeted_sender: channel::Sender<(PacemakerTimeout, Vec<Author>)>,
+}
+
+#[allow(dead_code)]
+impl ChannelTimeoutBroadcaster {
+    pub fn new(
+        sender: channel::Sender<PacemakerTimeout>,
+        targeted_sender: channel::Sender<(PacemakerTimeout, Vec<Author>)>,
+    ) -> Self {
+        Self {
+            sender,
+            targeted_sender,
+        }
+    }
+}
+
+impl TimeoutBroadcaster for ChannelTimeoutBroadcaster {
+    fn broadcast(&self, timeout: PacemakerTimeout) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let mut sender = self.sender.clone();
+        async move {
+            if let Err(e) = sender.send(timeout).await {
+                debug!("Error in sending the local timeout: {:?}", e);
+            }
+        }
+            .boxed()
+    }
+
+    fn send_to(
+        &self,
+        timeout: PacemakerTimeout,
+        recipients: Vec<Author>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let mut targeted_sender = self.targeted_sender.clone();
+        async move {
+            if let Err(e) = targeted_sender.send((timeout, recipients)).await {
+                debug!("Error in sending the targeted local timeout: {:?}", e);
+            }
+        }
+            .boxed()
+    }
+}
+
+/// TimeoutBroadcaster partitioning the validators into multicast groups, which bounds the cost of
+/// a timeout in large validator sets: a timeout is only sent to the group of its author and to
+/// the first `relays_per_group` members (in the order of the authors) of each other group.  The
+/// relays forward it to the rest of their group (see `relay`).  The forwarded timeouts keep the
+/// signature of their author, so the pacemakers process them as any other remote timeout.
+/// The timeouts of the authors outside of the groups are broadcast.
+#[allow(dead_code)]
+pub struct MulticastTimeoutBroadcaster {
+    inner: Arc<dyn TimeoutBroadcaster>,
+    // Group of each validator
+    author_to_group: HashMap<Author, u64>,
+    // Members of each group, sorted
+    groups: BTreeMap<u64, Vec<Author>>,
+    relays_per_group: usize,
+}
+
+#[allow(dead_code)]
+impl MulticastTimeoutBroadcaster {
+    pub fn new(
+        inner: Arc<dyn TimeoutBroadcaster>,
+        author_to_group: HashMap<Author, u64>,
+        relays_per_group: usize,
+    ) -> Self {
+        let mut groups: BTreeMap<u64, Vec<Author>> = BTreeMap::new();
+        for (author, group) in &author_to_group {
+            groups.entry(*group).or_default().push(*author);
+        }
+        for members in groups.values_mut() {
+            members.sort();
+        }
+        Self {
+            inner,
+            author_to_group,
+            groups,
+            relays_per_group,
+        }
+    }
+
+    /// Returns the validators a timeout of the given author is sent to, none if the author is
+    /// not in any group.
+    pub fn recipients(&self, author: Author) -> Option<Vec<Author>> {
+        let author_group = *self.author_to_group.get(&author)?;
+        let mut recipients = vec![];
+        for (group, members) in &self.groups {
+            if *group == author_group {
+                recipients.extend(members.iter().filter(|member| **member != author));
+            } else {
+                recipients.extend(members.iter().take(self.relays_per_group));
+            }
+        }
+        Some(recipients)
+    }
+
+    /// Forwards the timeout received by the given relay to the rest of the group of the relay,
+    /// when the timeout comes from another group.
+    pub fn relay(
+        &self,
+        timeout: PacemakerTimeout,
+        relay: Author,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let relay_group = match self.author_to_group.get(&relay) {
+            Some(group) => group,
+            None => return future::ready(()).boxed(),
+        };
+        if self.author_to_group.get(&timeout.author()) == Some(relay_group) {
+            return future::ready(()).boxed();
+        }
+        let recipients = self.groups[relay_group]
+            .iter()
+            .filter(|member| **member != relay && **member != timeout.author())
+            .cloned()
+            .collect();
+        self.inner.send_to(timeout, recipients)
+    }
+}
+
+impl TimeoutBroadcaster for MulticastTimeoutBroadcaster {
+    fn broadcast(&self, timeout: PacemakerTimeout) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        match self.recipients(timeout.author()) {
+            Some(recipients) => self.inner.send_to(timeout, recipients),
+            None => self.inner.broadcast(timeout),
+        }
+    }
+
+    fn send_to(
+        &self,
+        timeout: PacemakerTimeout,
+        recipients: Vec<Author>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        self.inner.send_to(timeout, recipients)
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::{Author, Payload, Round},
+    consensus_types::block::Block,
+    liveness::proposer_election::{compute_validator_set_root, ProposerElection},
+};
+use crypto::{
+    hash::{CryptoHasher, ProposerElectionHasher},
+    HashValue,
+};
+use std::{cmp, collections::BTreeMap, sync::Mutex};
+
+// Interval between the rounds whose proposers are checkpointed when the previous proposer is
+// excluded, which bounds the length of the chain computed for a round below the last computed one.
+const CHECKPOINT_INTERVAL: Round = 1_024;
+
+/// The weighted proposer maps a round to an author by a deterministic pseudo-random draw, in
+/// which the chance of an author to be elected is proportional to its weight (e.g., its voting
+/// power).  All honest replicas compute the same draws because they only depend on the round and
+/// on the root of the validator set: replicas that disagree about the validator set compute
+/// unrelated draws.
+///
+/// The draws are mapped onto the authors ordered by address, which breaks the ties between
+/// authors (e.g., of the same weight) identically on all the replicas, whatever the order they
+/// were given the validator set in.
+///
+/// Optionally, the draw of a round is repeated (using the next hash iteration) as long as it
+/// elects the proposer of the previous round, such that no author proposes two rounds in a row.
+/// The proposer of a round then depends on the whole chain of the proposers before it, which is
+/// computed from the closest checkpointed round.
+pub struct WeightedProposer {
+    // Proposers with their weights, ordered by address
+    proposers: Vec<(Author, u64)>,
+    // Sum of the weights of all the proposers
+    total_weight: u64,
+    // Root hash of the proposers with their weights, mixed into every draw
+    validator_set_root: HashValue,
+    // Whether the proposer of the previous round is excluded from the draw of a round
+    exclude_previous_proposer: bool,
+    // The proposer of a round depends on the proposer of the previous round when the previous
+    // proposer is excluded: cache the computed rounds to avoid recomputing the whole chain.
+    chain: Mutex<ProposerChain>,
+}
+
+#[derive(Default)]
+struct ProposerChain {
+    // Proposers of the rounds multiple of CHECKPOINT_INTERVAL computed so far
+    checkpoints: BTreeMap<Round, Author>,
+    // Last computed round and its proposer
+    last_elected: Option<(Round, Author)>,
+}
+
+impl WeightedProposer {
+    /// Authors with a zero weight can never be elected and are ignored.
+    pub fn new(proposers: Vec<(Author, u64)>, exclude_previous_proposer: bool) -> Self {
+        let mut proposers: Vec<(Author, u64)> = proposers
+            .into_iter()
+            .filter(|(_, weight)| *weight > 0)
+            .collect();
+        assert!(
+            !proposers.is_empty(),
+            "WeightedProposer requires at least one proposer with a positive weight"
+        );
+        proposers.sort_by_key(|(author, _)| *author);
+        let total_weight = proposers.iter().map(|(_, weight)| weight).sum();
+        Self {
+            validator_set_root: compute_validator_set_root(&proposers),
+            proposers,
+            total_weight,
+            exclude_previous_proposer,
+            chain: Mutex::new(ProposerChain::default()),
+        }
+    }
+
+    /// Returns the author chosen by the given hash iteration of the draw for the round.
+    fn draw(&self, round: Round, iteration: u64) -> Author {
+        let mut hasher = ProposerElectionHasher::default();
+        hasher.write(&self.validator_set_root.to_vec());
+        hasher.write(&round.to_le_bytes());
+        hasher.write(&iteration.to_le_bytes());
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hasher.finish().to_vec()[..8]);
+        let mut target = u64::from_le_bytes(bytes) % self.total_weight;
+        for (author, weight) in &self.proposers {
+            if target < *weight {
+                return *author;
+            }
+            target -= weight;
+        }
+        unreachable!("The draw target is always smaller than the total weight");
+    }
+
+    /// Returns the first draw for the round that does not elect the excluded author.
+    fn draw_excluding(&self, round: Round, excluded: Option<Author>) -> Author {
+        let mut iteration = 0;
+        loop {
+            let author = self.draw(round, iteration);
+            if Some(author) != excluded {
+                return author;
+            }
+            iteration += 1;
+        }
+    }
+
+    fn get_proposer(&self, round: Round) -> Author {
+        if !self.exclude_previous_proposer || self.proposers.len() == 1 {
+            return self.draw(round, 0);
+        }
+        // Continue the chain from the closest cached round if possible, otherwise start from
+        // round 0.  The chain is computed without holding the lock.
+        let cached = {
+            let chain = self.chain.lock().unwrap();
+            let checkpoint = chain
+                .checkpoints
+                .range(..=round)
+                .next_back()
+                .map(|(cached_round, cached_proposer)| (*cached_round, *cached_proposer));
+            match chain.last_elected {
+                Some(last_elected) if last_elected.0 <= round => {
+                    cmp::max(Some(last_elected), checkpoint)
+                }
+                _ => checkpoint,
+            }
+        };
+        let (mut next_round, mut proposer) = match cached {
+            Some((cached_round, cached_proposer)) if cached_round == round => {
+                return cached_proposer;
+            }
+            Some((cached_round, cached_proposer)) => (cached_round + 1, cached_proposer),
+            None => (0, self.draw(0, 0)),
+        };
+        let mut checkpoints = vec![];
+        if cached.is_none() {
+            checkpoints.push((0, proposer));
+        }
+        while next_round <= round {
+            proposer = self.draw_excluding(next_round, Some(proposer));
+            if next_round % CHECKPOINT_INTERVAL == 0 {
+                checkpoints.push((next_round, proposer));
+            }
+            next_round += 1;
+        }
+        let mut chain = self.chain.lock().unwrap();
+        chain.checkpoints.extend(checkpoints);
+        chain.last_elected = Some((round, proposer));
+        proposer
+    }
+}
+
+impl<T: Payload> ProposerElection<T> for WeightedProposer {
+    fn is_valid_proposer(&self, author: Author, round: Round) -> Option<Author> {
+        if self.get_proposer(round) == author {
+            Some(author)
+        } else {
+            None
+        }
+    }
+
+    fn get_valid_proposers(&self, round: Round) -> Vec<Author> {
+        vec![self.get_proposer(round)]
+    }
+
+    fn process_proposal(&self, proposal: Block<T>) -> Option<Block<T>> {
+        let round_author = self.get_proposer(proposal.round());
+        if Some(round_author) != proposal.author() {
+            None
+        } else {
+            Some(proposal)
+        }
+    }
+
+    fn validator_set_root(&self) -> HashValue {
+        self.validator_set_root
+    }
+
+    fn next_change_round(&self, from_round: Round, author: Author) -> Option<Round> {
+        if self
+            .proposers
+            .iter()
+            .all(|(proposer, _)| *proposer == author)
+        {
+            return None;
+        }
+        let mut round = from_round;
+        while self.get_proposer(round) == author {
+            round = round.checked_add(1)?;
+        }
+        Some(round)
+    }
+}
@@ -3,24 +3,35 @@
 
 use crate::{
     chained_bft::{
-        common::Payload,
+        common::{Payload, Round},
         consensus_types::{block::Block, quorum_cert::QuorumCert},
         consensusdb::ConsensusDB,
-        liveness::pacemaker_timeout_manager::HighestTimeoutCertificates,
+        liveness::{
+            pacemaker_observers::RoundSummary,
+            pacemaker_timeout_manager::HighestTimeoutCertificates,
+        },
         safety::safety_rules::ConsensusState,
     },
     consensus_provider::create_storage_read_client,
+    util::time_service::{ScheduledTask, TimeService},
 };
 use config::config::NodeConfig;
 use crypto::HashValue;
 use failure::Result;
+use futures::{Future, FutureExt};
 use logger::prelude::*;
 use rmp_serde::{from_slice, to_vec_named};
 use std::{
     collections::{HashMap, HashSet},
-    sync::Arc,
+    pin::Pin,
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
 };
 
+#[cfg(test)]
+#[path = "persistent_storage_test.rs"]
+mod persistent_storage_test;
+
 /// Persistent storage for liveness data
 pub trait PersistentLivenessStorage: Send + Sync {
     /// Persist the highest timeout certificate for improved liveness - proof for other replicas
@@ -29,6 +40,219 @@ pub trait PersistentLivenessStorage: Send + Sync {
         &self,
         highest_timeout_certs: HighestTimeoutCertificates,
     ) -> Result<()>;
+
+    /// Persist the current round of the pacemaker.  It is not critical (the round is recovered
+    /// from the certificates after a restart), storages may ignore it.
+    fn save_current_round(&self, _round: Round) -> Result<()> {
+        Ok(())
+    }
+
+    /// Persist the detailed state of an ended round, for the rounds sampled by
+    /// `PacemakerConfig::round_detail_sampling`.  It is not critical, storages may ignore it.
+    fn save_round_details(&self, _summary: RoundSummary) -> Result<()> {
+        Ok(())
+    }
+
+    /// Persist the state of the proposer election, in the encoding of the election (opaque to the
+    /// storage), so elections tracking the history of the proposers survive a restart.  Storages
+    /// may ignore it, the elections then restart from a clean state.
+    fn save_election_state(&self, _state: Vec<u8>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns the last persisted state of the proposer election, if any.
+    fn recover_election_state(&self) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
+
+/// Encoding of the liveness data written to the database, which lets the deployments use a more
+/// compact or a versioned format.
+pub trait LivenessCodec: Send + Sync {
+    fn encode_highest_timeout_certificates(
+        &self,
+        highest_timeout_certs: &HighestTimeoutCertificates,
+    ) -> Result<Vec<u8>>;
+
+    fn decode_highest_timeout_certificates(
+        &self,
+        bytes: &[u8],
+    ) -> Result<HighestTimeoutCertificates>;
+
+    fn encode_round_summary(&self, summary: &RoundSummary) -> Result<Vec<u8>>;
+
+    fn decode_round_summary(&self, bytes: &[u8]) -> Result<RoundSummary>;
+}
+
+/// The MessagePack encoding the rest of the consensus data is stored in as well.
+#[derive(Default)]
+pub struct DefaultLivenessCodec;
+
+impl LivenessCodec for DefaultLivenessCodec {
+    fn encode_highest_timeout_certificates(
+        &self,
+        highest_timeout_certs: &HighestTimeoutCertificates,
+    ) -> Result<Vec<u8>> {
+        Ok(to_vec_named(highest_timeout_certs)?)
+    }
+
+    fn decode_highest_timeout_certificates(
+        &self,
+        bytes: &[u8],
+    ) -> Result<HighestTimeoutCertificates> {
+        Ok(from_slice(bytes)?)
+    }
+
+    fn encode_round_summary(&self, summary: &RoundSummary) -> Result<Vec<u8>> {
+        Ok(to_vec_named(summary)?)
+    }
+
+    fn decode_round_summary(&self, bytes: &[u8]) -> Result<RoundSummary> {
+        Ok(from_slice(bytes)?)
+    }
+}
+
+/// Liveness storage that batches the non-critical writes: the current round is only written to
+/// the wrapped storage once the flush interval elapsed since the last flush, or right before a
+/// critical write.  The highest timeout certificates are always written synchronously.
+/// A buffered round is flushed by a task scheduled on the time service, and on drop, so it is
+/// not lost when no later write happens.
+pub struct BatchingLivenessStorage {
+    state: Arc<BatchingState>,
+}
+
+struct BatchingState {
+    inner: Box<dyn PersistentLivenessStorage>,
+    flush_interval: Duration,
+    time_service: Arc<dyn TimeService>,
+    pending: Mutex<PendingWrites>,
+}
+
+struct PendingWrites {
+    // The round not written yet
+    round: Option<Round>,
+    // Timestamp of the last flush
+    last_flush: Duration,
+    // Whether a flush task is scheduled on the time service
+    flush_scheduled: bool,
+}
+
+impl BatchingLivenessStorage {
+    pub fn new(
+        inner: Box<dyn PersistentLivenessStorage>,
+        flush_interval: Duration,
+        time_service: Arc<dyn TimeService>,
+    ) -> Self {
+        let last_flush = time_service.get_current_timestamp();
+        Self {
+            state: Arc::new(BatchingState {
+                inner,
+                flush_interval,
+                time_service,
+                pending: Mutex::new(PendingWrites {
+                    round: None,
+                    last_flush,
+                    flush_scheduled: false,
+                }),
+            }),
+        }
+    }
+
+    /// Writes the pending updates to the wrapped storage.
+    #[allow(dead_code)]
+    pub fn flush(&self) -> Result<()> {
+        let mut pending = self.state.pending.lock().unwrap();
+        self.state.flush_pending(&mut pending)
+    }
+}
+
+impl BatchingState {
+    fn flush_pending(&self, pending: &mut PendingWrites) -> Result<()> {
+        pending.last_flush = self.time_service.get_current_timestamp();
+        if let Some(round) = pending.round.take() {
+            self.inner.save_current_round(round)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for BatchingLivenessStorage {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            error!(
+                "Failed to flush the pending liveness writes on drop: {:?}",
+                e
+            );
+        }
+    }
+}
+
+/// Flushes the pending round of a batching storage, unless the storage was dropped meanwhile
+struct FlushTask {
+    state: Weak<BatchingState>,
+}
+
+impl ScheduledTask for FlushTask {
+    fn run(&mut self) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        if let Some(state) = self.state.upgrade() {
+            let mut pending = state.pending.lock().unwrap();
+            pending.flush_scheduled = false;
+            if let Err(e) = state.flush_pending(&mut pending) {
+                error!("Failed to flush the pending liveness writes: {:?}", e);
+            }
+        }
+        async {}.boxed()
+    }
+}
+
+impl PersistentLivenessStorage for BatchingLivenessStorage {
+    fn save_highest_timeout_cert(
+        &self,
+        highest_timeout_certs: HighestTimeoutCertificates,
+    ) -> Result<()> {
+        let mut pending = self.state.pending.lock().unwrap();
+        self.state.flush_pending(&mut pending)?;
+        self.state
+            .inner
+            .save_highest_timeout_cert(highest_timeout_certs)
+    }
+
+    fn save_current_round(&self, round: Round) -> Result<()> {
+        let schedule_flush = {
+            let mut pending = self.state.pending.lock().unwrap();
+            pending.round = Some(round);
+            let now = self.state.time_service.get_current_timestamp();
+            if now.checked_sub(pending.last_flush).unwrap_or_default() >= self.state.flush_interval
+            {
+                self.state.flush_pending(&mut pending)?;
+                false
+            } else {
+                !std::mem::replace(&mut pending.flush_scheduled, true)
+            }
+        };
+        // Scheduled without holding the lock, the time service may run the task right away
+        if schedule_flush {
+            self.state.time_service.run_after(
+                self.state.flush_interval,
+                Box::new(FlushTask {
+                    state: Arc::downgrade(&self.state),
+                }),
+            );
+        }
+        Ok(())
+    }
+
+    fn save_round_details(&self, summary: RoundSummary) -> Result<()> {
+        self.state.inner.save_round_details(summary)
+    }
+
+    fn save_election_state(&self, state: Vec<u8>) -> Result<()> {
+        self.state.inner.save_election_state(state)
+    }
+
+    fn recover_election_state(&self) -> Result<Option<Vec<u8>>> {
+        self.state.inner.recover_election_state()
+    }
 }
 
 /// Persistent storage is essential for maintaining safety when a node crashes.  Specifically,
@@ -263,30 +487,68 @@ impl<T: Payload> RecoveryData<T> {
     }
 }
 
-/// The proxy we use to persist data in libra db storage service via grpc.
-pub struct StorageWriteProxy {
+/// The proxy we use to persist data in libra db storage service via grpc.  The liveness data is
+/// encoded by the codec.  The current round of the pacemaker is not persisted, as it is recovered
+/// from the certificates after a restart.
+pub struct StorageWriteProxy<C = DefaultLivenessCodec> {
     db: Arc<ConsensusDB>,
+    codec: Arc<C>,
 }
 
-impl StorageWriteProxy {
+impl<C: LivenessCodec + Default> StorageWriteProxy<C> {
     pub fn new(db: Arc<ConsensusDB>) -> Self {
-        StorageWriteProxy { db }
+        Self::with_codec(db, Arc::new(C::default()))
     }
 }
 
-impl PersistentLivenessStorage for StorageWriteProxy {
+impl<C: LivenessCodec> StorageWriteProxy<C> {
+    pub fn with_codec(db: Arc<ConsensusDB>, codec: Arc<C>) -> Self {
+        StorageWriteProxy { db, codec }
+    }
+
+    /// Returns the details of the last persisted sampled round, if any.
+    #[allow(dead_code)]
+    pub fn round_details(&self) -> Result<Option<RoundSummary>> {
+        match self.db.get_round_details()? {
+            Some(bytes) => Ok(Some(self.codec.decode_round_summary(&bytes[..])?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<C: LivenessCodec + 'static> PersistentLivenessStorage for StorageWriteProxy<C> {
     fn save_highest_timeout_cert(
         &self,
         highest_timeout_certs: HighestTimeoutCertificates,
     ) -> Result<()> {
+        self.db.save_highest_timeout_certificates(
+            self.codec
+                .encode_highest_timeout_certificates(&highest_timeout_certs)?,
+        )
+    }
+
+    fn save_round_details(&self, summary: RoundSummary) -> Result<()> {
         self.db
-            .save_highest_timeout_certificates(to_vec_named(&highest_timeout_certs)?)
+            .save_round_details(self.codec.encode_round_summary(&summary)?)
+    }
+
+    fn save_election_state(&self, state: Vec<u8>) -> Result<()> {
+        self.db.save_election_state(state)
+    }
+
+    fn recover_election_state(&self) -> Result<Option<Vec<u8>>> {
+        self.db.get_election_state()
     }
 }
 
-impl<T: Payload> PersistentStorage<T> for StorageWriteProxy {
+impl<T: Payload, C: LivenessCodec + Default + 'static> PersistentStorage<T>
+    for StorageWriteProxy<C>
+{
     fn persistent_liveness_storage(&self) -> Box<dyn PersistentLivenessStorage> {
-        Box::new(StorageWriteProxy::new(Arc::clone(&self.db)))
+        Box::new(StorageWriteProxy::with_codec(
+            Arc::clone(&self.db),
+            Arc::clone(&self.codec),
+        ))
     }
 
     fn save_tree(&self, blocks: Vec<Block<T>>, quorum_certs: Vec<QuorumCert>) -> Result<()> {
@@ -317,11 +579,15 @@ impl<T: Payload> PersistentStorage<T> for StorageWriteProxy {
             from_slice(&s[..]).expect("unable to deserialize consensus state")
         });
         debug!("Recovered consensus state: {}", consensus_state);
-        let highest_timeout_certificates = initial_data
-            .1
-            .map_or_else(HighestTimeoutCertificates::default, |s| {
-                from_slice(&s[..]).expect("unable to deserialize highest timeout certificates")
-            });
+        let highest_timeout_certificates =
+            initial_data
+                .1
+                .map_or_else(HighestTimeoutCertificates::default, |s| {
+                    proxy
+                        .codec
+                        .decode_highest_timeout_certificates(&s[..])
+                        .expect("unable to deserialize highest timeout certificates")
+                });
         let mut blocks = initial_data.2;
         let mut quorum_certs: Vec<_> = initial_data.3;
         // bootstrap the empty store with genesis block and qc.
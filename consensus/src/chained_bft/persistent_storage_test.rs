@@ -0,0 +1,255 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        common::Round,
+        consensus_types::timeout_msg::{PacemakerTimeout, PacemakerTimeoutCertificate},
+        consensusdb::ConsensusDB,
+        liveness::{
+            pacemaker::NewRoundReason, pacemaker_observers::RoundSummary,
+            pacemaker_timeout_manager::HighestTimeoutCertificates,
+        },
+        persistent_storage::{
+            BatchingLivenessStorage, DefaultLivenessCodec, LivenessCodec,
+            PersistentLivenessStorage, StorageWriteProxy,
+        },
+        test_utils::TestPayload,
+    },
+    util::mock_time_service::SimulatedTimeService,
+};
+use crypto::ed25519::*;
+use failure::prelude::*;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use tempfile::tempdir;
+use types::validator_signer::ValidatorSigner;
+
+#[derive(Debug, PartialEq)]
+enum Write {
+    TimeoutCertificates,
+    CurrentRound(Round),
+}
+
+/// Records the writes it receives, in order
+#[derive(Default)]
+struct RecordingStorage {
+    writes: Arc<Mutex<Vec<Write>>>,
+}
+
+impl PersistentLivenessStorage for RecordingStorage {
+    fn save_highest_timeout_cert(&self, _: HighestTimeoutCertificates) -> Result<()> {
+        self.writes.lock().unwrap().push(Write::TimeoutCertificates);
+        Ok(())
+    }
+
+    fn save_current_round(&self, round: Round) -> Result<()> {
+        self.writes.lock().unwrap().push(Write::CurrentRound(round));
+        Ok(())
+    }
+}
+
+#[test]
+fn test_batching_liveness_storage() {
+    let inner = RecordingStorage::default();
+    let writes = inner.writes.clone();
+    let storage = BatchingLivenessStorage::new(
+        Box::new(inner),
+        Duration::from_secs(3600),
+        Arc::new(SimulatedTimeService::new()),
+    );
+
+    // The round updates are coalesced until the next flush
+    for round in 1..4 {
+        storage.save_current_round(round).unwrap();
+    }
+    assert!(writes.lock().unwrap().is_empty());
+
+    // The timeout certificates are written immediately, after the pending round
+    storage
+        .save_highest_timeout_cert(HighestTimeoutCertificates::default())
+        .unwrap();
+    assert_eq!(
+        *writes.lock().unwrap(),
+        vec![Write::CurrentRound(3), Write::TimeoutCertificates]
+    );
+
+    storage.save_current_round(4).unwrap();
+    storage.flush().unwrap();
+    storage.flush().unwrap();
+    assert_eq!(writes.lock().unwrap().len(), 3);
+    assert_eq!(writes.lock().unwrap()[2], Write::CurrentRound(4));
+
+    // Without an interval, every round update is written through
+    let inner = RecordingStorage::default();
+    let writes = inner.writes.clone();
+    let storage = BatchingLivenessStorage::new(
+        Box::new(inner),
+        Duration::from_millis(0),
+        Arc::new(SimulatedTimeService::new()),
+    );
+    storage.save_current_round(1).unwrap();
+    storage.save_current_round(2).unwrap();
+    assert_eq!(
+        *writes.lock().unwrap(),
+        vec![Write::CurrentRound(1), Write::CurrentRound(2)]
+    );
+}
+
+#[test]
+fn test_batching_liveness_storage_flush_without_later_write() {
+    let inner = RecordingStorage::default();
+    let writes = inner.writes.clone();
+    let mut time_service = SimulatedTimeService::new();
+    let storage = BatchingLivenessStorage::new(
+        Box::new(inner),
+        Duration::from_secs(1),
+        Arc::new(time_service.clone()),
+    );
+
+    // The buffered round is written by the scheduled flush once the interval elapses
+    storage.save_current_round(1).unwrap();
+    storage.save_current_round(2).unwrap();
+    assert!(writes.lock().unwrap().is_empty());
+    time_service.update_auto_advance_limit(Duration::from_secs(1));
+    assert_eq!(*writes.lock().unwrap(), vec![Write::CurrentRound(2)]);
+
+    // The round still buffered is written when the storage is dropped
+    let inner = RecordingStorage::default();
+    let writes = inner.writes.clone();
+    let mut time_service = SimulatedTimeService::new();
+    let storage = BatchingLivenessStorage::new(
+        Box::new(inner),
+        Duration::from_secs(1),
+        Arc::new(time_service.clone()),
+    );
+    storage.save_current_round(3).unwrap();
+    assert!(writes.lock().unwrap().is_empty());
+    drop(storage);
+    assert_eq!(*writes.lock().unwrap(), vec![Write::CurrentRound(3)]);
+
+    // The flush scheduled before the drop has nothing left to do
+    time_service.update_auto_advance_limit(Duration::from_secs(1));
+    assert_eq!(writes.lock().unwrap().len(), 1);
+}
+
+/// Versioned codec: the default encoding behind a version byte
+#[derive(Default)]
+struct VersionedCodec {
+    decoded: AtomicUsize,
+}
+
+const CODEC_VERSION: u8 = 7;
+
+impl VersionedCodec {
+    fn add_version(mut bytes: Vec<u8>) -> Vec<u8> {
+        bytes.insert(0, CODEC_VERSION);
+        bytes
+    }
+
+    fn strip_version<'a>(&self, bytes: &'a [u8]) -> Result<&'a [u8]> {
+        ensure!(
+            bytes.first() == Some(&CODEC_VERSION),
+            "Unknown liveness codec version"
+        );
+        self.decoded.fetch_add(1, Ordering::SeqCst);
+        Ok(&bytes[1..])
+    }
+}
+
+impl LivenessCodec for VersionedCodec {
+    fn encode_highest_timeout_certificates(
+        &self,
+        highest_timeout_certs: &HighestTimeoutCertificates,
+    ) -> Result<Vec<u8>> {
+        DefaultLivenessCodec
+            .encode_highest_timeout_certificates(highest_timeout_certs)
+            .map(Self::add_version)
+    }
+
+    fn decode_highest_timeout_certificates(
+        &self,
+        bytes: &[u8],
+    ) -> Result<HighestTimeoutCertificates> {
+        DefaultLivenessCodec.decode_highest_timeout_certificates(self.strip_version(bytes)?)
+    }
+
+    fn encode_round_summary(&self, summary: &RoundSummary) -> Result<Vec<u8>> {
+        DefaultLivenessCodec
+            .encode_round_summary(summary)
+            .map(Self::add_version)
+    }
+
+    fn decode_round_summary(&self, bytes: &[u8]) -> Result<RoundSummary> {
+        DefaultLivenessCodec.decode_round_summary(self.strip_version(bytes)?)
+    }
+}
+
+#[test]
+fn test_liveness_codec() {
+    let tmp_dir = tempdir().unwrap();
+    let db = Arc::new(ConsensusDB::new(&tmp_dir));
+    let codec = Arc::new(VersionedCodec::default());
+    let storage = StorageWriteProxy::with_codec(Arc::clone(&db), Arc::clone(&codec));
+
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let tc = PacemakerTimeoutCertificate::new(5, vec![PacemakerTimeout::new(0, 5, &signer, None)]);
+    storage
+        .save_highest_timeout_cert(HighestTimeoutCertificates::new(None, Some(tc.clone())))
+        .unwrap();
+
+    // The stored bytes are in the format of the codec
+    let stored_certs = db.get_data::<TestPayload>().unwrap().1.unwrap();
+    assert_eq!(stored_certs[0], CODEC_VERSION);
+    assert!(DefaultLivenessCodec
+        .decode_highest_timeout_certificates(&stored_certs[..])
+        .is_err());
+
+    let recovered_certs = codec
+        .decode_highest_timeout_certificates(&stored_certs[..])
+        .unwrap();
+    assert_eq!(recovered_certs.highest_timeout_certificate(), Some(&tc));
+    assert_eq!(codec.decoded.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_round_details_and_election_state() {
+    let tmp_dir = tempdir().unwrap();
+    let db = Arc::new(ConsensusDB::new(&tmp_dir));
+    let codec = Arc::new(VersionedCodec::default());
+    let storage = StorageWriteProxy::with_codec(Arc::clone(&db), Arc::clone(&codec));
+    assert_eq!(storage.round_details().unwrap(), None);
+    assert_eq!(storage.recover_election_state().unwrap(), None);
+
+    let signer = ValidatorSigner::<Ed25519PrivateKey>::random([0; 32]);
+    let summary = RoundSummary {
+        round: 5,
+        start: Duration::from_millis(100),
+        end: Duration::from_millis(300),
+        reason: NewRoundReason::Timeout {
+            cert: PacemakerTimeoutCertificate::new(
+                5,
+                vec![PacemakerTimeout::new(0, 5, &signer, None)],
+            ),
+            external: false,
+        },
+        retransmit_count: 1,
+        timeout_authors: vec![signer.author()],
+    };
+    storage.save_round_details(summary.clone()).unwrap();
+    storage.save_election_state(vec![1, 2, 3]).unwrap();
+
+    // Both survive a restart, the round details in the format of the codec
+    let storage = StorageWriteProxy::with_codec(Arc::clone(&db), Arc::clone(&codec));
+    assert_eq!(storage.round_details().unwrap(), Some(summary));
+    assert_eq!(codec.decoded.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        storage.recover_election_state().unwrap(),
+        Some(vec![1, 2, 3])
+    );
+}
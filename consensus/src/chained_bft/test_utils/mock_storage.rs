@@ -2,18 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::chained_bft::{
-    common::Payload,
+    common::{Payload, Round},
     consensus_types::{block::Block, quorum_cert::QuorumCert},
-    liveness::pacemaker_timeout_manager::HighestTimeoutCertificates,
+    liveness::{
+        pacemaker_observers::RoundSummary, pacemaker_timeout_manager::HighestTimeoutCertificates,
+    },
     persistent_storage::{PersistentLivenessStorage, PersistentStorage, RecoveryData},
     safety::safety_rules::ConsensusState,
 };
 use config::config::{NodeConfig, NodeConfigHelpers};
 use crypto::HashValue;
-use failure::Result;
+use failure::prelude::*;
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
 pub struct MockSharedStorage<T> {
@@ -24,6 +29,13 @@ pub struct MockSharedStorage<T> {
 
     // Liveness state
     pub highest_timeout_certificates: Mutex<HighestTimeoutCertificates>,
+    pub election_state: Mutex<Option<Vec<u8>>>,
+    pub current_round: Mutex<Option<Round>>,
+    pub round_details: Mutex<Vec<RoundSummary>>,
+    // Whether the writes of the liveness state fail, to simulate the failures of the storage
+    pub fail_liveness_writes: AtomicBool,
+    // Number of the next writes of the liveness state that fail, to simulate transient failures
+    pub fail_next_liveness_writes: Mutex<usize>,
 }
 
 /// A storage that simulates the operations in-memory, used in the tests that cares about storage
@@ -80,6 +92,26 @@ impl<T: Payload> MockStorage<T> {
     pub fn start_for_testing() -> (Arc<Self>, RecoveryData<T>) {
         Self::start(&NodeConfigHelpers::get_single_node_test_config(false))
     }
+
+    fn check_liveness_write(&self) -> Result<()> {
+        ensure!(
+            !self
+                .shared_storage
+                .fail_liveness_writes
+                .load(Ordering::SeqCst),
+            "Simulated failure of the liveness storage"
+        );
+        let mut failures = self
+            .shared_storage
+            .fail_next_liveness_writes
+            .lock()
+            .unwrap();
+        if *failures > 0 {
+            *failures -= 1;
+            bail!("Simulated transient failure of the liveness storage");
+        }
+        Ok(())
+    }
 }
 
 impl<T: Payload> PersistentLivenessStorage for MockStorage<T> {
@@ -87,6 +119,7 @@ impl<T: Payload> PersistentLivenessStorage for MockStorage<T> {
         &self,
         highest_timeout_certificates: HighestTimeoutCertificates,
     ) -> Result<()> {
+        self.check_liveness_write()?;
         *self
             .shared_storage
             .highest_timeout_certificates
@@ -94,6 +127,30 @@ impl<T: Payload> PersistentLivenessStorage for MockStorage<T> {
             .unwrap() = highest_timeout_certificates;
         Ok(())
     }
+
+    fn save_current_round(&self, round: Round) -> Result<()> {
+        *self.shared_storage.current_round.lock().unwrap() = Some(round);
+        Ok(())
+    }
+
+    fn save_round_details(&self, summary: RoundSummary) -> Result<()> {
+        self.shared_storage
+            .round_details
+            .lock()
+            .unwrap()
+            .push(summary);
+        Ok(())
+    }
+
+    fn save_election_state(&self, state: Vec<u8>) -> Result<()> {
+        self.check_liveness_write()?;
+        *self.shared_storage.election_state.lock().unwrap() = Some(state);
+        Ok(())
+    }
+
+    fn recover_election_state(&self) -> Result<Option<Vec<u8>>> {
+        Ok(self.shared_storage.election_state.lock().unwrap().clone())
+    }
 }
 
 // A impl that always start from genesis.
@@ -147,6 +204,11 @@ impl<T: Payload> PersistentStorage<T> for MockStorage<T> {
             qc: Mutex::new(HashMap::new()),
             state: Mutex::new(ConsensusState::default()),
             highest_timeout_certificates: Mutex::new(HighestTimeoutCertificates::new(None, None)),
+            election_state: Mutex::new(None),
+            current_round: Mutex::new(None),
+            round_details: Mutex::new(vec![]),
+            fail_liveness_writes: AtomicBool::new(false),
+            fail_next_liveness_writes: Mutex::new(0),
         });
         let storage = MockStorage {
             shared_storage: Arc::clone(&shared_storage),
@@ -65,6 +65,82 @@ pub static ref ROUND_TIMEOUT_MS: IntGauge = OP_COUNTERS.gauge("round_timeout_ms"
 /// Count the number of cases, in which the vote carried by a timeout message helps to form a QC
 pub static ref TIMEOUT_VOTES_FORM_QC_COUNT: IntCounter = OP_COUNTERS.counter("timeout_votes_form_qc_count");
 
+/// Count of the remote timeouts accepted by the pacemaker.
+pub static ref TIMEOUT_INGEST_ACCEPTED_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_accepted_count");
+
+/// Count of the remote timeouts dropped because of an invalid signature.
+pub static ref TIMEOUT_INGEST_BAD_SIGNATURE_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_bad_signature_count");
+
+/// Count of the remote timeouts dropped because their author is not a validator.
+pub static ref TIMEOUT_INGEST_UNKNOWN_AUTHOR_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_unknown_author_count");
+
+/// Count of the remote timeouts dropped because they are signed for another epoch.
+pub static ref TIMEOUT_INGEST_WRONG_EPOCH_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_wrong_epoch_count");
+
+/// Count of the remote timeouts dropped because their round is already committed.
+pub static ref TIMEOUT_INGEST_COMMITTED_ROUND_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_committed_round_count");
+
+/// Count of the remote timeouts dropped because their round is lower than the current round.
+pub static ref TIMEOUT_INGEST_STALE_ROUND_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_stale_round_count");
+
+/// Count of the timeouts of the local node echoed back (e.g., by gossip) after it left their round.
+pub static ref TIMEOUT_INGEST_SELF_ECHO_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_self_echo_count");
+
+/// Count of the quorum certificates dropped by the pacemaker because they are too far below the
+/// current round.
+pub static ref OLD_QC_DROPPED_COUNT: IntCounter = OP_COUNTERS.counter("old_qc_dropped_count");
+
+/// Count of the timeout certificates received for the round of the highest quorum certificate,
+/// which contradicts it.
+pub static ref CONFLICTING_TIMEOUT_CERTIFICATE_COUNT: IntCounter = OP_COUNTERS.counter("conflicting_timeout_certificate_count");
+
+/// Count of the received timeout certificates proving that an author signed conflicting timeouts
+/// for the same round.
+pub static ref CONFLICTING_TIMEOUT_PROOF_COUNT: IntCounter = OP_COUNTERS.counter("conflicting_timeout_proof_count");
+
+/// Count of the quorum certificates of blocks proposed by authors the proposer election does not
+/// elect for their round.
+pub static ref PROPOSER_MISMATCH_COUNT: IntCounter = OP_COUNTERS.counter("proposer_mismatch_count");
+
+/// Count of the round durations clamped to the sanity bound of the pacemaker.
+pub static ref ROUND_DURATION_CLAMPED_COUNT: IntCounter = OP_COUNTERS.counter("round_duration_clamped_count");
+
+/// Count of the remote timeouts dropped because their round is too far ahead of the current round.
+pub static ref TIMEOUT_INGEST_FUTURE_ROUND_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_future_round_count");
+
+/// Count of the remote timeouts dropped because their author already timed out at the same or a
+/// higher round.
+pub static ref TIMEOUT_INGEST_DUPLICATE_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_duplicate_count");
+
+/// Count of the remote timeouts dropped because the quorum certificate they come with is invalid.
+pub static ref TIMEOUT_INGEST_BAD_QUORUM_CERT_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_bad_quorum_cert_count");
+
+/// Count of the remote timeouts dropped because the quorum certificate they come with is
+/// timestamped too far ahead of the local clock.
+pub static ref TIMEOUT_INGEST_IMPLAUSIBLE_QC_TIMESTAMP_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_implausible_qc_timestamp_count");
+
+/// Count of the verifications of remote timeouts slower than the configured threshold.
+pub static ref SLOW_TIMEOUT_VERIFICATION_COUNT: IntCounter = OP_COUNTERS.counter("slow_timeout_verification_count");
+
+/// Count of the remote timeouts dropped because the timeouts of too many authors are already
+/// buffered for their round.
+pub static ref TIMEOUT_INGEST_TOO_MANY_AUTHORS_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_too_many_authors_count");
+
+/// Count of the remote timeouts rejected because the input budget of the pacemaker is exhausted.
+pub static ref TIMEOUT_INGEST_BUDGET_EXHAUSTED_COUNT: IntCounter = OP_COUNTERS.counter("timeout_ingest_budget_exhausted_count");
+
+/// Histogram of the time between the first received timeout of a timeout certificate and the
+/// timeout reaching its quorum.
+pub static ref TIMEOUT_CERTIFICATE_FORMATION_MS: Histogram = OP_COUNTERS.histogram("timeout_certificate_formation_ms");
+
+/// Histogram of the delay (ms, negative if early) between the expected end of a round and the
+/// local timeout of the round, see `PacemakerConfig::timer_lag_warn_threshold`.
+pub static ref TIMER_LAG_MS: Histogram = OP_COUNTERS.histogram("timer_lag_ms");
+
+/// Count of the received timeouts evicted because more rounds than the cap were accumulating
+/// timeouts (see `PacemakerConfig::max_tracked_timeout_rounds`).
+pub static ref EVICTED_TIMEOUT_COUNT: IntCounter = OP_COUNTERS.counter("evicted_timeout_count");
+
 ////////////////////////
 // SYNCMANAGER COUNTERS
 ////////////////////////
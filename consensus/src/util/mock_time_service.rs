@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::util::time_service::{ScheduledTask, TimeService};
-use futures::{Future, FutureExt};
+use futures::{future, Future, FutureExt};
 use logger::prelude::*;
 use std::{
     pin::Pin,
@@ -53,6 +53,8 @@ impl TimeService for SimulatedTimeService {
             if inner.now > inner.max {
                 inner.now = inner.max;
             }
+            // The task may use the time service as well
+            drop(inner);
             // Perhaps this could be done better, but I think its good enough for tests...
             futures::executor::block_on(t.run());
         }
@@ -129,6 +131,8 @@ impl SimulatedTimeService {
                 i += 1;
             }
         }
+        // The tasks may use the time service as well
+        drop(inner);
         for (_, mut t) in drain {
             // probably could be done better then that, but for now I feel its good enough for tests
             futures::executor::block_on(t.run());
@@ -143,3 +147,22 @@ impl Clone for SimulatedTimeService {
         }
     }
 }
+
+/// ManualTimeService implements TimeService with a clock that is only set by the test through
+/// `now`, and never runs the scheduled tasks.
+#[derive(Default)]
+pub struct ManualTimeService {
+    pub now: Mutex<Duration>,
+}
+
+impl TimeService for ManualTimeService {
+    fn run_after(&self, _timeout: Duration, _task: Box<dyn ScheduledTask>) {}
+
+    fn get_current_timestamp(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep(&self, _t: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        future::ready(()).boxed()
+    }
+}
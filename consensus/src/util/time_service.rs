@@ -5,7 +5,12 @@ use channel;
 use futures::{compat::Future01CompatExt, Future, FutureExt, SinkExt, TryFutureExt};
 use logger::prelude::*;
 use std::{
+    cmp,
     pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use tokio::{executor::Executor, runtime::TaskExecutor, timer::Delay};
@@ -79,6 +84,43 @@ where
     }
 }
 
+/// This task runs the wrapped task unless it is cancelled before it runs
+pub struct CancellableTask {
+    task: Box<dyn ScheduledTask>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellableTask {
+    /// Wraps the given task to Box, the returned handle cancels it
+    pub fn make(task: Box<dyn ScheduledTask>) -> (Box<dyn ScheduledTask>, CancellationHandle) {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let handle = CancellationHandle {
+            cancelled: cancelled.clone(),
+        };
+        (Box::new(CancellableTask { task, cancelled }), handle)
+    }
+}
+
+impl ScheduledTask for CancellableTask {
+    fn run(&mut self) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        if self.cancelled.load(Ordering::SeqCst) {
+            return async {}.boxed();
+        }
+        self.task.run()
+    }
+}
+
+/// Cancels a CancellableTask, cancelling a task that already ran has no effect
+pub struct CancellationHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationHandle {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
 /// TimeService implementation that uses actual clock to schedule tasks
 pub struct ClockTimeService {
     executor: TaskExecutor,
@@ -117,6 +159,113 @@ impl TimeService for ClockTimeService {
     }
 }
 
+/// TimeService that relies on a primary time service, and falls back to a secondary one while the
+/// clock of the primary is implausible: zero, behind its highest earlier reading, or stuck while
+/// the clock of the secondary advances.  Once it has fallen back, the primary is trusted again only
+/// when its clock passes its highest earlier reading.  The tasks and sleeps are scheduled on the
+/// time service currently trusted.
+///
+/// The two clocks are unrelated, hence the timestamps are not read from the trusted clock as is:
+/// they advance with it from the timestamp returned when it was selected, so that they never go
+/// backwards across the switches.
+pub struct FallbackTimeService {
+    primary: Arc<dyn TimeService>,
+    secondary: Arc<dyn TimeService>,
+    state: Mutex<FallbackState>,
+}
+
+#[derive(Default)]
+struct FallbackState {
+    // Highest reading of the primary clock so far
+    highest_primary: Option<Duration>,
+    // Reading of the secondary clock at the last check
+    last_secondary: Option<Duration>,
+    // Whether the secondary time service is used since the last check
+    using_secondary: bool,
+    // Reading of the trusted clock when it was selected and the timestamp returned then
+    anchor: Option<(Duration, Duration)>,
+    // Highest timestamp returned so far
+    last_timestamp: Duration,
+}
+
+impl FallbackTimeService {
+    pub fn new(primary: Arc<dyn TimeService>, secondary: Arc<dyn TimeService>) -> Self {
+        Self {
+            primary,
+            secondary,
+            state: Mutex::new(FallbackState::default()),
+        }
+    }
+
+    /// Reads both clocks and returns the time service to trust along with the current timestamp.
+    fn select(&self) -> (&dyn TimeService, Duration) {
+        let primary_now = self.primary.get_current_timestamp();
+        let secondary_now = self.secondary.get_current_timestamp();
+        let mut state = self.state.lock().unwrap();
+        let implausible = primary_now == Duration::from_millis(0)
+            || match (state.highest_primary, state.last_secondary) {
+                (Some(highest_primary), Some(last_secondary)) => {
+                    primary_now < highest_primary
+                        || (primary_now == highest_primary
+                            && (state.using_secondary || secondary_now > last_secondary))
+                }
+                _ => false,
+            };
+        let switched = implausible != state.using_secondary;
+        if switched {
+            if implausible {
+                warn!(
+                    "Implausible primary clock at {:?}, falling back to the secondary time service",
+                    primary_now
+                );
+            } else {
+                info!(
+                    "Primary clock is plausible again, no longer using the secondary time service"
+                );
+            }
+            state.using_secondary = implausible;
+        }
+        state.highest_primary = Some(match state.highest_primary {
+            Some(highest_primary) => cmp::max(highest_primary, primary_now),
+            None => primary_now,
+        });
+        state.last_secondary = Some(secondary_now);
+        let (time_service, now) = if implausible {
+            (self.secondary.as_ref(), secondary_now)
+        } else {
+            (self.primary.as_ref(), primary_now)
+        };
+        let (anchor_now, anchor_timestamp) = match state.anchor {
+            Some(anchor) if !switched => anchor,
+            _ => {
+                let anchor = (now, cmp::max(now, state.last_timestamp));
+                state.anchor = Some(anchor);
+                anchor
+            }
+        };
+        let elapsed = now
+            .checked_sub(anchor_now)
+            .unwrap_or_else(|| Duration::from_millis(0));
+        let timestamp = cmp::max(anchor_timestamp + elapsed, state.last_timestamp);
+        state.last_timestamp = timestamp;
+        (time_service, timestamp)
+    }
+}
+
+impl TimeService for FallbackTimeService {
+    fn run_after(&self, timeout: Duration, task: Box<dyn ScheduledTask>) {
+        self.select().0.run_after(timeout, task)
+    }
+
+    fn get_current_timestamp(&self) -> Duration {
+        self.select().1
+    }
+
+    fn sleep(&self, t: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        self.select().0.sleep(t)
+    }
+}
+
 /// Return the duration since the UNIX_EPOCH
 pub fn duration_since_epoch() -> Duration {
     SystemTime::now()
@@ -1,9 +1,14 @@
 use crate::util::{
-    mock_time_service::SimulatedTimeService,
-    time_service::{wait_if_possible, TimeService, WaitingError, WaitingSuccess},
+    mock_time_service::{ManualTimeService, SimulatedTimeService},
+    time_service::{
+        wait_if_possible, FallbackTimeService, TimeService, WaitingError, WaitingSuccess,
+    },
 };
 use futures::executor::block_on;
-use std::time::{Duration, Instant};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 #[test]
 fn wait_if_possible_test_waiting_required() {
@@ -79,3 +84,57 @@ fn wait_if_possible_test_sleep_failed() {
         }
     );
 }
+
+#[test]
+fn fallback_time_service_test() {
+    let primary = Arc::new(ManualTimeService::default());
+    let secondary = Arc::new(ManualTimeService::default());
+    let set = |time_service: &ManualTimeService, secs| {
+        *time_service.now.lock().unwrap() = Duration::from_secs(secs)
+    };
+    set(&primary, 1_000);
+    set(&secondary, 10);
+    let time_service = FallbackTimeService::new(primary.clone(), secondary.clone());
+    assert_eq!(
+        time_service.get_current_timestamp(),
+        Duration::from_secs(1_000)
+    );
+
+    // The primary clock goes backwards: the timestamps advance with the secondary clock from the
+    // last one instead of jumping to the unrelated reading of the secondary
+    set(&primary, 500);
+    set(&secondary, 12);
+    assert_eq!(
+        time_service.get_current_timestamp(),
+        Duration::from_secs(1_000)
+    );
+    set(&primary, 501);
+    set(&secondary, 15);
+    assert_eq!(
+        time_service.get_current_timestamp(),
+        Duration::from_secs(1_003)
+    );
+
+    // The primary clock advances again but is still behind its highest reading
+    set(&primary, 1_000);
+    set(&secondary, 16);
+    assert_eq!(
+        time_service.get_current_timestamp(),
+        Duration::from_secs(1_004)
+    );
+
+    // The primary clock passes its highest reading: it is trusted again, and the timestamps do not
+    // go backwards
+    set(&primary, 1_001);
+    set(&secondary, 17);
+    assert_eq!(
+        time_service.get_current_timestamp(),
+        Duration::from_secs(1_004)
+    );
+    set(&primary, 1_003);
+    set(&secondary, 17);
+    assert_eq!(
+        time_service.get_current_timestamp(),
+        Duration::from_secs(1_006)
+    );
+}
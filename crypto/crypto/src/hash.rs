@@ -536,6 +536,29 @@ define_hasher! {
     (VoteMsgHasher, VOTE_MSG_HASHER, b"VoteMsg")
 }
 
+define_hasher! {
+    /// The hasher used to compute the pseudo-random draws of a proposer election.
+    (
+        ProposerElectionHasher,
+        PROPOSER_ELECTION_HASHER,
+        b"ProposerElection"
+    )
+}
+
+define_hasher! {
+    /// The hasher used to compute the root of the validator set a proposer election is bound to.
+    (ValidatorSetHasher, VALIDATOR_SET_HASHER, b"ValidatorSet")
+}
+
+define_hasher! {
+    /// The hasher used to compute the deterministic jitter of the pacemaker round timeouts.
+    (
+        PacemakerJitterHasher,
+        PACEMAKER_JITTER_HASHER,
+        b"PacemakerJitter"
+    )
+}
+
 define_hasher! {
     /// The hasher used to compute the hash of a ContractEvent object.
     (ContractEventHasher, CONTRACT_EVENT_HASHER, b"ContractEvent")